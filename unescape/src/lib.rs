@@ -36,6 +36,9 @@ pub fn unescape(s: &str) -> Option<String> {
             Some('\'') => s.push('\''),
             Some('\"') => s.push('\"'),
             Some('\\') => s.push('\\'),
+            // braces are escaped in ZenLang interpolated strings
+            Some('{') => s.push('{'),
+            Some('}') => s.push('}'),
             Some('u') => s.push(try_option!(unescape_unicode(&mut queue))),
             Some('x') => s.push(try_option!(unescape_byte(&mut queue))),
             Some(c) if c.is_digit(8) => s.push(try_option!(unescape_octal(c, &mut queue))),
@@ -1,6 +1,8 @@
 pub struct ArgParser {
     pub filename: String,
     pub compile: bool,
+    pub strip: bool,
+    pub optimize: bool,
 }
 
 impl ArgParser {
@@ -8,6 +10,8 @@ impl ArgParser {
         return Self {
             filename: String::new(),
             compile: false,
+            strip: false,
+            optimize: false,
         };
     }
 
@@ -15,6 +19,10 @@ impl ArgParser {
         for arg in args {
             if arg == "-compile" {
                 self.compile = true;
+            } else if arg == "-strip" {
+                self.strip = true;
+            } else if arg == "-optimize" {
+                self.optimize = true;
             } else {
                 self.filename = arg.to_string();
             }
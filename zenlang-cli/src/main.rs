@@ -34,7 +34,7 @@ fn main() {
                         println!("read error: {}", error);
                         return;
                     }
-                    runner::run_code(text, module_name);
+                    runner::run_code(text, module_name, args.filename, args.optimize);
                 } else if args.filename.ends_with(".zenc") {
                     let bytes: Vec<u8>;
                     match fs::read(args.filename) {
@@ -55,7 +55,14 @@ fn main() {
                     return;
                 }
                 let filename = format!("{}.zenc", module_name);
-                runner::compile_code(text, module_name, filename);
+                runner::compile_code(
+                    text,
+                    module_name,
+                    args.filename,
+                    filename,
+                    args.strip,
+                    args.optimize,
+                );
             }
         }
         Err(e) => {
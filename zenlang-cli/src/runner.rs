@@ -1,5 +1,5 @@
 use std::fs;
-use zenlang::{compiler, module, parser, tokenizer, vm};
+use zenlang::{compiler, module, optimizer, parser, tokenizer, vm};
 use zenlang_platform_std::*;
 
 fn run_vm(vm: &mut vm::VM) {
@@ -9,17 +9,21 @@ fn run_vm(vm: &mut vm::VM) {
     }
     // println!("{:?}", vm.modules);
 
-    vm.run_until_halt();
-
-    if !vm.error.is_empty() {
-        let pc = vm.pc;
-
+    if let vm::StopReason::Error(error) = vm.run_until_halt() {
         println!("\n-- begin runtime error --");
-        println!("{}", vm.error);
-        if let Some(name) = vm.get_function_name_from_pc(&pc) {
-            println!("runtime error in function {}", name);
+        println!("{} ({:?})", error, error.kind);
+        println!("stack backtrace:");
+        for (index, frame) in vm.error_backtrace(&error).iter().enumerate() {
+            println!("{:>4}: {}", index, frame);
+            if let Some(location) = frame.location {
+                println!(
+                    "             at {}:{}:{}",
+                    frame.filename.as_deref().unwrap_or("<unknown>"),
+                    location.line,
+                    location.column
+                );
+            }
         }
-        println!("runtime error at pc = {}", pc);
         println!("-- end runtime error --");
         return;
     }
@@ -33,10 +37,19 @@ fn run_vm(vm: &mut vm::VM) {
     }
 }
 
-pub fn run_code(code: String, module_name: String) {
+fn get_optimization_level(optimize: bool) -> optimizer::OptimizationLevel {
+    if optimize {
+        return optimizer::OptimizationLevel::Full;
+    }
+    return optimizer::OptimizationLevel::None;
+}
+
+pub fn run_code(code: String, module_name: String, filename: String, optimize: bool) {
     let mut tokenizer = tokenizer::Tokenizer::new(code);
+    tokenizer.filename = filename;
     let mut parser = parser::Parser::new(&mut tokenizer);
     let mut compiler = compiler::Compiler::new(&mut parser);
+    compiler.optimization_level = get_optimization_level(optimize);
 
     if let Err(e) = compiler.compile() {
         println!("compile error: {}", e);
@@ -64,10 +77,19 @@ pub fn run_code(code: String, module_name: String) {
     run_vm(&mut vm);
 }
 
-pub fn compile_code(code: String, module_name: String, out_filename: String) {
+pub fn compile_code(
+    code: String,
+    module_name: String,
+    filename: String,
+    out_filename: String,
+    strip: bool,
+    optimize: bool,
+) {
     let mut tokenizer = tokenizer::Tokenizer::new(code);
+    tokenizer.filename = filename;
     let mut parser = parser::Parser::new(&mut tokenizer);
     let mut compiler = compiler::Compiler::new(&mut parser);
+    compiler.optimization_level = get_optimization_level(optimize);
 
     if let Err(e) = compiler.compile() {
         println!("compile error: {}", e);
@@ -83,6 +105,9 @@ pub fn compile_code(code: String, module_name: String, out_filename: String) {
 
     let module = compiler.get_module();
     module.name = module_name;
+    if strip {
+        module.strip_debug_info();
+    }
     match module.compile() {
         Err(e) => {
             println!("module compile error: {}", e);
@@ -0,0 +1,74 @@
+//! zenlang-derive
+//!
+//! Derive macros for `zenlang::interop::ToValue` and `zenlang::interop::FromValue`.
+//! Structs with named fields are converted to dictionaries with a `_typename` field
+#![allow(clippy::needless_return)]
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DataStruct, DeriveInput, Fields, parse_macro_input};
+
+fn named_fields(input: &DeriveInput) -> Result<Vec<syn::Ident>, syn::Error> {
+    if let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    {
+        return Ok(fields
+            .named
+            .iter()
+            .map(|field| field.ident.clone().unwrap())
+            .collect());
+    }
+    return Err(syn::Error::new_spanned(
+        &input.ident,
+        "only structs with named fields are supported",
+    ));
+}
+
+#[proc_macro_derive(ToValue)]
+pub fn derive_to_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let fields = match named_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let typename = name.to_string();
+    let keys = fields.iter().map(|field| field.to_string());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::zenlang::interop::ToValue for #name #ty_generics #where_clause {
+            fn to_value(self) -> ::zenlang::value::Value {
+                return ::zenlang::interop::make_dictionary([
+                    ("_typename", ::zenlang::interop::ToValue::to_value(#typename)),
+                    #((#keys, ::zenlang::interop::ToValue::to_value(self.#fields)),)*
+                ]);
+            }
+        }
+    };
+    return expanded.into();
+}
+
+#[proc_macro_derive(FromValue)]
+pub fn derive_from_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let fields = match named_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let keys = fields.iter().map(|field| field.to_string());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::zenlang::interop::FromValue for #name #ty_generics #where_clause {
+            fn from_value(value: &::zenlang::value::Value) -> ::core::result::Result<Self, ::zenlang::interop::ConversionError> {
+                return ::core::result::Result::Ok(Self {
+                    #(#fields: ::zenlang::interop::get_field(value, #keys)?,)*
+                });
+            }
+        }
+    };
+    return expanded.into();
+}
@@ -48,6 +48,7 @@ impl App {
         }
 
         let mut tokenizer = Tokenizer::new(code);
+        tokenizer.filename = path.to_string_lossy().to_string();
         let mut parser = Parser::new(&mut tokenizer);
         let mut compiler = Compiler::new(&mut parser);
 
@@ -33,8 +33,7 @@ impl App {
 
                             // Opcode label
                             let current = self.vm.pc == addr_pc;
-                            let current_error =
-                                addr_pc == self.error_pc && !self.vm.error.is_empty();
+                            let current_error = addr_pc == self.error_pc && self.vm.error.is_some();
 
                             let mut opcode_color = Color32::from_rgb(255, 255, 255);
                             if current {
@@ -69,6 +68,24 @@ impl App {
                                 columns[1].label(format!("{}", function.name));
                             }
                         }
+
+                        // Source line label, shown where the line changes
+                        if let Some(location) = module.get_source_location(addr) {
+                            let previous = match addr {
+                                0 => None,
+                                _ => module.get_source_location(addr - 1),
+                            };
+                            if previous.map(|p| p.line) != Some(location.line) {
+                                columns[1].label(
+                                    RichText::new(format!(
+                                        "{}:{}",
+                                        module.get_source_filename().unwrap_or("<unknown>"),
+                                        location.line
+                                    ))
+                                    .weak(),
+                                );
+                            }
+                        }
                     })
                 }
             });
@@ -1,28 +1,24 @@
 use crate::app::App;
 use eframe::egui::{self, Panel};
 use egui_extras::{Column, TableBuilder};
-use zenlang::vm::ProgramCounter;
+use zenlang::vm::{ProgramCounter, StopReason};
 
 impl App {
     pub(crate) fn draw_vm_panel(&mut self, ui: &mut egui::Ui) {
         Panel::right("vm").resizable(true).show_inside(ui, |ui| {
             ui.horizontal(|ui| {
                 // Control panel
-                if ui.button("step").clicked() && self.vm.error.is_empty() {
-                    self.vm.step();
-                    if !self.vm.error.is_empty() {
-                        self.error_pc = self.vm.pc;
-                        self.error_pc.inst -= 1;
+                if ui.button("step").clicked() && self.vm.error.is_none() {
+                    if let Some(StopReason::Error(error)) = self.vm.step() {
+                        self.error_pc = error.pc;
                     }
                     self.view_module_id = self.vm.pc.module;
                     self.want_scroll = true;
                 }
 
-                if ui.button("step until stopped").clicked() && self.vm.error.is_empty() {
-                    while self.vm.step().is_none() {}
-                    if !self.vm.error.is_empty() {
-                        self.error_pc = self.vm.pc;
-                        self.error_pc.inst -= 1;
+                if ui.button("step until stopped").clicked() && self.vm.error.is_none() {
+                    if let StopReason::Error(error) = self.vm.run_until_halt() {
+                        self.error_pc = error.pc;
                     }
                     self.view_module_id = self.vm.pc.module;
                     self.want_scroll = true;
@@ -40,7 +36,9 @@ impl App {
                     ui.label("halted");
                 }
 
-                ui.label(format!("error: {}", self.vm.error));
+                if let Some(error) = &self.vm.error {
+                    ui.label(format!("error ({:?}): {}", error.kind, error));
+                }
             });
 
             // Breakpoints
@@ -63,24 +61,17 @@ impl App {
             ui.strong(format!("Return register: {}", self.vm.ret));
 
             ui.strong("Call stack (most recent call first):");
-            for (index, pc) in self.vm.call_stack.iter().enumerate().rev() {
-                let mut module_name = "<unknown module>";
-                let mut function_name = "<unknown function>";
-                for (module_index, module) in self.vm.modules.iter().enumerate() {
-                    if module_index == pc.module {
-                        module_name = module.name.as_str();
-
-                        for func in module.functions.iter() {
-                            if pc.inst >= func.addr {
-                                function_name = func.name.as_str();
-                            }
-                        }
-                    }
+            let frames = self.vm.backtrace();
+            for (index, frame) in frames.iter().enumerate().skip(1) {
+                let mut line = String::new();
+                if let Some(location) = frame.location {
+                    line = format!(
+                        " at {}:{}",
+                        frame.filename.as_deref().unwrap_or("<unknown>"),
+                        location.line
+                    );
                 }
-                ui.label(format!(
-                    "{}. {}:{} {}",
-                    index, module_name, function_name, pc
-                ));
+                ui.label(format!("{}. {}{}", frames.len() - index - 1, frame, line));
             }
 
             ui.strong("Stack (most recent value first):");
@@ -121,6 +112,26 @@ impl App {
                                 });
                             });
                         }
+                        for (slot, value) in scope.slots.iter().enumerate() {
+                            body.row(18.0, |mut row| {
+                                row.col(|ui| {
+                                    ui.strong(format!("slot {}", slot));
+                                });
+                                row.col(|ui| {
+                                    ui.strong(format!("{}", value));
+                                });
+                            });
+                        }
+                        for cell in scope.cells.iter() {
+                            body.row(18.0, |mut row| {
+                                row.col(|ui| {
+                                    ui.strong(format!("{} (captured)", cell.0));
+                                });
+                                row.col(|ui| {
+                                    ui.strong(format!("{}", cell.1.borrow()));
+                                });
+                            });
+                        }
                     }
                 });
         });
@@ -23,10 +23,7 @@ impl AstArrayAssign {
 
 impl Compile for AstArrayAssign {
     fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
-        {
-            let module = compiler.get_module();
-            module.opcodes.push(Opcode::LoadVar(self.name.clone()));
-        }
+        compiler.load_var(&self.name);
 
         let len = self.indexes.len();
         for (i, index) in self.indexes.iter_mut().enumerate() {
@@ -4,12 +4,15 @@ use crate::{ast::node::Compile, opcode::Opcode};
 use alloc::boxed::*;
 use alloc::string::String;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum AstBinopOp {
     PLUS,
     MINUS,
     MUL,
     DIV,
+    MOD,
+    INTDIV,
+    POW,
     EQ,
     NEQ,
     LT,
@@ -20,6 +23,7 @@ pub enum AstBinopOp {
     BITSHL,
     BITAND,
     BITOR,
+    BITXOR,
 }
 
 #[derive(Debug)]
@@ -61,6 +65,15 @@ impl Compile for AstBinop {
             AstBinopOp::DIV => {
                 opcode = Opcode::Div();
             }
+            AstBinopOp::MOD => {
+                opcode = Opcode::Mod();
+            }
+            AstBinopOp::INTDIV => {
+                opcode = Opcode::IntDiv();
+            }
+            AstBinopOp::POW => {
+                opcode = Opcode::Pow();
+            }
             AstBinopOp::EQ => {
                 opcode = Opcode::Eq();
             }
@@ -83,6 +96,7 @@ impl Compile for AstBinop {
             AstBinopOp::BITSHL => opcode = Opcode::Bshl(),
             AstBinopOp::BITAND => opcode = Opcode::Band(),
             AstBinopOp::BITOR => opcode = Opcode::Bor(),
+            AstBinopOp::BITXOR => opcode = Opcode::Bxor(),
         }
 
         let module = compiler.get_module();
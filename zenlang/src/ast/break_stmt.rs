@@ -15,6 +15,8 @@ impl AstBreak {
 
 impl Compile for AstBreak {
     fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        compiler.end_loop_try_blocks();
+
         let addr: usize;
         let module = compiler.get_module();
         addr = module.opcodes.len();
@@ -15,6 +15,8 @@ impl AstContinue {
 
 impl Compile for AstContinue {
     fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        compiler.end_loop_try_blocks();
+
         let addr: usize;
         {
             let module = compiler.get_module();
@@ -0,0 +1,79 @@
+use crate::ast::node::Compile;
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+use alloc::boxed::*;
+use alloc::string::String;
+use alloc::vec::*;
+
+/// Names a destructured value is stored in
+#[derive(Debug)]
+pub enum AstBindingPattern {
+    Name(String),
+    /// `[a, b, ...rest]`, missing elements are null
+    Array(Vec<AstBindingPattern>, Option<String>),
+    /// `{"key" = name}`, missing keys are null
+    Dictionary(Vec<(String, AstBindingPattern)>),
+}
+
+impl AstBindingPattern {
+    /// Store the value on stack in the names of the pattern
+    pub(crate) fn compile_store(&self, compiler: &mut Compiler) {
+        match self {
+            AstBindingPattern::Name(name) => {
+                compiler.store_var(name);
+            }
+            AstBindingPattern::Array(items, rest) => {
+                compiler
+                    .get_module()
+                    .opcodes
+                    .push(Opcode::UnpackArray(items.len() as u64, rest.is_some()));
+
+                // the last element is on top of the stack
+                if let Some(rest) = rest {
+                    compiler.store_var(rest);
+                }
+                for item in items.iter().rev() {
+                    item.compile_store(compiler);
+                }
+            }
+            AstBindingPattern::Dictionary(fields) => {
+                let keys = fields.iter().map(|(key, _)| key.clone()).collect();
+                compiler.get_module().opcodes.push(Opcode::UnpackDict(keys));
+
+                for (_, field) in fields.iter().rev() {
+                    field.compile_store(compiler);
+                }
+            }
+        }
+    }
+}
+
+/// Destructuring `let`
+#[derive(Debug)]
+pub struct AstDestructure {
+    pub pattern: AstBindingPattern,
+    pub expr: Option<Box<dyn Compile>>,
+}
+
+impl AstDestructure {
+    pub fn new(pattern: AstBindingPattern) -> Self {
+        return Self {
+            pattern: pattern,
+            expr: None,
+        };
+    }
+}
+
+impl Compile for AstDestructure {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        if let Some(expr) = &mut self.expr {
+            expr.compile(compiler)?;
+        } else {
+            return Err("expr is None".into());
+        }
+
+        self.pattern.compile_store(compiler);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::ast::block::AstBlock;
+use crate::compiler::Compiler;
+use crate::{ast::node::Compile, opcode::*};
+use alloc::boxed::*;
+use alloc::format;
+use alloc::string::String;
+
+#[derive(Debug)]
+pub struct AstForStmt {
+    /// Name of the variable the key or the index is stored in, if there are two variables
+    pub key_name: Option<String>,
+    pub value_name: String,
+    pub value: Option<Box<dyn Compile>>,
+    pub body: AstBlock,
+}
+
+impl AstForStmt {
+    pub fn new() -> Self {
+        return Self {
+            key_name: None,
+            value_name: String::new(),
+            value: None,
+            body: AstBlock::new(),
+        };
+    }
+}
+
+impl Compile for AstForStmt {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        // We compile it to this:
+        /*
+           ... (value)
+           IterBegin()
+           StoreVar(iterator)
+        1: LoadVar(iterator)
+           IterNext("2", has_key)
+           StoreVar(key_name) (if there is a key)
+           StoreVar(value_name)
+           ... (body)
+           Branch("1")
+        2: the rest of the code
+        */
+        if let Some(value) = &mut self.value {
+            value.compile(compiler)?;
+        } else {
+            return Err("self.value is None".into());
+        }
+
+        // the iterator is kept in a hidden variable, so break, return and throw don't leave it on stack
+        let iterator_name = format!("#for{}", compiler.while_stmts_break_indexes.len());
+        compiler.get_module().opcodes.push(Opcode::IterBegin());
+        compiler.store_var(&iterator_name);
+
+        let next_addr = compiler.get_module().opcodes.len();
+        compiler.load_var(&iterator_name);
+
+        let next_opcode_index;
+        {
+            let module = compiler.get_module();
+            next_opcode_index = module.opcodes.len();
+            module
+                .opcodes
+                .push(Opcode::IterNext(0, self.key_name.is_some()));
+        }
+        if let Some(key_name) = &self.key_name {
+            compiler.store_var(key_name);
+        }
+        compiler.store_var(&self.value_name);
+
+        // * compile body
+        compiler.begin_loop();
+        self.body.compile(compiler)?;
+
+        let exit_addr;
+        {
+            let module = compiler.get_module();
+            module.opcodes.push(Opcode::Branch(next_addr));
+
+            exit_addr = module.opcodes.len();
+            if let Opcode::IterNext(addr, _) = &mut module.opcodes[next_opcode_index] {
+                *addr = exit_addr;
+            }
+        }
+
+        // break and continue statements
+        compiler.end_loop(next_addr, exit_addr);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::ast::node::Compile;
+use crate::ast::node::StatementExpression;
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+use alloc::boxed::*;
+use alloc::string::String;
+use alloc::vec::*;
+
+/// Interpolated string, its parts are converted to strings and concatenated
+#[derive(Debug)]
+pub struct AstFormatString {
+    pub parts: Vec<Box<dyn Compile>>,
+    do_push: bool,
+}
+
+impl AstFormatString {
+    pub fn new() -> Self {
+        return Self {
+            parts: Vec::new(),
+            do_push: true,
+        };
+    }
+}
+
+impl Compile for AstFormatString {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        // the embedded expressions may have side effects, so they are compiled even if unused
+        for part in self.parts.iter_mut() {
+            part.compile(compiler)?;
+        }
+
+        let module = compiler.get_module();
+        module.opcodes.push(Opcode::Csfse(self.parts.len() as u64));
+        if !self.do_push {
+            module.opcodes.push(Opcode::Pop());
+        }
+
+        Ok(())
+    }
+}
+
+impl StatementExpression for AstFormatString {
+    fn disable_push(&mut self) {
+        self.do_push = false;
+    }
+}
@@ -1,7 +1,8 @@
 use crate::FunctionAttribute;
 use crate::ast::block::AstBlock;
-use crate::module::ModuleFunction;
+use crate::module::{Arity, ModuleFunction};
 use crate::{ast::node::Compile, compiler::Compiler, opcode::Opcode};
+use alloc::boxed::*;
 use alloc::format;
 use alloc::string::*;
 use alloc::vec::*;
@@ -11,6 +12,12 @@ pub struct AstFunction {
     pub block: AstBlock,
     pub name: String,
     pub args: Vec<String>,
+    /// Defaults of the last parameters before the rest parameter
+    pub defaults: Vec<Box<dyn Compile>>,
+    /// The last parameter collects extra arguments into an array
+    pub variadic: bool,
+    /// Exported with `pub`
+    pub public: bool,
     pub attrs: Vec<FunctionAttribute>,
 }
 
@@ -20,6 +27,9 @@ impl AstFunction {
             block: AstBlock::new(),
             name: String::new(),
             args: Vec::new(),
+            defaults: Vec::new(),
+            variadic: false,
+            public: false,
             attrs: Vec::new(),
         };
     }
@@ -32,6 +42,8 @@ impl Compile for AstFunction {
         }
 
         // Add the function to the module
+        let max = self.args.len() - self.variadic as usize;
+        let arity = Arity::new(max - self.defaults.len(), max, self.variadic);
         let module = compiler.get_module();
         {
             let name = self.name.to_string();
@@ -39,15 +51,36 @@ impl Compile for AstFunction {
             module.functions.push(ModuleFunction::new(
                 name,
                 module.opcodes.len(),
-                self.args.len(),
+                arity,
                 ctor,
+                self.public,
             ));
         }
 
+        compiler.begin_function(&self.args, false);
+
         // Do attributes
         if !self.attrs.contains(&FunctionAttribute::Naked) {
             for arg in self.args.iter().rev() {
-                module.opcodes.push(Opcode::StoreArg(arg.to_string()));
+                compiler.store_arg(arg);
+            }
+
+            // Missing arguments are null, give them their defaults
+            for (index, default) in self.defaults.iter_mut().enumerate() {
+                let name = &self.args[arity.min + index];
+                compiler.load_var(name);
+                let module = compiler.get_module();
+                let branch_index = module.opcodes.len();
+                module.opcodes.push(Opcode::BranchNonNull(0));
+
+                default.compile(compiler)?;
+                compiler.store_var(name);
+
+                let module = compiler.get_module();
+                let end_addr = module.opcodes.len();
+                if let Opcode::BranchNonNull(addr) = &mut module.opcodes[branch_index] {
+                    *addr = end_addr;
+                }
             }
         }
 
@@ -55,6 +88,7 @@ impl Compile for AstFunction {
         for child in self.block.children.iter_mut() {
             child.compile(compiler)?;
         }
+        compiler.end_function();
 
         // Check for implicit null
         let module = compiler.get_module();
@@ -94,14 +94,10 @@ impl Compile for AstIfChain {
                     return Err("head.if_let_expr is None".into());
                 }
 
-                let module = compiler.get_module();
-                module
-                    .opcodes
-                    .push(Opcode::StoreVar(head.if_let_name.clone()));
-                module
-                    .opcodes
-                    .push(Opcode::LoadVar(head.if_let_name.clone()));
+                compiler.store_var(&head.if_let_name);
+                compiler.load_var(&head.if_let_name);
 
+                let module = compiler.get_module();
                 branch_indexes.push(module.opcodes.len());
                 module.opcodes.push(Opcode::BranchNonNull(0));
             }
@@ -128,14 +124,10 @@ impl Compile for AstIfChain {
                     return Err("elif_node.elif_let_expr is None".into());
                 }
 
-                let module = compiler.get_module();
-                module
-                    .opcodes
-                    .push(Opcode::StoreVar(elif_node.elif_let_name.clone()));
-                module
-                    .opcodes
-                    .push(Opcode::LoadVar(elif_node.elif_let_name.clone()));
+                compiler.store_var(&elif_node.elif_let_name);
+                compiler.load_var(&elif_node.elif_let_name);
 
+                let module = compiler.get_module();
                 branch_indexes.push(module.opcodes.len());
                 module.opcodes.push(Opcode::BranchNonNull(0));
             }
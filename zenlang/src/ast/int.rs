@@ -0,0 +1,37 @@
+use alloc::string::String;
+
+use crate::ast::node::{Compile, StatementExpression};
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+
+#[derive(Debug)]
+pub struct AstInt {
+    pub int: i64,
+    do_push: bool,
+}
+
+impl AstInt {
+    pub fn new(int: i64) -> Self {
+        return Self {
+            int: int,
+            do_push: true,
+        };
+    }
+}
+
+impl Compile for AstInt {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        let module = compiler.get_module();
+        if self.do_push {
+            module.opcodes.push(Opcode::LoadInt(self.int));
+        }
+
+        Ok(())
+    }
+}
+
+impl StatementExpression for AstInt {
+    fn disable_push(&mut self) {
+        self.do_push = false;
+    }
+}
@@ -33,13 +33,17 @@ impl Compile for AstLambda {
             let module = compiler.get_module();
             branch_opcode_index = module.opcodes.len();
             module.opcodes.push(Opcode::Branch(0));
-
-            for arg in self.args.iter().rev() {
-                module.opcodes.push(Opcode::StoreArg(arg.clone()));
-            }
         }
 
+        // the lambda body runs in its own frame, outside of any try block
+        let try_depth = core::mem::take(&mut compiler.try_depth);
+        compiler.begin_function(&self.args, true);
+        for arg in self.args.iter().rev() {
+            compiler.store_arg(arg);
+        }
         self.block.compile(compiler)?;
+        let captures = compiler.end_function();
+        compiler.try_depth = try_depth;
 
         // Check for implicit null
         let module = compiler.get_module();
@@ -59,9 +63,11 @@ impl Compile for AstLambda {
                 *addr = len;
             }
 
-            module
-                .opcodes
-                .push(Opcode::Lambda(branch_opcode_index + 1, self.args.len()))
+            module.opcodes.push(Opcode::Lambda(
+                branch_opcode_index + 1,
+                self.args.len(),
+                captures,
+            ))
         }
 
         Ok(())
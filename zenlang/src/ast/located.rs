@@ -0,0 +1,33 @@
+use crate::ast::node::Compile;
+use crate::compiler::Compiler;
+use crate::tokenizer::Span;
+use alloc::boxed::Box;
+use alloc::string::String;
+
+/// Node wrapper that records the source location of the opcodes compiled by the inner node
+#[derive(Debug)]
+pub struct AstLocated {
+    pub node: Box<dyn Compile>,
+    pub span: Span,
+}
+
+impl AstLocated {
+    pub fn new(node: Box<dyn Compile>, span: Span) -> Self {
+        return Self {
+            node: node,
+            span: span,
+        };
+    }
+}
+
+impl Compile for AstLocated {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        let start = compiler.get_module().opcodes.len();
+
+        self.node.compile(compiler)?;
+
+        compiler.set_location(start, &self.span);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::ast::node::StatementExpression;
+use crate::compiler::Compiler;
+use crate::{ast::node::Compile, opcode::Opcode};
+use alloc::boxed::*;
+use alloc::string::String;
+
+#[derive(Debug)]
+pub enum AstLogicalOp {
+    AND,
+    OR,
+}
+
+/// Short-circuiting `&&` and `||`, evaluating to the operand that decided the result
+#[derive(Debug)]
+pub struct AstLogical {
+    pub left: Box<dyn Compile>,
+    pub right: Box<dyn Compile>,
+    pub op: AstLogicalOp,
+    do_push: bool,
+}
+
+impl AstLogical {
+    pub fn new(left: Box<dyn Compile>, op: AstLogicalOp, right: Box<dyn Compile>) -> Self {
+        return Self {
+            left: left,
+            right: right,
+            op: op,
+            do_push: true,
+        };
+    }
+}
+
+impl Compile for AstLogical {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        // We compile it to this:
+        /*
+           ... (left)
+           Dup()
+           BranchTrue("1")      (&&)    BranchTrue("2")  (||)
+           Branch("2")                  Pop()
+        1: Pop()                        ... (right)
+           ... (right)               2: the rest of the code
+        2: the rest of the code
+        */
+        self.left.compile(compiler)?;
+
+        let branch_end_index;
+        {
+            let module = compiler.get_module();
+            module.opcodes.push(Opcode::Dup());
+            match self.op {
+                AstLogicalOp::AND => {
+                    let right_addr = module.opcodes.len() + 2;
+                    module.opcodes.push(Opcode::BranchTrue(right_addr));
+                    branch_end_index = module.opcodes.len();
+                    module.opcodes.push(Opcode::Branch(0));
+                }
+                AstLogicalOp::OR => {
+                    branch_end_index = module.opcodes.len();
+                    module.opcodes.push(Opcode::BranchTrue(0));
+                }
+            }
+            module.opcodes.push(Opcode::Pop());
+        }
+
+        self.right.compile(compiler)?;
+
+        let module = compiler.get_module();
+        let end_addr = module.opcodes.len();
+        match &mut module.opcodes[branch_end_index] {
+            Opcode::Branch(addr) | Opcode::BranchTrue(addr) => *addr = end_addr,
+            _ => {}
+        }
+        if !self.do_push {
+            module.opcodes.push(Opcode::Pop());
+        }
+
+        Ok(())
+    }
+}
+
+impl StatementExpression for AstLogical {
+    fn disable_push(&mut self) {
+        self.do_push = false;
+    }
+}
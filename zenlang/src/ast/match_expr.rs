@@ -0,0 +1,246 @@
+use crate::ast::block::AstBlock;
+use crate::ast::node::Compile;
+use crate::ast::node::StatementExpression;
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+use crate::tokenizer::Span;
+use alloc::boxed::*;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::*;
+
+/// Pattern of a match arm
+#[derive(Debug)]
+pub enum AstPattern {
+    /// `_`, matches anything
+    Wildcard,
+    /// `name`, matches anything and stores it in the variable
+    Binding(String),
+    /// A number, string, boolean or null compared with `==`
+    Literal(Box<dyn Compile>),
+    /// `[a, b]` matches arrays of that length, `[a, b, ..]` arrays at least that long
+    Array(Vec<AstPattern>, bool),
+    /// `{"key" = pattern}` matches dictionaries with the keys, `Name {...}` also checks `_typename`
+    Dictionary(Option<String>, Vec<(String, AstPattern)>),
+}
+
+impl AstPattern {
+    /// Does the pattern match every value
+    fn is_irrefutable(&self) -> bool {
+        return matches!(self, AstPattern::Wildcard | AstPattern::Binding(_));
+    }
+}
+
+#[derive(Debug)]
+pub enum AstMatchBody {
+    Expression(Box<dyn Compile>),
+    /// A block gives null
+    Block(AstBlock),
+}
+
+#[derive(Debug)]
+pub struct AstMatchArm {
+    pub pattern: AstPattern,
+    pub guard: Option<Box<dyn Compile>>,
+    pub body: AstMatchBody,
+}
+
+#[derive(Debug)]
+pub struct AstMatch {
+    pub value: Option<Box<dyn Compile>>,
+    pub arms: Vec<AstMatchArm>,
+    /// Span of the `match` keyword
+    pub span: Span,
+    do_push: bool,
+}
+
+impl AstMatch {
+    pub fn new(span: Span) -> Self {
+        return Self {
+            value: None,
+            arms: Vec::new(),
+            span: span,
+            do_push: true,
+        };
+    }
+}
+
+/// Push the part of the matched value that `path` indexes
+fn load_path(compiler: &mut Compiler, name: &String, path: &Vec<Opcode>) {
+    compiler.load_var(name);
+    let module = compiler.get_module();
+    for index in path.iter() {
+        module.opcodes.push(index.clone());
+        module.opcodes.push(Opcode::Iafs());
+    }
+}
+
+/// Branch to the next arm if the boolean on stack is false
+fn branch_if_false(compiler: &mut Compiler, fail_indexes: &mut Vec<usize>) {
+    let module = compiler.get_module();
+    module.opcodes.push(Opcode::Not());
+    fail_indexes.push(module.opcodes.len());
+    module.opcodes.push(Opcode::BranchTrue(0));
+}
+
+fn compile_pattern(
+    pattern: &mut AstPattern,
+    compiler: &mut Compiler,
+    name: &String,
+    path: &mut Vec<Opcode>,
+    fail_indexes: &mut Vec<usize>,
+) -> Result<(), String> {
+    match pattern {
+        AstPattern::Wildcard => {}
+        AstPattern::Binding(binding) => {
+            load_path(compiler, name, path);
+            compiler.store_var(binding);
+        }
+        AstPattern::Literal(literal) => {
+            load_path(compiler, name, path);
+            literal.compile(compiler)?;
+            compiler.get_module().opcodes.push(Opcode::Eq());
+            branch_if_false(compiler, fail_indexes);
+        }
+        AstPattern::Array(items, rest) => {
+            load_path(compiler, name, path);
+            compiler
+                .get_module()
+                .opcodes
+                .push(Opcode::MatchArray(items.len() as u64, *rest));
+            branch_if_false(compiler, fail_indexes);
+
+            for (index, item) in items.iter_mut().enumerate() {
+                path.push(Opcode::LoadInt(index as i64));
+                compile_pattern(item, compiler, name, path, fail_indexes)?;
+                path.pop();
+            }
+        }
+        AstPattern::Dictionary(typename, fields) => {
+            let mut keys: Vec<String> = fields.iter().map(|(key, _)| key.clone()).collect();
+            if typename.is_some() {
+                keys.push("_typename".into());
+            }
+            load_path(compiler, name, path);
+            compiler.get_module().opcodes.push(Opcode::MatchDict(keys));
+            branch_if_false(compiler, fail_indexes);
+
+            if let Some(typename) = typename {
+                path.push(Opcode::LoadStr("_typename".into()));
+                load_path(compiler, name, path);
+                path.pop();
+                let module = compiler.get_module();
+                module.opcodes.push(Opcode::LoadStr(typename.clone()));
+                module.opcodes.push(Opcode::Eq());
+                branch_if_false(compiler, fail_indexes);
+            }
+
+            for (key, field) in fields.iter_mut() {
+                path.push(Opcode::LoadStr(key.clone()));
+                compile_pattern(field, compiler, name, path, fail_indexes)?;
+                path.pop();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl Compile for AstMatch {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        // We compile it to this:
+        /*
+           ... (value)
+           StoreVar(matched)
+        1: ... (checks of the first pattern, each one is followed by Not, BranchTrue("2"))
+           ... (guard), Not, BranchTrue("2")
+           ... (body)
+           Branch("3")
+        2: ... (the next arms)
+           LoadNull() (no arm matched)
+        3: the rest of the code
+        */
+        if let Some(value) = &mut self.value {
+            value.compile(compiler)?;
+        } else {
+            return Err("self.value is None".into());
+        }
+
+        // the value is kept in a hidden variable, so the patterns can index it
+        let name = format!("#match{}", compiler.match_depth);
+        compiler.store_var(&name);
+        compiler.match_depth += 1;
+
+        let mut end_indexes: Vec<usize> = Vec::new();
+        for arm in self.arms.iter_mut() {
+            let mut fail_indexes: Vec<usize> = Vec::new();
+            compile_pattern(
+                &mut arm.pattern,
+                compiler,
+                &name,
+                &mut Vec::new(),
+                &mut fail_indexes,
+            )?;
+            if let Some(guard) = &mut arm.guard {
+                guard.compile(compiler)?;
+                branch_if_false(compiler, &mut fail_indexes);
+            }
+
+            match &mut arm.body {
+                AstMatchBody::Expression(expr) => expr.compile(compiler)?,
+                AstMatchBody::Block(block) => {
+                    block.compile(compiler)?;
+                    compiler.get_module().opcodes.push(Opcode::LoadNull());
+                }
+            }
+
+            let module = compiler.get_module();
+            end_indexes.push(module.opcodes.len());
+            module.opcodes.push(Opcode::Branch(0));
+
+            let next_addr = module.opcodes.len();
+            for index in fail_indexes.iter() {
+                if let Opcode::BranchTrue(addr) = &mut module.opcodes[*index] {
+                    *addr = next_addr;
+                }
+            }
+        }
+        compiler.match_depth -= 1;
+
+        let exhaustive = self
+            .arms
+            .iter()
+            .any(|arm| arm.guard.is_none() && arm.pattern.is_irrefutable());
+        if !exhaustive {
+            let filename = if compiler.parser.tokenizer.filename.is_empty() {
+                "<unknown>".into()
+            } else {
+                compiler.parser.tokenizer.filename.clone()
+            };
+            compiler.warnings.push(format!(
+                "{}:{}:{}: match is not exhaustive, it gives null when no arm matches",
+                filename, self.span.line, self.span.column
+            ));
+        }
+
+        let module = compiler.get_module();
+        module.opcodes.push(Opcode::LoadNull());
+        let end_addr = module.opcodes.len();
+        for index in end_indexes.iter() {
+            if let Opcode::Branch(addr) = &mut module.opcodes[*index] {
+                *addr = end_addr;
+            }
+        }
+        if !self.do_push {
+            module.opcodes.push(Opcode::Pop());
+        }
+
+        Ok(())
+    }
+}
+
+impl StatementExpression for AstMatch {
+    fn disable_push(&mut self) {
+        self.do_push = false;
+    }
+}
@@ -9,23 +9,35 @@ pub mod block;
 pub mod boolean;
 pub mod break_stmt;
 pub mod continue_stmt;
+pub mod destructure;
 pub mod dict;
 pub mod dynmod_stmt;
 pub mod elif_stmt;
 pub mod else_stmt;
+pub mod for_stmt;
+pub mod format_string;
 pub mod func_call;
 pub mod function;
 pub mod global_var;
 pub mod if_chain;
 pub mod if_stmt;
+pub mod int;
 pub mod lambda;
+pub mod located;
+pub mod logical;
+pub mod match_expr;
 pub mod mod_stmt;
 pub mod node;
 pub mod null;
 pub mod number;
+pub mod range;
 pub mod ret;
 pub mod root;
 pub mod string;
+pub mod throw_stmt;
+pub mod try_catch;
+pub mod unary;
+pub mod use_stmt;
 pub mod var_assign;
 pub mod var_ref;
 pub mod vmcall;
@@ -0,0 +1,52 @@
+use crate::ast::node::Compile;
+use crate::ast::node::StatementExpression;
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+use alloc::boxed::*;
+use alloc::string::String;
+
+#[derive(Debug)]
+pub struct AstRange {
+    pub start: Box<dyn Compile>,
+    pub end: Box<dyn Compile>,
+    pub step: Option<Box<dyn Compile>>,
+    pub inclusive: bool,
+    do_push: bool,
+}
+
+impl AstRange {
+    pub fn new(start: Box<dyn Compile>, end: Box<dyn Compile>, inclusive: bool) -> Self {
+        return Self {
+            start: start,
+            end: end,
+            step: None,
+            inclusive: inclusive,
+            do_push: true,
+        };
+    }
+}
+
+impl Compile for AstRange {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        if self.do_push {
+            self.start.compile(compiler)?;
+            self.end.compile(compiler)?;
+            if let Some(step) = &mut self.step {
+                step.compile(compiler)?;
+            }
+
+            let module = compiler.get_module();
+            module
+                .opcodes
+                .push(Opcode::MakeRange(self.inclusive, self.step.is_some()));
+        }
+
+        Ok(())
+    }
+}
+
+impl StatementExpression for AstRange {
+    fn disable_push(&mut self) {
+        self.do_push = false;
+    }
+}
@@ -0,0 +1,27 @@
+use crate::ast::node::Compile;
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+use alloc::boxed::Box;
+use alloc::string::String;
+
+#[derive(Debug)]
+pub struct AstThrow {
+    pub value: Box<dyn Compile>,
+}
+
+impl AstThrow {
+    pub fn new(value: Box<dyn Compile>) -> Self {
+        return Self { value: value };
+    }
+}
+
+impl Compile for AstThrow {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        self.value.compile(compiler)?;
+
+        let module = compiler.get_module();
+        module.opcodes.push(Opcode::Throw());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::ast::block::AstBlock;
+use crate::ast::node::Compile;
+use crate::compiler::Compiler;
+use crate::opcode::Opcode;
+use alloc::string::String;
+
+#[derive(Debug)]
+pub struct AstTryCatch {
+    pub body: AstBlock,
+    /// Name of the variable the caught value is stored in, None to discard it
+    pub catch_name: Option<String>,
+    pub catch_block: AstBlock,
+}
+
+impl AstTryCatch {
+    pub fn new() -> Self {
+        return Self {
+            body: AstBlock::new(),
+            catch_name: None,
+            catch_block: AstBlock::new(),
+        };
+    }
+}
+
+impl Compile for AstTryCatch {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        // We compile it to this:
+        /*
+           TryBegin("1")
+           ... (body)
+           TryEnd()
+           Branch("2")
+        1: StoreVar(catch_name)
+           ... (catch block)
+        2: the rest of the code
+        */
+        let try_begin_index;
+        {
+            let module = compiler.get_module();
+            try_begin_index = module.opcodes.len();
+            module.opcodes.push(Opcode::TryBegin(0));
+        }
+
+        compiler.try_depth += 1;
+        self.body.compile(compiler)?;
+        compiler.try_depth -= 1;
+
+        let branch_end_index;
+        {
+            let module = compiler.get_module();
+            module.opcodes.push(Opcode::TryEnd());
+            branch_end_index = module.opcodes.len();
+            module.opcodes.push(Opcode::Branch(0));
+
+            let catch_addr = module.opcodes.len();
+            if let Opcode::TryBegin(addr) = &mut module.opcodes[try_begin_index] {
+                *addr = catch_addr;
+            }
+
+            // the VM pushes the caught value before jumping to the handler
+            match &self.catch_name {
+                Some(name) => compiler.store_var(name),
+                None => module.opcodes.push(Opcode::Pop()),
+            }
+        }
+
+        self.catch_block.compile(compiler)?;
+
+        {
+            let module = compiler.get_module();
+            let end_addr = module.opcodes.len();
+            if let Opcode::Branch(addr) = &mut module.opcodes[branch_end_index] {
+                *addr = end_addr;
+            }
+        }
+
+        Ok(())
+    }
+}
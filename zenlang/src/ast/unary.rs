@@ -0,0 +1,55 @@
+use crate::ast::node::StatementExpression;
+use crate::compiler::Compiler;
+use crate::{ast::node::Compile, opcode::Opcode};
+use alloc::boxed::*;
+use alloc::string::String;
+
+#[derive(Debug)]
+pub enum AstUnaryOp {
+    NOT,
+    NEG,
+    BITNOT,
+}
+
+#[derive(Debug)]
+pub struct AstUnary {
+    pub value: Box<dyn Compile>,
+    pub op: AstUnaryOp,
+    do_push: bool,
+}
+
+impl AstUnary {
+    pub fn new(op: AstUnaryOp, value: Box<dyn Compile>) -> Self {
+        return Self {
+            value: value,
+            op: op,
+            do_push: true,
+        };
+    }
+}
+
+impl Compile for AstUnary {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        self.value.compile(compiler)?;
+
+        let opcode = match self.op {
+            AstUnaryOp::NOT => Opcode::Not(),
+            AstUnaryOp::NEG => Opcode::Neg(),
+            AstUnaryOp::BITNOT => Opcode::Bnot(),
+        };
+
+        let module = compiler.get_module();
+        module.opcodes.push(opcode);
+        if !self.do_push {
+            module.opcodes.push(Opcode::Pop());
+        }
+
+        Ok(())
+    }
+}
+
+impl StatementExpression for AstUnary {
+    fn disable_push(&mut self) {
+        self.do_push = false;
+    }
+}
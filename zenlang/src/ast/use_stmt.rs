@@ -0,0 +1,42 @@
+use crate::{ast::node::Compile, compiler::Compiler};
+use alloc::format;
+use alloc::string::*;
+use alloc::vec::*;
+
+/// `use module::{names};`, imports exported functions of a module declared with `mod`
+#[derive(Debug)]
+pub struct AstUse {
+    pub module: String,
+    pub names: Vec<String>,
+}
+
+impl AstUse {
+    pub fn new(module: String) -> Self {
+        return Self {
+            module: module,
+            names: Vec::new(),
+        };
+    }
+}
+
+impl Compile for AstUse {
+    fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
+        if !compiler.get_module().dependencies.contains(&self.module) {
+            return Err(format!(
+                "use of undeclared module {}, declare it with `mod {};` first",
+                self.module, self.module
+            ));
+        }
+
+        // the names are qualified when loaded, whether they're exported is checked at link time
+        for name in self.names.iter() {
+            let qualified = format!("{}::{}", self.module, name);
+            compiler.get_module().imports.push(qualified.clone());
+            if let Some(other) = compiler.imports.insert(name.clone(), qualified) {
+                return Err(format!("{} is imported twice (already as {})", name, other));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ast::node::Compile, compiler::Compiler, opcode::Opcode};
+use crate::{ast::node::Compile, compiler::Compiler};
 use alloc::string::String;
 
 #[derive(Debug)]
@@ -24,8 +24,7 @@ impl Compile for AstAssign {
             return Err("expr is None".into());
         }
 
-        let module = compiler.get_module();
-        module.opcodes.push(Opcode::StoreVar(self.name.clone()));
+        compiler.store_var(&self.name);
 
         Ok(())
     }
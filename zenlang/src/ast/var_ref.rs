@@ -1,6 +1,6 @@
+use crate::ast::node::Compile;
 use crate::ast::node::StatementExpression;
 use crate::compiler::Compiler;
-use crate::{ast::node::Compile, opcode::Opcode};
 use alloc::string::*;
 
 #[derive(Debug)]
@@ -20,9 +20,8 @@ impl AstVarRef {
 
 impl Compile for AstVarRef {
     fn compile(&mut self, compiler: &mut Compiler) -> Result<(), String> {
-        let module = compiler.get_module();
         if self.do_push {
-            module.opcodes.push(Opcode::LoadVar(self.name.clone()));
+            compiler.load_var(&self.name);
         }
         Ok(())
     }
@@ -3,7 +3,6 @@ use crate::compiler::Compiler;
 use crate::{ast::node::Compile, opcode::*};
 use alloc::boxed::*;
 use alloc::string::String;
-use alloc::vec::*;
 
 #[derive(Debug)]
 pub struct AstWhileStmt {
@@ -45,8 +44,7 @@ impl Compile for AstWhileStmt {
         }
 
         // * compile body
-        compiler.while_stmts_break_indexes.push(Vec::new());
-        compiler.while_stmts_continue_indexes.push(Vec::new());
+        compiler.begin_loop();
         self.body.compile(compiler)?;
 
         let exit_addr;
@@ -60,47 +58,8 @@ impl Compile for AstWhileStmt {
             module.opcodes.push(Opcode::Branch(cmp_addr));
         }
 
-        // break statementss
-        {
-            let last: Vec<usize>;
-            {
-                last = compiler.while_stmts_break_indexes.last().unwrap().clone();
-            }
-
-            {
-                let module = compiler.get_module();
-                for index in last.iter() {
-                    if let Opcode::Branch(addr) = &mut module.opcodes[*index] {
-                        *addr = exit_addr;
-                    }
-                }
-            }
-
-            compiler.while_stmts_break_indexes.pop();
-        }
-
-        // continue statementss
-        {
-            let last: Vec<usize>;
-            {
-                last = compiler
-                    .while_stmts_continue_indexes
-                    .last()
-                    .unwrap()
-                    .clone();
-            }
-
-            {
-                let module = compiler.get_module();
-                for index in last.iter() {
-                    if let Opcode::Branch(addr) = &mut module.opcodes[*index] {
-                        *addr = cmp_addr;
-                    }
-                }
-            }
-
-            compiler.while_stmts_continue_indexes.pop();
-        }
+        // break and continue statements
+        compiler.end_loop(cmp_addr, exit_addr);
 
         Ok(())
     }
@@ -3,17 +3,80 @@
 //! Compiles ZenLang into a module
 
 use crate::ast::node::Compile;
-use crate::module::Module;
+use crate::module::{Module, ModuleDebugInfo, SourceLocation};
+use crate::opcode::Opcode;
+use crate::optimizer::OptimizationLevel;
 use crate::parser::*;
+use crate::tokenizer::Span;
+use alloc::collections::btree_map::BTreeMap;
 use alloc::string::*;
 use alloc::vec::*;
 
+/// Variables of a function or a lambda being compiled
+pub(crate) struct FunctionScope {
+    /// Arguments and variables assigned so far, a variable's slot is its index
+    locals: Vec<String>,
+    /// Variables of the enclosing functions used by a lambda
+    captures: Vec<String>,
+    /// Locals captured by a nested lambda, they're accessed by name
+    shared: Vec<String>,
+    /// Variables read before they're assigned, they're accessed by name so the read finds
+    /// the function or global of that name until the variable is assigned
+    read_early: Vec<String>,
+    /// Indexes of the variable opcodes emitted for the function body
+    var_opcodes: Vec<usize>,
+    is_lambda: bool,
+}
+
+impl FunctionScope {
+    pub(crate) fn new(args: &[String], is_lambda: bool) -> Self {
+        return Self {
+            locals: args.to_vec(),
+            captures: Vec::new(),
+            shared: Vec::new(),
+            read_early: Vec::new(),
+            var_opcodes: Vec::new(),
+            is_lambda: is_lambda,
+        };
+    }
+
+    /// Check if a variable is an argument, a local or a captured variable
+    fn contains(&self, name: &String) -> bool {
+        // functions get self from the VM, it's always accessed by name
+        if !self.is_lambda && name == "self" {
+            return true;
+        }
+        return self.locals.contains(name) || self.captures.contains(name);
+    }
+
+    /// Get the slot of a variable, None if it has to be accessed by name
+    fn get_slot(&self, name: &String) -> Option<u16> {
+        if self.shared.contains(name) || self.read_early.contains(name) {
+            return None;
+        }
+        let slot = self.locals.iter().position(|local| local == name)?;
+        return u16::try_from(slot).ok();
+    }
+}
+
 pub struct Compiler<'a> {
     pub parser: &'a mut Parser<'a>,
     module: Module,
     pub(crate) while_stmts_break_indexes: Vec<Vec<usize>>,
     pub(crate) while_stmts_continue_indexes: Vec<Vec<usize>>,
+    /// Try depth at the start of every loop being compiled
+    pub(crate) while_stmts_try_depths: Vec<usize>,
+    /// How many try blocks the current node is nested in
+    pub(crate) try_depth: usize,
+    /// How many match expressions the current node is nested in
+    pub(crate) match_depth: usize,
+    /// Functions and lambdas being compiled, innermost last
+    pub(crate) function_scopes: Vec<FunctionScope>,
+    /// Names imported with `use` and the qualified names they refer to
+    pub(crate) imports: BTreeMap<String, String>,
     pub warnings: Vec<String>,
+    /// Optimizations applied to the module after it's compiled
+    pub optimization_level: OptimizationLevel,
 }
 
 impl<'a> Compiler<'_> {
@@ -23,7 +86,13 @@ impl<'a> Compiler<'_> {
             module: Module::new(),
             while_stmts_break_indexes: Vec::new(),
             while_stmts_continue_indexes: Vec::new(),
+            while_stmts_try_depths: Vec::new(),
+            try_depth: 0,
+            match_depth: 0,
+            function_scopes: Vec::new(),
+            imports: BTreeMap::new(),
             warnings: Vec::new(),
+            optimization_level: OptimizationLevel::None,
         };
 
         return inst;
@@ -34,6 +103,159 @@ impl<'a> Compiler<'_> {
         return &mut self.module;
     }
 
+    /// Set the source location of opcodes from start to the end of the module, skipping ones that already have it
+    ///
+    /// Called after a node is compiled, so nested nodes set their locations first
+    pub(crate) fn set_location(&mut self, start: usize, span: &Span) {
+        let len = self.module.opcodes.len();
+        if let Some(debug_info) = &mut self.module.debug_info {
+            debug_info.locations.resize(len, None);
+            for location in debug_info.locations[start..].iter_mut() {
+                if location.is_none() {
+                    *location = Some(SourceLocation {
+                        line: span.line,
+                        column: span.column,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Start compiling a function or a lambda body
+    pub(crate) fn begin_function(&mut self, args: &[String], is_lambda: bool) {
+        self.function_scopes
+            .push(FunctionScope::new(args, is_lambda));
+    }
+
+    /// Finish compiling a function or a lambda body, returns the captured variables
+    ///
+    /// Variable opcodes of the body are resolved to slots here, once it's known which
+    /// variables are local and which of them are shared with lambdas
+    pub(crate) fn end_function(&mut self) -> Vec<String> {
+        let scope = match self.function_scopes.pop() {
+            Some(scope) => scope,
+            None => return Vec::new(),
+        };
+
+        for index in scope.var_opcodes.iter() {
+            let opcode = &mut self.module.opcodes[*index];
+            let resolved = match opcode {
+                Opcode::LoadVar(name) => scope.get_slot(name).map(Opcode::LoadLocal),
+                Opcode::StoreVar(name) => scope.get_slot(name).map(Opcode::StoreLocal),
+                Opcode::StoreArg(name) => scope.get_slot(name).map(Opcode::StoreArgLocal),
+                _ => None,
+            };
+            if let Some(resolved) = resolved {
+                *opcode = resolved;
+            }
+        }
+
+        return scope.captures;
+    }
+
+    /// Check if a variable belongs to the function at `depth`, capturing it from
+    /// the enclosing functions if the function is a lambda
+    fn resolve_variable(&mut self, depth: usize, name: &String) -> bool {
+        let scope = &self.function_scopes[depth];
+        if scope.contains(name) {
+            return true;
+        }
+        if !scope.is_lambda || depth == 0 {
+            return false;
+        }
+        if self.resolve_variable(depth - 1, name) {
+            self.function_scopes[depth].captures.push(name.clone());
+            let parent = &mut self.function_scopes[depth - 1];
+            if parent.locals.contains(name) && !parent.shared.contains(name) {
+                parent.shared.push(name.clone());
+            }
+            return true;
+        }
+        return false;
+    }
+
+    fn is_local_variable(&mut self, name: &String) -> bool {
+        if self.function_scopes.is_empty() || self.module.globals.contains(name) {
+            return false;
+        }
+        return self.resolve_variable(self.function_scopes.len() - 1, name);
+    }
+
+    /// Push a variable opcode, remembering it so it's resolved to a slot at the end of the function
+    fn push_var_opcode(&mut self, name: &String, opcode: Opcode) {
+        if !self.module.globals.contains(name) {
+            if let Some(scope) = self.function_scopes.last_mut() {
+                scope.var_opcodes.push(self.module.opcodes.len());
+            }
+        }
+        self.module.opcodes.push(opcode);
+    }
+
+    /// Emit a variable load
+    pub(crate) fn load_var(&mut self, name: &String) {
+        if !self.is_local_variable(name) && !self.module.globals.contains(name) {
+            if let Some(scope) = self.function_scopes.last_mut() {
+                if !scope.read_early.contains(name) {
+                    scope.read_early.push(name.clone());
+                }
+            }
+            // an imported name is the function of its module, unless a variable shadows it
+            if let Some(qualified) = self.imports.get(name) {
+                self.module.opcodes.push(Opcode::LoadVar(qualified.clone()));
+                return;
+            }
+        }
+        self.push_var_opcode(name, Opcode::LoadVar(name.clone()));
+    }
+
+    /// Emit a variable store, declaring the variable if it's not local or captured yet
+    pub(crate) fn store_var(&mut self, name: &String) {
+        if !self.is_local_variable(name) && !self.module.globals.contains(name) {
+            if let Some(scope) = self.function_scopes.last_mut() {
+                scope.locals.push(name.clone());
+            }
+        }
+        self.push_var_opcode(name, Opcode::StoreVar(name.clone()));
+    }
+
+    /// Emit an argument store, must be called after `begin_function`
+    pub(crate) fn store_arg(&mut self, name: &String) {
+        self.push_var_opcode(name, Opcode::StoreArg(name.clone()));
+    }
+
+    /// Start compiling a loop body, `break` and `continue` inside it are patched by `end_loop`
+    pub(crate) fn begin_loop(&mut self) {
+        self.while_stmts_break_indexes.push(Vec::new());
+        self.while_stmts_continue_indexes.push(Vec::new());
+        self.while_stmts_try_depths.push(self.try_depth);
+    }
+
+    /// Finish compiling a loop body, pointing its `continue` and `break` branches at the addresses
+    pub(crate) fn end_loop(&mut self, continue_addr: usize, exit_addr: usize) {
+        self.while_stmts_try_depths.pop();
+        let breaks = self.while_stmts_break_indexes.pop().unwrap_or_default();
+        let continues = self.while_stmts_continue_indexes.pop().unwrap_or_default();
+
+        for (indexes, target) in [(breaks, exit_addr), (continues, continue_addr)] {
+            for index in indexes.iter() {
+                if let Opcode::Branch(addr) = &mut self.module.opcodes[*index] {
+                    *addr = target;
+                }
+            }
+        }
+    }
+
+    /// Emit TryEnd for every try block that a break or continue jumps out of
+    pub(crate) fn end_loop_try_blocks(&mut self) {
+        let loop_try_depth = *self
+            .while_stmts_try_depths
+            .last()
+            .unwrap_or(&self.try_depth);
+        for _ in loop_try_depth..self.try_depth {
+            self.module.opcodes.push(Opcode::TryEnd());
+        }
+    }
+
     /// Compile everything
     pub fn compile(&mut self) -> Result<(), String> {
         self.warnings.clear();
@@ -41,11 +263,15 @@ impl<'a> Compiler<'_> {
             return Err(e.to_string());
         }
 
+        let filename = self.parser.tokenizer.filename.clone();
+        self.module.debug_info = Some(ModuleDebugInfo::new(filename));
+
         let mut root = core::mem::take(&mut self.parser.root);
 
         let _ = root.compile(self)?;
 
         self.parser.root = root;
+        self.module.optimize(self.optimization_level);
 
         Ok(())
     }
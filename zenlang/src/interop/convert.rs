@@ -0,0 +1,297 @@
+use crate::interop::{interop_err, interop_ok};
+use crate::value::*;
+use alloc::collections::btree_map::BTreeMap;
+use alloc::format;
+use alloc::rc::*;
+use alloc::string::*;
+use alloc::vec::*;
+use core::cell::*;
+
+/// Error message of a failed conversion
+pub type ConversionError = String;
+
+/// Conversion of a Rust value into a ZenLang value
+pub trait ToValue {
+    fn to_value(self) -> Value;
+}
+
+/// Conversion of a ZenLang value into a Rust value
+///
+/// Returns an error message if the value has a wrong type
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, ConversionError>;
+}
+
+/// Conversion of native function arguments into a tuple of Rust values
+pub trait FromArgs: Sized {
+    /// Argument count
+    const ARITY: usize;
+
+    fn from_args(args: &[Value]) -> Result<Self, ConversionError>;
+}
+
+/// Make a dictionary from key-value pairs, used by `#[derive(ToValue)]`
+pub fn make_dictionary<'a>(fields: impl IntoIterator<Item = (&'a str, Value)>) -> Value {
+    let dict = fields
+        .into_iter()
+        .map(|(key, value)| (key.into(), value))
+        .collect();
+    return Value::Object(Rc::new(RefCell::new(Object::Dictionary(dict))));
+}
+
+/// Get a field of a dictionary, used by `#[derive(FromValue)]`
+pub fn get_field<T: FromValue>(value: &Value, name: &str) -> Result<T, ConversionError> {
+    if let Value::Object(obj) = value {
+        if let Object::Dictionary(dict) = &*obj.borrow() {
+            return match dict.get(name) {
+                Some(field) => T::from_value(field).map_err(|e| format!("field {}: {}", name, e)),
+                None => Err(format!("missing field {}", name)),
+            };
+        }
+    }
+    return Err(format!("expected a dictionary, found {}", value.get_type()));
+}
+
+fn type_error<T>(expected: &str, value: &Value) -> Result<T, ConversionError> {
+    return Err(format!("expected {}, found {}", expected, value.get_type()));
+}
+
+impl ToValue for Value {
+    fn to_value(self) -> Value {
+        return self;
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        return Ok(value.clone());
+    }
+}
+
+impl ToValue for () {
+    fn to_value(self) -> Value {
+        return Value::Null();
+    }
+}
+
+impl FromValue for () {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Value::Null() = value {
+            return Ok(());
+        }
+        return type_error("null", value);
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(self) -> Value {
+        return Value::Number(self);
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Some(num) = value.as_number() {
+            return Ok(num);
+        }
+        return type_error("number", value);
+    }
+}
+
+impl ToValue for f32 {
+    fn to_value(self) -> Value {
+        return Value::Number(self as f64);
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        return f64::from_value(value).map(|num| num as f32);
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl ToValue for $t {
+                fn to_value(self) -> Value {
+                    // only u64 and usize values can be too large for an integer
+                    return match i64::try_from(self) {
+                        Ok(int) => Value::Int(int),
+                        Err(_) => Value::Number(self as f64),
+                    };
+                }
+            }
+
+            impl FromValue for $t {
+                fn from_value(value: &Value) -> Result<Self, ConversionError> {
+                    if let Value::Int(int) = value {
+                        return <$t>::try_from(*int)
+                            .map_err(|_| format!("{} is not a valid {}", int, stringify!($t)));
+                    }
+                    let num = f64::from_value(value)?;
+                    if num != libm::trunc(num) || num < <$t>::MIN as f64 || num > <$t>::MAX as f64 {
+                        return Err(format!("{} is not a valid {}", num, stringify!($t)));
+                    }
+                    return Ok(num as $t);
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl ToValue for bool {
+    fn to_value(self) -> Value {
+        return Value::Boolean(self);
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Value::Boolean(b) = value {
+            return Ok(*b);
+        }
+        return type_error("boolean", value);
+    }
+}
+
+impl ToValue for String {
+    fn to_value(self) -> Value {
+        return Value::String(self);
+    }
+}
+
+impl ToValue for &str {
+    fn to_value(self) -> Value {
+        return Value::String(self.into());
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Value::String(s) = value {
+            return Ok(s.clone());
+        }
+        return type_error("string", value);
+    }
+}
+
+impl<T: ToValue> ToValue for Vec<T> {
+    fn to_value(self) -> Value {
+        let array = self.into_iter().map(|element| element.to_value()).collect();
+        return Value::Object(Rc::new(RefCell::new(Object::Array(array))));
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Value::Object(obj) = value {
+            if let Object::Array(array) = &*obj.borrow() {
+                let mut result = Vec::with_capacity(array.len());
+                for (i, element) in array.iter().enumerate() {
+                    result.push(T::from_value(element).map_err(|e| format!("index {}: {}", i, e))?);
+                }
+                return Ok(result);
+            }
+        }
+        return type_error("array", value);
+    }
+}
+
+impl<T: ToValue> ToValue for BTreeMap<String, T> {
+    fn to_value(self) -> Value {
+        let dict = self
+            .into_iter()
+            .map(|(key, element)| (key, element.to_value()))
+            .collect();
+        return Value::Object(Rc::new(RefCell::new(Object::Dictionary(dict))));
+    }
+}
+
+impl<T: FromValue> FromValue for BTreeMap<String, T> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Value::Object(obj) = value {
+            if let Object::Dictionary(dict) = &*obj.borrow() {
+                let mut result = BTreeMap::new();
+                for (key, element) in dict.iter() {
+                    let element =
+                        T::from_value(element).map_err(|e| format!("key {}: {}", key, e))?;
+                    result.insert(key.clone(), element);
+                }
+                return Ok(result);
+            }
+        }
+        return type_error("dictionary", value);
+    }
+}
+
+/// `None` is converted to null
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(self) -> Value {
+        return match self {
+            Some(value) => value.to_value(),
+            None => Value::Null(),
+        };
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        if let Value::Null() = value {
+            return Ok(None);
+        }
+        return T::from_value(value).map(Some);
+    }
+}
+
+/// Converted to a stdlib Result, see `interop_ok` and `interop_err`
+impl<T: ToValue, E: ToValue> ToValue for Result<T, E> {
+    fn to_value(self) -> Value {
+        return match self {
+            Ok(value) => interop_ok(value.to_value()),
+            Err(value) => interop_err(value.to_value()),
+        };
+    }
+}
+
+impl<T: FromValue, E: FromValue> FromValue for Result<T, E> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        let typename: String = get_field(value, "_typename")?;
+        if typename != "Result" {
+            return Err(format!("expected Result, found {}", typename));
+        }
+        let err: Value = get_field(value, "_err")?;
+        if let Value::Null() = err {
+            return Ok(Ok(get_field(value, "_ok")?));
+        }
+        return Ok(Err(E::from_value(&err)?));
+    }
+}
+
+macro_rules! impl_from_args {
+    ($count:expr; $($t:ident $i:tt),*) => {
+        impl<$($t: FromValue),*> FromArgs for ($($t,)*) {
+            const ARITY: usize = $count;
+
+            #[allow(unused_variables)]
+            fn from_args(args: &[Value]) -> Result<Self, ConversionError> {
+                if args.len() != $count {
+                    return Err(format!("expected {} arguments, found {}", $count, args.len()));
+                }
+                return Ok(($(
+                    $t::from_value(&args[$i]).map_err(|e| format!("argument {}: {}", $i, e))?,
+                )*));
+            }
+        }
+    };
+}
+
+impl_from_args!(0;);
+impl_from_args!(1; A 0);
+impl_from_args!(2; A 0, B 1);
+impl_from_args!(3; A 0, B 1, C 2);
+impl_from_args!(4; A 0, B 1, C 2, D 3);
+impl_from_args!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_args!(6; A 0, B 1, C 2, D 3, E 4, F 5);
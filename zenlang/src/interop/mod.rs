@@ -1,12 +1,17 @@
 //! Interop between ZenLang <-> Rust
 //!
-//! Used in zenlang::vm
+//! Used in zenlang::vm. Rust types are converted with [ToValue] and [FromValue],
+//! which can be derived for structs with named fields
 use crate::value::*;
 use alloc::collections::btree_map::BTreeMap;
 use alloc::rc::*;
 use alloc::string::*;
 use core::cell::*;
 
+mod convert;
+pub use convert::*;
+pub use zenlang_derive::{FromValue, ToValue};
+
 /// Returns a result with an ok value
 ///
 /// See ok() function in zenlang's stdlib
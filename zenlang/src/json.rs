@@ -0,0 +1,401 @@
+//! JSON
+//!
+//! Conversion between JSON text and values, used by the `json_parse` and `json_stringify` natives
+use crate::value::*;
+use alloc::collections::btree_map::BTreeMap;
+use alloc::format;
+use alloc::rc::*;
+use alloc::string::*;
+use alloc::vec::*;
+use core::cell::RefCell;
+use core::fmt::Write;
+
+/// Maximum nesting of arrays and objects when parsing
+pub static MAX_DEPTH: usize = 256;
+
+/// Maximum indent of [stringify], like `JSON.stringify` in JavaScript
+pub static MAX_INDENT: usize = 10;
+
+/// Parse a JSON text into a value
+///
+/// Objects become dictionaries and arrays become arrays
+pub fn parse(text: &str) -> Result<Value, String> {
+    let mut parser = JsonParser {
+        text: text,
+        pos: 0,
+        depth: 0,
+    };
+    parser.skip_whitespace();
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos != text.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+    return Ok(value);
+}
+
+/// Convert a value into a JSON text
+///
+/// Objects are pretty printed with `indent` spaces (up to [MAX_INDENT]), or on a single line
+/// if `indent` is 0. Non-finite numbers become null, functions can't be converted
+pub fn stringify(value: &Value, indent: usize) -> Result<String, String> {
+    if indent > MAX_INDENT {
+        return Err(format!("indent {} is larger than {}", indent, MAX_INDENT));
+    }
+    let mut out = String::new();
+    write_value(&mut out, value, indent, 0)?;
+    return Ok(out);
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, message: &str) -> String {
+        let before = &self.text[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = match before.rfind('\n') {
+            Some(newline) => before[newline + 1..].chars().count() + 1,
+            None => before.chars().count() + 1,
+        };
+        return format!("{} at {}:{}", message, line, column);
+    }
+
+    fn peek(&self) -> Option<u8> {
+        return self.text.as_bytes().get(self.pos).copied();
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        if self.text[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            return Ok(());
+        }
+        return Err(self.error(&format!("expected {}", literal)));
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => Ok(Value::String(self.parse_string()?)),
+            Some(b't') => self.expect("true").map(|_| Value::Boolean(true)),
+            Some(b'f') => self.expect("false").map(|_| Value::Boolean(false)),
+            Some(b'n') => self.expect("null").map(|_| Value::Null()),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error("maximum nesting depth exceeded"));
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+        return Ok(());
+    }
+
+    fn parse_array(&mut self) -> Result<Value, String> {
+        self.enter()?;
+        let mut array = Vec::new();
+
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                array.push(self.parse_value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => {
+                        self.pos += 1;
+                        self.skip_whitespace();
+                    }
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expected , or ]")),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        return Ok(Value::Object(Rc::new(RefCell::new(Object::Array(array)))));
+    }
+
+    fn parse_object(&mut self) -> Result<Value, String> {
+        self.enter()?;
+        let mut dict = BTreeMap::new();
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+        } else {
+            loop {
+                if self.peek() != Some(b'"') {
+                    return Err(self.error("expected a string key"));
+                }
+                let key = self.parse_string()?;
+                self.skip_whitespace();
+                self.expect(":")?;
+                self.skip_whitespace();
+                dict.insert(key, self.parse_value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => {
+                        self.pos += 1;
+                        self.skip_whitespace();
+                    }
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    _ => return Err(self.error("expected , or }")),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        return Ok(Value::Object(Rc::new(RefCell::new(Object::Dictionary(
+            dict,
+        )))));
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, String> {
+        let digits = match self.text.get(self.pos..self.pos + 4) {
+            Some(digits) => digits,
+            None => return Err(self.error("invalid unicode escape")),
+        };
+        match u32::from_str_radix(digits, 16) {
+            Ok(code) => {
+                self.pos += 4;
+                return Ok(code);
+            }
+            Err(_) => return Err(self.error("invalid unicode escape")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        // skip the opening quote
+        self.pos += 1;
+        let mut string = String::new();
+
+        loop {
+            let ch = match self.text[self.pos..].chars().next() {
+                Some(ch) => ch,
+                None => return Err(self.error("unterminated string")),
+            };
+
+            match ch {
+                '"' => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    let escape = match self.peek() {
+                        Some(escape) => escape,
+                        None => return Err(self.error("unterminated string")),
+                    };
+                    self.pos += 1;
+                    match escape {
+                        b'"' => string.push('"'),
+                        b'\\' => string.push('\\'),
+                        b'/' => string.push('/'),
+                        b'b' => string.push('\u{8}'),
+                        b'f' => string.push('\u{c}'),
+                        b'n' => string.push('\n'),
+                        b'r' => string.push('\r'),
+                        b't' => string.push('\t'),
+                        b'u' => {
+                            let mut code = self.parse_hex4()?;
+                            // surrogate pair
+                            if (0xD800..0xDC00).contains(&code)
+                                && self.text[self.pos..].starts_with("\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.parse_hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error("invalid surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            match char::from_u32(code) {
+                                Some(ch) => string.push(ch),
+                                None => return Err(self.error("invalid unicode escape")),
+                            }
+                        }
+                        _ => {
+                            self.pos -= 2;
+                            return Err(self.error("invalid escape"));
+                        }
+                    }
+                }
+                '\u{0}'..='\u{1f}' => {
+                    return Err(self.error("control character in string"));
+                }
+                _ => {
+                    string.push(ch);
+                    self.pos += ch.len_utf8();
+                }
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("invalid number"));
+            }
+            self.skip_digits();
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("invalid number"));
+            }
+            self.skip_digits();
+        }
+
+        // numbers without a fraction or an exponent are integers when they fit
+        if let Ok(int) = self.text[start..self.pos].parse::<i64>() {
+            return Ok(Value::Int(int));
+        }
+        match self.text[start..self.pos].parse::<f64>() {
+            Ok(num) => return Ok(Value::Number(num)),
+            Err(_) => {
+                self.pos = start;
+                return Err(self.error("invalid number"));
+            }
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+}
+
+fn write_string(out: &mut String, string: &str) {
+    out.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\u{0}'..='\u{1f}' => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            }
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+fn write_newline(out: &mut String, indent: usize, level: usize) {
+    if indent != 0 {
+        out.push('\n');
+        for _ in 0..indent * level {
+            out.push(' ');
+        }
+    }
+}
+
+fn write_value(out: &mut String, value: &Value, indent: usize, level: usize) -> Result<(), String> {
+    match value {
+        Value::Number(num) => {
+            if num.is_finite() {
+                let _ = write!(out, "{}", num);
+            } else {
+                out.push_str("null");
+            }
+        }
+        Value::Int(int) => {
+            let _ = write!(out, "{}", int);
+        }
+        Value::String(string) => write_string(out, string),
+        Value::Boolean(boolean) => {
+            let _ = write!(out, "{}", boolean);
+        }
+        Value::Null() => out.push_str("null"),
+        Value::Object(obj) => {
+            if level >= MAX_DEPTH {
+                return Err("json: maximum nesting depth exceeded".into());
+            }
+
+            match &*obj.borrow() {
+                Object::Array(array) => {
+                    out.push('[');
+                    for (i, element) in array.iter().enumerate() {
+                        if i != 0 {
+                            out.push(',');
+                        }
+                        write_newline(out, indent, level + 1);
+                        write_value(out, element, indent, level + 1)?;
+                    }
+                    if !array.is_empty() {
+                        write_newline(out, indent, level);
+                    }
+                    out.push(']');
+                }
+                Object::Dictionary(dict) => {
+                    out.push('{');
+                    for (i, (key, element)) in dict.iter().enumerate() {
+                        if i != 0 {
+                            out.push(',');
+                        }
+                        write_newline(out, indent, level + 1);
+                        write_string(out, key);
+                        out.push(':');
+                        if indent != 0 {
+                            out.push(' ');
+                        }
+                        write_value(out, element, indent, level + 1)?;
+                    }
+                    if !dict.is_empty() {
+                        write_newline(out, indent, level);
+                    }
+                    out.push('}');
+                }
+            }
+        }
+        _ => {
+            return Err(format!(
+                "json: cannot convert a {} to JSON",
+                value.get_type()
+            ));
+        }
+    }
+    return Ok(());
+}
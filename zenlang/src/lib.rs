@@ -12,8 +12,10 @@
 pub mod ast;
 pub mod compiler;
 pub mod interop;
+pub mod json;
 pub mod module;
 pub mod opcode;
+pub mod optimizer;
 pub mod parser;
 pub mod platform;
 pub mod scope;
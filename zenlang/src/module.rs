@@ -9,6 +9,57 @@ use bincode;
 use bincode::config::Configuration;
 use bincode::error::DecodeError;
 use bincode::*;
+use core::fmt::Display;
+
+/// Arity
+///
+/// How many arguments a function accepts
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arity {
+    /// Arguments that have to be provided, the ones after it have defaults
+    pub min: usize,
+    /// Parameters before the rest parameter
+    pub max: usize,
+    /// Extra arguments are collected into an array by the rest parameter
+    pub variadic: bool,
+}
+
+impl Arity {
+    pub fn new(min: usize, max: usize, variadic: bool) -> Arity {
+        return Arity {
+            min: min,
+            max: max,
+            variadic: variadic,
+        };
+    }
+
+    /// Accepts exactly `count` arguments
+    pub fn exact(count: usize) -> Arity {
+        return Arity::new(count, count, false);
+    }
+
+    /// Does a call with `count` arguments fit
+    pub fn accepts(&self, count: usize) -> bool {
+        return count >= self.min && (self.variadic || count <= self.max);
+    }
+
+    /// Parameter count, the rest parameter included
+    pub fn params_count(&self) -> usize {
+        return self.max + self.variadic as usize;
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.variadic {
+            return write!(f, "at least {}", self.min);
+        }
+        if self.min == self.max {
+            return write!(f, "exactly {}", self.min);
+        }
+        return write!(f, "{} to {}", self.min, self.max);
+    }
+}
 
 /// ModuleFunction
 ///
@@ -19,19 +70,59 @@ pub struct ModuleFunction {
     pub name: String,
     /// Function address relative to the module offset
     pub addr: usize,
-    /// Argument count
-    pub args_count: usize,
+    /// Accepted argument counts
+    pub arity: Arity,
     /// Is a constructor function
     pub ctor: bool,
+    /// Is exported with `pub`, so other modules can access it as `module.name`
+    pub public: bool,
 }
 
 impl ModuleFunction {
-    pub fn new(name: String, addr: usize, args_count: usize, ctor: bool) -> ModuleFunction {
+    pub fn new(
+        name: String,
+        addr: usize,
+        arity: Arity,
+        ctor: bool,
+        public: bool,
+    ) -> ModuleFunction {
         return ModuleFunction {
             name: name,
             addr: addr,
-            args_count: args_count,
+            arity: arity,
             ctor: ctor,
+            public: public,
+        };
+    }
+}
+
+/// SourceLocation
+///
+/// Line and column in the source file an opcode was compiled from
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SourceLocation {
+    /// Line number, starting from 1
+    pub line: usize,
+    /// Column number, starting from 1
+    pub column: usize,
+}
+
+/// ModuleDebugInfo
+///
+/// Debug information of a module, can be stripped with Module::strip_debug_info
+#[derive(Encode, Decode, Debug, Clone)]
+pub struct ModuleDebugInfo {
+    /// Source file name
+    pub filename: String,
+    /// Source location of every opcode, indexed by the opcode address
+    pub locations: Vec<Option<SourceLocation>>,
+}
+
+impl ModuleDebugInfo {
+    pub fn new(filename: String) -> ModuleDebugInfo {
+        return ModuleDebugInfo {
+            filename: filename,
+            locations: Vec::new(),
         };
     }
 }
@@ -51,6 +142,10 @@ pub struct Module {
     pub opcodes: Vec<Opcode>,
     /// Global variables
     pub globals: Vec<String>,
+    /// Functions imported with `use`, as `module::name`
+    pub imports: Vec<String>,
+    /// Debug information (opcode to source line table)
+    pub debug_info: Option<ModuleDebugInfo>,
 }
 
 impl Module {
@@ -61,6 +156,8 @@ impl Module {
             dependencies: Vec::new(),
             name: String::new(),
             globals: Vec::new(),
+            imports: Vec::new(),
+            debug_info: None,
         };
     }
 
@@ -89,6 +186,29 @@ impl Module {
     pub fn get_opcode(&self, addr: u32) -> &Opcode {
         return &self.opcodes[addr as usize];
     }
+
+    /// Get the source location of an opcode at a certain address, if debug information is present
+    pub fn get_source_location(&self, addr: usize) -> Option<SourceLocation> {
+        if let Some(debug_info) = &self.debug_info {
+            if let Some(location) = debug_info.locations.get(addr) {
+                return *location;
+            }
+        }
+        return None;
+    }
+
+    /// Get the source file name, if debug information is present
+    pub fn get_source_filename(&self) -> Option<&str> {
+        if let Some(debug_info) = &self.debug_info {
+            return Some(debug_info.filename.as_str());
+        }
+        return None;
+    }
+
+    /// Remove debug information from the module
+    pub fn strip_debug_info(&mut self) {
+        self.debug_info = None;
+    }
 }
 
 impl Default for Module {
@@ -11,20 +11,29 @@ pub enum Opcode {
     Call(),
     Vmcall(u8),
     Dynvmcall(),
-    LoadConstant(f64),  // load contant number
-    LoadNull(),         // load constant null
-    LoadBool(bool),     // load constant boolean
-    LoadStr(String),    // load constant string
-    LoadVar(String),    // load variable
-    StoreVar(String),   // store variable
-    PushRet(),          // push the ret register
-    Cafse(u64),         // construct array from stack elements
-    Iafs(),             // Index array or dictionary from stack
-    Cdfse(Vec<String>), // construct dictionary from stack elements
+    LoadConstant(f64),       // load contant number
+    LoadInt(i64),            // load integer
+    LoadNull(),              // load constant null
+    LoadBool(bool),          // load constant boolean
+    LoadStr(String),         // load constant string
+    LoadVar(String),         // load variable
+    StoreVar(String),        // store variable
+    PushRet(),               // push the ret register
+    Cafse(u64),              // construct array from stack elements
+    Iafs(),                  // Index array or dictionary from stack
+    Cdfse(Vec<String>),      // construct dictionary from stack elements
+    Csfse(u64),              // construct string from stack elements
+    MatchArray(u64, bool), // push if the value on stack is an array of the length, or at least of it if true
+    MatchDict(Vec<String>), // push if the value on stack is a dictionary with the keys
+    UnpackArray(u64, bool), // push the first elements of the array on stack, and an array of the rest if true
+    UnpackDict(Vec<String>), // push the values of the keys of the dictionary on stack
     Aiafs(),
     BeginArgs(),
     PushArg(),
     StoreArg(String),
+    LoadLocal(u16),       // load a local variable from a frame slot
+    StoreLocal(u16),      // store a local variable to a frame slot
+    StoreArgLocal(u16),   // store the next argument to a frame slot
     Pop(),                // pop from stack
     BranchTrue(usize),    // branch stack true (branch if stack value is true)
     BranchNonNull(usize), // branch stack non null
@@ -33,6 +42,9 @@ pub enum Opcode {
     Sub(),
     Mul(),
     Div(),
+    Mod(),
+    IntDiv(), // floor division
+    Pow(),
     Eq(),
     Neq(),
     Lt(),
@@ -43,6 +55,17 @@ pub enum Opcode {
     Bshl(),
     Band(),
     Bor(),
+    Bxor(),
+    Bnot(),
+    Not(),
+    Neg(),
+    Dup(), // duplicate the value on stack
     Ret(),
-    Lambda(usize, usize),
+    MakeRange(bool, bool), // make a range from the stack values, inclusive and with a step if true
+    IterBegin(),           // make an iterator from the value on stack
+    IterNext(usize, bool), // advance the iterator on stack, branch when done, push the key too if true
+    Lambda(usize, usize, Vec<String>), // address, arguments count and captured variables
+    TryBegin(usize),       // install an exception handler that jumps to the address
+    TryEnd(),              // remove the last exception handler
+    Throw(),               // throw the value on stack
 }
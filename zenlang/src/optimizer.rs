@@ -0,0 +1,295 @@
+//! Optimizer
+//!
+//! Bytecode optimization passes over a compiled module
+
+use crate::module::Module;
+use crate::opcode::Opcode;
+use crate::value::Value;
+use crate::vm::{floor_div, int_floor_div, int_modulo, int_pow, modulo};
+use alloc::vec;
+use alloc::vec::*;
+use libm::pow;
+
+/// Optimization level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptimizationLevel {
+    /// Keep the opcodes as emitted by the compiler
+    #[default]
+    None,
+    /// Constant folding, branch threading and push/pop removal
+    Basic,
+    /// Basic optimizations and unreachable code removal
+    Full,
+}
+
+impl Module {
+    /// Optimize the module opcodes, fixing up branch targets, function addresses and debug information
+    pub fn optimize(&mut self, level: OptimizationLevel) {
+        if level == OptimizationLevel::None {
+            return;
+        }
+
+        thread_branches(&mut self.opcodes);
+        loop {
+            let mut changed = false;
+            changed |=
+                self.run_pass(|module, removed| fold_constants(&mut module.opcodes, removed));
+            changed |= self.run_pass(|module, removed| remove_push_pop(&module.opcodes, removed));
+            if level >= OptimizationLevel::Full {
+                changed |= self.run_pass(remove_unreachable);
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Run a pass that marks opcodes to remove, returns if anything was removed
+    fn run_pass(&mut self, pass: impl FnOnce(&mut Module, &mut Vec<bool>)) -> bool {
+        let mut removed = vec![false; self.opcodes.len()];
+        pass(self, &mut removed);
+        if !removed.contains(&true) {
+            return false;
+        }
+        self.remove_opcodes(&removed);
+        return true;
+    }
+
+    /// Remove the marked opcodes, remapping every address to the next opcode left
+    fn remove_opcodes(&mut self, removed: &[bool]) {
+        let mut new_addrs = Vec::with_capacity(removed.len() + 1);
+        let mut new_addr = 0;
+        for is_removed in removed.iter() {
+            new_addrs.push(new_addr);
+            if !is_removed {
+                new_addr += 1;
+            }
+        }
+        new_addrs.push(new_addr);
+        let remap = |addr: &mut usize| {
+            *addr = new_addrs[(*addr).min(removed.len())];
+        };
+
+        let mut index = 0;
+        self.opcodes.retain(|_| {
+            index += 1;
+            return !removed[index - 1];
+        });
+        for opcode in self.opcodes.iter_mut() {
+            if let Some(addr) = get_target_mut(opcode) {
+                remap(addr);
+            }
+        }
+        for function in self.functions.iter_mut() {
+            remap(&mut function.addr);
+        }
+
+        if let Some(debug_info) = &mut self.debug_info {
+            debug_info.locations.resize(removed.len(), None);
+            let mut index = 0;
+            debug_info.locations.retain(|_| {
+                index += 1;
+                return !removed[index - 1];
+            });
+        }
+    }
+}
+
+/// Get the address an opcode jumps to or creates a lambda at
+fn get_target_mut(opcode: &mut Opcode) -> Option<&mut usize> {
+    return match opcode {
+        Opcode::Branch(addr)
+        | Opcode::BranchTrue(addr)
+        | Opcode::BranchNonNull(addr)
+        | Opcode::TryBegin(addr)
+        | Opcode::IterNext(addr, _)
+        | Opcode::Lambda(addr, _, _) => Some(addr),
+        _ => None,
+    };
+}
+
+fn get_target(opcode: &Opcode) -> Option<usize> {
+    return match opcode {
+        Opcode::Branch(addr)
+        | Opcode::BranchTrue(addr)
+        | Opcode::BranchNonNull(addr)
+        | Opcode::TryBegin(addr)
+        | Opcode::IterNext(addr, _)
+        | Opcode::Lambda(addr, _, _) => Some(*addr),
+        _ => None,
+    };
+}
+
+/// Mark the addresses that opcodes jump to, code can't be merged across them
+fn get_jump_targets(opcodes: &[Opcode]) -> Vec<bool> {
+    let mut targets = vec![false; opcodes.len() + 1];
+    for opcode in opcodes.iter() {
+        if let Some(addr) = get_target(opcode) {
+            if addr < targets.len() {
+                targets[addr] = true;
+            }
+        }
+    }
+    return targets;
+}
+
+/// Make branches that jump to another branch jump to its target directly
+fn thread_branches(opcodes: &mut Vec<Opcode>) {
+    for i in 0..opcodes.len() {
+        let mut addr = match &opcodes[i] {
+            Opcode::Branch(addr) | Opcode::BranchTrue(addr) | Opcode::BranchNonNull(addr) => *addr,
+            _ => continue,
+        };
+
+        // the number of hops is limited, so branches that loop forever are left alone
+        for _ in 0..opcodes.len() {
+            match opcodes.get(addr) {
+                Some(Opcode::Branch(next)) if *next != addr => addr = *next,
+                _ => break,
+            }
+        }
+
+        if let Some(target) = get_target_mut(&mut opcodes[i]) {
+            *target = addr;
+        }
+    }
+}
+
+/// Compute a binary operation on constants, None if it can't be done at compile time
+fn fold_binop(opcode: &Opcode, left: f64, right: f64) -> Option<f64> {
+    return match opcode {
+        Opcode::Add() => Some(left + right),
+        Opcode::Sub() => Some(left - right),
+        Opcode::Mul() => Some(left * right),
+        // division by zero is a runtime error
+        Opcode::Div() if right != 0.0 => Some(left / right),
+        Opcode::Mod() if right != 0.0 => Some(modulo(left, right)),
+        Opcode::IntDiv() if right != 0.0 => Some(floor_div(left, right)),
+        Opcode::Pow() => Some(pow(left, right)),
+        _ => None,
+    };
+}
+
+/// Compute a binary operation on integer constants, overflows are left to fail at runtime
+fn fold_int_binop(opcode: &Opcode, left: i64, right: i64) -> Option<Opcode> {
+    let result = match opcode {
+        Opcode::Add() => Value::Int(left.checked_add(right)?),
+        Opcode::Sub() => Value::Int(left.checked_sub(right)?),
+        Opcode::Mul() => Value::Int(left.checked_mul(right)?),
+        Opcode::Div() if right != 0 => Value::Number(left as f64 / right as f64),
+        Opcode::Mod() => Value::Int(int_modulo(left, right)?),
+        Opcode::IntDiv() => Value::Int(int_floor_div(left, right)?),
+        Opcode::Pow() => int_pow(left, right)?,
+        _ => return None,
+    };
+    return match result {
+        Value::Int(int) => Some(Opcode::LoadInt(int)),
+        Value::Number(num) => Some(Opcode::LoadConstant(num)),
+        _ => None,
+    };
+}
+
+/// Fold a binary operation on two constants of the same type
+fn fold_pair(left: &Opcode, right: &Opcode, opcode: &Opcode) -> Option<Opcode> {
+    return match (left, right) {
+        (Opcode::LoadConstant(left), Opcode::LoadConstant(right)) => {
+            fold_binop(opcode, *left, *right).map(Opcode::LoadConstant)
+        }
+        (Opcode::LoadInt(left), Opcode::LoadInt(right)) => fold_int_binop(opcode, *left, *right),
+        _ => None,
+    };
+}
+
+/// Replace two constants and a binary operation, or a constant and `Neg`, with the result
+fn fold_constants(opcodes: &mut Vec<Opcode>, removed: &mut Vec<bool>) {
+    let targets = get_jump_targets(opcodes);
+    let mut i = 0;
+    while i + 1 < opcodes.len() {
+        if targets[i + 1] {
+            i += 1;
+            continue;
+        }
+        let negated = match (&opcodes[i], &opcodes[i + 1]) {
+            (Opcode::LoadConstant(value), Opcode::Neg()) => Some(Opcode::LoadConstant(-*value)),
+            (Opcode::LoadInt(value), Opcode::Neg()) => value.checked_neg().map(Opcode::LoadInt),
+            _ => None,
+        };
+        if let Some(negated) = negated {
+            opcodes[i] = negated;
+            removed[i + 1] = true;
+            i += 2;
+            continue;
+        }
+        if i + 2 >= opcodes.len() || targets[i + 2] {
+            i += 1;
+            continue;
+        }
+        if let Some(result) = fold_pair(&opcodes[i], &opcodes[i + 1], &opcodes[i + 2]) {
+            opcodes[i] = result;
+            removed[i + 1] = true;
+            removed[i + 2] = true;
+            i += 3;
+            continue;
+        }
+        i += 1;
+    }
+}
+
+/// Remove values that are pushed only to be popped right away
+fn remove_push_pop(opcodes: &[Opcode], removed: &mut Vec<bool>) {
+    let targets = get_jump_targets(opcodes);
+    let mut i = 0;
+    while i + 1 < opcodes.len() {
+        let is_push = matches!(
+            opcodes[i],
+            Opcode::LoadConstant(_)
+                | Opcode::LoadInt(_)
+                | Opcode::LoadNull()
+                | Opcode::LoadBool(_)
+                | Opcode::LoadStr(_)
+                | Opcode::LoadVar(_)
+                | Opcode::LoadLocal(_)
+                | Opcode::PushRet()
+        );
+        if is_push && !targets[i + 1] && matches!(opcodes[i + 1], Opcode::Pop()) {
+            removed[i] = true;
+            removed[i + 1] = true;
+            i += 2;
+            continue;
+        }
+        i += 1;
+    }
+}
+
+/// Remove opcodes that can't be reached from any function, lambda or exception handler
+fn remove_unreachable(module: &mut Module, removed: &mut Vec<bool>) {
+    let opcodes = &module.opcodes;
+    let mut reachable = vec![false; opcodes.len()];
+    let mut pending: Vec<usize> = module
+        .functions
+        .iter()
+        .map(|function| function.addr)
+        .collect();
+
+    while let Some(addr) = pending.pop() {
+        if addr >= opcodes.len() || reachable[addr] {
+            continue;
+        }
+        reachable[addr] = true;
+
+        match &opcodes[addr] {
+            Opcode::Branch(target) => pending.push(*target),
+            Opcode::Ret() | Opcode::Throw() => {}
+            opcode => {
+                pending.push(addr + 1);
+                if let Some(target) = get_target(opcode) {
+                    pending.push(target);
+                }
+            }
+        }
+    }
+
+    for (addr, is_reachable) in reachable.iter().enumerate() {
+        removed[addr] = !is_reachable;
+    }
+}
@@ -8,7 +8,7 @@ impl Parser<'_> {
 
         let lb = self.next();
         if !matches!(lb, Token::Lbrace) {
-            return Err(error::Error::BlockLbrace(lb));
+            return Err(self.error(error::ErrorKind::BlockLbrace(lb)));
         }
 
         loop {
@@ -0,0 +1,125 @@
+use crate::ast::destructure::{AstBindingPattern, AstDestructure};
+use crate::ast::node::Compile;
+use crate::ast::var_ref::AstVarRef;
+use crate::parser::*;
+use crate::tokenizer::Token;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+impl Parser<'_> {
+    /// Parse `[a, b, ...rest]`, `{"key" = name}` or a name, starting with `token`
+    pub(crate) fn parse_binding_pattern(
+        &mut self,
+        token: Token,
+    ) -> Result<AstBindingPattern, error::Error> {
+        match token {
+            Token::Identifier(name) => Ok(AstBindingPattern::Name(name)),
+            Token::Lbracket => {
+                let mut items = Vec::new();
+                let mut rest = None;
+                loop {
+                    let token = self.next();
+                    match token {
+                        Token::Rbracket => break,
+                        Token::Ellipsis => {
+                            let name = self.next();
+                            rest = match name {
+                                Token::Identifier(name) => Some(name),
+                                _ => {
+                                    return Err(self.error(error::ErrorKind::DestructureName(name)));
+                                }
+                            };
+                            // the rest is always last
+                            let rb = self.next();
+                            if !matches!(rb, Token::Rbracket) {
+                                return Err(self.error(error::ErrorKind::DestructureRbracket(rb)));
+                            }
+                            break;
+                        }
+                        _ => items.push(self.parse_binding_pattern(token)?),
+                    }
+
+                    let token = self.next();
+                    match token {
+                        Token::Comma => continue,
+                        Token::Rbracket => break,
+                        _ => return Err(self.error(error::ErrorKind::DestructureRbracket(token))),
+                    }
+                }
+                Ok(AstBindingPattern::Array(items, rest))
+            }
+            Token::Lbrace => {
+                let mut fields = Vec::new();
+                loop {
+                    let token = self.next();
+                    let key = match token {
+                        Token::Rbrace => break,
+                        Token::String(key) => key,
+                        _ => return Err(self.error(error::ErrorKind::DictStrKey(token))),
+                    };
+
+                    let eq = self.next();
+                    if !matches!(eq, Token::Assign) {
+                        return Err(self.error(error::ErrorKind::DictEqual(eq)));
+                    }
+                    let token = self.next();
+                    fields.push((key, self.parse_binding_pattern(token)?));
+
+                    let token = self.next();
+                    match token {
+                        Token::Comma => continue,
+                        Token::Rbrace => break,
+                        _ => return Err(self.error(error::ErrorKind::DestructureRbrace(token))),
+                    }
+                }
+                Ok(AstBindingPattern::Dictionary(fields))
+            }
+            _ => Err(self.error(error::ErrorKind::DestructureName(token))),
+        }
+    }
+
+    /// Parse `let pattern = expr` after the `let`
+    pub(crate) fn parse_destructure(&mut self) -> Result<AstDestructure, error::Error> {
+        let token = self.next();
+        let mut node = AstDestructure::new(self.parse_binding_pattern(token)?);
+
+        let eq = self.next();
+        if !matches!(eq, Token::Assign) {
+            return Err(self.error(error::ErrorKind::LetExpectedAssign(eq)));
+        }
+        node.expr = Some(self.parse_expression()?);
+
+        Ok(node)
+    }
+
+    /// Check if the `{` just read starts a dictionary pattern and not a block
+    pub(crate) fn is_dict_pattern(&mut self) -> bool {
+        let key = self.next();
+        let eq = self.next();
+        self.back();
+        self.back();
+        return matches!((key, eq), (Token::String(_), Token::Assign));
+    }
+
+    /// Parse a name or a pattern starting with `token`, None if it's neither
+    ///
+    /// A pattern is stored in `hidden` and destructured by a statement added to `prologue`
+    pub(crate) fn parse_binding_name(
+        &mut self,
+        token: Token,
+        hidden: String,
+        prologue: &mut Vec<Box<dyn Compile>>,
+    ) -> Result<Option<String>, error::Error> {
+        match token {
+            Token::Identifier(name) => return Ok(Some(name)),
+            Token::Lbracket | Token::Lbrace => {}
+            _ => return Ok(None),
+        }
+
+        let mut node = AstDestructure::new(self.parse_binding_pattern(token)?);
+        node.expr = Some(Box::new(AstVarRef::new(hidden.clone())));
+        prologue.push(Box::new(node));
+        Ok(Some(hidden))
+    }
+}
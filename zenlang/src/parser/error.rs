@@ -1,12 +1,15 @@
-use crate::tokenizer::Token;
+use crate::tokenizer::{Span, Token};
 use alloc::string::String;
 use alloc::{format, string::ToString};
 
-pub enum Error {
+/// Parser error kind
+pub enum ErrorKind {
     UnexpectedGlobalScopeToken(Token),
     FunctionSyntaxName(Token),
     FunctionSyntaxArg(Token),
     FunctionSyntaxBraces(),
+    FunctionRestLast(Token),
+    FunctionDefaultMissing(String),
     StatementSyntax(Token),
     StatementSemicolon(Token),
     LetNameSyntax(Token),
@@ -28,11 +31,77 @@ pub enum Error {
     IfLetIdent(Token),
     IfLetEq(Token),
     ModIdentifier(Token),
+    PubExpectedFn(Token),
+    UseModule(Token),
+    UseDoubleColon(Token),
+    UseName(Token),
+    QualifiedName(Token),
     LambdaArgIdent(Token),
     ExprUnexpectedPrimary(Token),
+    TryExpectedCatch(Token),
+    ForIdent(Token),
+    ForExpectedIn(Token),
+    FormatStringEnd(Token),
+    FormatStringUnterminated(),
+    MatchLbrace(Token),
+    MatchPattern(Token),
+    MatchArrow(Token),
+    MatchComma(Token),
+    DestructureName(Token),
+    DestructureRbracket(Token),
+    DestructureRbrace(Token),
+}
+
+/// Parser error
+///
+/// Contains the error kind and where in the source code it happened
+pub struct Error {
+    pub kind: ErrorKind,
+    /// Span of the offending token
+    pub span: Span,
+    /// Name of the file being parsed
+    pub filename: String,
+    /// Text of the line containing the offending token
+    pub line_text: String,
 }
 
 impl ToString for Error {
+    fn to_string(&self) -> alloc::string::String {
+        let filename = if self.filename.is_empty() {
+            "<unknown>"
+        } else {
+            self.filename.as_str()
+        };
+        let line_number = format!("{}", self.span.line);
+        let padding = " ".repeat(line_number.len());
+
+        // caret under the offending token, at least one character wide
+        let prefix: String = self
+            .line_text
+            .chars()
+            .take(self.span.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(self.span.len.max(1));
+
+        return format!(
+            "{}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.kind.to_string(),
+            padding,
+            filename,
+            self.span.line,
+            self.span.column,
+            padding,
+            line_number,
+            self.line_text,
+            padding,
+            prefix,
+            carets
+        );
+    }
+}
+
+impl ToString for ErrorKind {
     fn to_string(&self) -> alloc::string::String {
         return match self {
             Self::UnexpectedGlobalScopeToken(token) => {
@@ -50,6 +119,18 @@ impl ToString for Error {
                     token
                 )
             }
+            Self::FunctionRestLast(token) => {
+                format!(
+                    "Expected `{{` after the rest parameter of a function, but got {:?}",
+                    token
+                )
+            }
+            Self::FunctionDefaultMissing(name) => {
+                format!(
+                    "Parameter {} needs a default, because a parameter before it has one",
+                    name
+                )
+            }
             Self::StatementSyntax(token) => {
                 format!("Unexpected {:?} where a statement should be", token)
             }
@@ -134,6 +215,30 @@ impl ToString for Error {
             Self::ModIdentifier(token) => {
                 format!("Expected identifier after global mod, but got {:?}", token)
             }
+            Self::PubExpectedFn(token) => {
+                format!("Expected `fn` after pub, but got {:?}", token)
+            }
+            Self::UseModule(token) => {
+                format!("Expected a module name after use, but got {:?}", token)
+            }
+            Self::UseDoubleColon(token) => {
+                format!(
+                    "Expected `::` after the module name in use, but got {:?}",
+                    token
+                )
+            }
+            Self::UseName(token) => {
+                format!(
+                    "Expected a function name to import in use, but got {:?}",
+                    token
+                )
+            }
+            Self::QualifiedName(token) => {
+                format!(
+                    "Expected a function name after the module name, but got {:?}",
+                    token
+                )
+            }
             Self::LambdaArgIdent(token) => {
                 format!(
                     "Expected identifier as an argument for a lambda, but got {:?}",
@@ -143,6 +248,63 @@ impl ToString for Error {
             Self::ExprUnexpectedPrimary(token) => {
                 format!("Unexpected token for a primary expression - {:?}", token)
             }
+            Self::TryExpectedCatch(token) => {
+                format!("Expected `catch` after a try block, but got {:?}", token)
+            }
+            Self::ForIdent(token) => {
+                format!(
+                    "Expected identifier as a for loop variable, but got {:?}",
+                    token
+                )
+            }
+            Self::ForExpectedIn(token) => {
+                format!(
+                    "Expected `in` after for loop variables, but got {:?}",
+                    token
+                )
+            }
+            Self::FormatStringUnterminated() => {
+                format!("Unterminated `{{` in string")
+            }
+            Self::FormatStringEnd(token) => {
+                format!(
+                    "Expected `}}` after an interpolated expression, but got {:?}",
+                    token
+                )
+            }
+            Self::MatchLbrace(token) => {
+                format!("Expected `{{` after the matched value, but got {:?}", token)
+            }
+            Self::MatchPattern(token) => {
+                format!("Expected a pattern, but got {:?}", token)
+            }
+            Self::MatchArrow(token) => {
+                format!("Expected `=>` after a match pattern, but got {:?}", token)
+            }
+            Self::MatchComma(token) => {
+                format!(
+                    "Expected `,` between match arms or pattern elements, but got {:?}",
+                    token
+                )
+            }
+            Self::DestructureName(token) => {
+                format!(
+                    "Expected a name, `[` or `{{` in a destructuring pattern, but got {:?}",
+                    token
+                )
+            }
+            Self::DestructureRbracket(token) => {
+                format!(
+                    "Expected `,` or `]` in an array pattern, but got {:?}",
+                    token
+                )
+            }
+            Self::DestructureRbrace(token) => {
+                format!(
+                    "Expected `,` or `}}` in a dictionary pattern, but got {:?}",
+                    token
+                )
+            }
         };
     }
 }
@@ -3,16 +3,23 @@ use crate::ast::array_index::AstArrayIndex;
 use crate::ast::binop::{AstBinop, AstBinopOp};
 use crate::ast::boolean::AstBoolean;
 use crate::ast::dict::AstDict;
+use crate::ast::format_string::AstFormatString;
 use crate::ast::func_call::AstFuncCall;
+use crate::ast::int::AstInt;
 use crate::ast::lambda::AstLambda;
-use crate::ast::node::CompileStatementExpression;
+use crate::ast::logical::{AstLogical, AstLogicalOp};
+use crate::ast::node::{Compile, CompileStatementExpression};
 use crate::ast::null::AstNull;
 use crate::ast::number::AstNumber;
+use crate::ast::range::AstRange;
 use crate::ast::string::AstString;
+use crate::ast::unary::{AstUnary, AstUnaryOp};
 use crate::ast::var_ref::AstVarRef;
 use crate::parser::*;
-use crate::tokenizer::Token;
+use crate::tokenizer::{FormatPart, Token, Tokenizer};
 use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
 
 impl Parser<'_> {
     pub(crate) fn parse_primary(
@@ -21,6 +28,17 @@ impl Parser<'_> {
         let token = self.next();
         match token {
             Token::Identifier(ident) => {
+                // `net.connect` is the function connect of the module net
+                if self.namespaces.contains(&ident) && matches!(self.peek(), Token::Dot) {
+                    self.next();
+                    let name = self.next();
+                    let name = match name {
+                        Token::Identifier(name) => name,
+                        _ => return Err(self.error(error::ErrorKind::QualifiedName(name))),
+                    };
+                    return Ok(Box::new(AstVarRef::new(format!("{}::{}", ident, name))));
+                }
+
                 let node = Box::new(AstVarRef::new(ident));
 
                 Ok(node)
@@ -29,10 +47,25 @@ impl Parser<'_> {
                 let node = Box::new(AstNumber::new(number));
                 Ok(node)
             }
+            Token::Int(int) => {
+                let node = Box::new(AstInt::new(int));
+                Ok(node)
+            }
             Token::String(str) => {
                 let node = Box::new(AstString::new(str));
                 Ok(node)
             }
+            Token::Match => {
+                let node = Box::new(self.parse_match()?);
+                Ok(node)
+            }
+            Token::FormatString(parts) => {
+                let node = Box::new(self.parse_format_string(parts)?);
+                Ok(node)
+            }
+            Token::UnterminatedFormatString => {
+                Err(self.error(error::ErrorKind::FormatStringUnterminated()))
+            }
             Token::Null => {
                 let node = Box::new(AstNull::new());
                 Ok(node)
@@ -81,12 +114,12 @@ impl Parser<'_> {
 
                     let key = match token {
                         Token::String(key) => key,
-                        _ => return Err(error::Error::DictStrKey(token)),
+                        _ => return Err(self.error(error::ErrorKind::DictStrKey(token))),
                     };
 
                     let eq = self.next();
                     if !matches!(eq, Token::Assign) {
-                        return Err(error::Error::DictEqual(eq));
+                        return Err(self.error(error::ErrorKind::DictEqual(eq)));
                     }
 
                     let value = self.parse_expression()?;
@@ -105,23 +138,29 @@ impl Parser<'_> {
             }
             Token::Fn => {
                 let mut lambda = AstLambda::new();
+                let mut prologue: Vec<Box<dyn Compile>> = Vec::new();
 
                 let mut token;
                 loop {
                     token = self.next();
-                    let name = match token {
-                        Token::Lbrace => {
-                            self.back();
-                            break;
-                        }
-                        Token::Identifier(ident) => ident,
-                        _ => return Err(error::Error::LambdaArgIdent(token)),
-                    };
+                    if matches!(token, Token::Lbrace) && !self.is_dict_pattern() {
+                        self.back();
+                        break;
+                    }
+
+                    let hidden = format!("#arg{}", lambda.args.len());
+                    let name =
+                        match self.parse_binding_name(token.clone(), hidden, &mut prologue)? {
+                            Some(name) => name,
+                            None => return Err(self.error(error::ErrorKind::LambdaArgIdent(token))),
+                        };
 
                     lambda.args.push(name);
                 }
 
                 lambda.block = self.parse_block()?;
+                prologue.append(&mut lambda.block.children);
+                lambda.block.children = prologue;
 
                 Ok(Box::new(lambda))
             }
@@ -129,12 +168,12 @@ impl Parser<'_> {
                 let expr = self.parse_expression()?;
                 let rp = self.next();
                 if !matches!(rp, Token::Rparen) {
-                    return Err(error::Error::ExprRparen(rp));
+                    return Err(self.error(error::ErrorKind::ExprRparen(rp)));
                 }
 
                 Ok(expr)
             }
-            _ => return Err(error::Error::ExprUnexpectedPrimary(token)),
+            _ => return Err(self.error(error::ErrorKind::ExprUnexpectedPrimary(token))),
         }
     }
 
@@ -163,7 +202,11 @@ impl Parser<'_> {
                         match comma {
                             Token::Comma => (),
                             Token::Rparen => self.back(),
-                            _ => return Err(error::Error::FunccallExpectedComma(comma)),
+                            _ => {
+                                return Err(
+                                    self.error(error::ErrorKind::FunccallExpectedComma(comma))
+                                );
+                            }
                         }
                     }
 
@@ -175,7 +218,7 @@ impl Parser<'_> {
 
                     let rb = self.next();
                     if !matches!(rb, Token::Rbracket) {
-                        return Err(error::Error::ArrayIndexRbracket(rb));
+                        return Err(self.error(error::ErrorKind::ArrayIndexRbracket(rb)));
                     }
 
                     index
@@ -185,8 +228,9 @@ impl Parser<'_> {
                     let v = self.next();
                     let v: Box<dyn CompileStatementExpression> = match v {
                         Token::Number(num) => Box::new(AstNumber::new(num)),
+                        Token::Int(int) => Box::new(AstInt::new(int)),
                         Token::Identifier(ident) => Box::new(AstString::new(ident)),
-                        _ => return Err(error::Error::IndexDotSyntax(v)),
+                        _ => return Err(self.error(error::ErrorKind::IndexDotSyntax(v))),
                     };
 
                     let index = Box::new(AstArrayIndex::new(left, v));
@@ -202,10 +246,46 @@ impl Parser<'_> {
         Ok(left)
     }
 
+    pub(crate) fn parse_power(
+        &mut self,
+    ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
+        let left = self.parse_postfix()?;
+
+        let token = self.next();
+        if !matches!(token, Token::DoubleOperator('*', '*')) {
+            self.back();
+            return Ok(left);
+        }
+
+        // right associative, binds tighter than a unary operator on its left
+        let right = self.parse_unary()?;
+
+        Ok(Box::new(AstBinop::new(left, AstBinopOp::POW, right)))
+    }
+
+    pub(crate) fn parse_unary(
+        &mut self,
+    ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
+        let token = self.next();
+        let op = match token {
+            Token::Operator('!') => AstUnaryOp::NOT,
+            Token::Operator('-') => AstUnaryOp::NEG,
+            Token::Operator('~') => AstUnaryOp::BITNOT,
+            _ => {
+                self.back();
+                return self.parse_power();
+            }
+        };
+
+        let value = self.parse_unary()?;
+
+        Ok(Box::new(AstUnary::new(op, value)))
+    }
+
     pub(crate) fn parse_multiplicative(
         &mut self,
     ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
-        let mut left = self.parse_postfix()?;
+        let mut left = self.parse_unary()?;
 
         let mut token;
         loop {
@@ -213,13 +293,15 @@ impl Parser<'_> {
             let op = match token {
                 Token::Operator('*') => AstBinopOp::MUL,
                 Token::Operator('/') => AstBinopOp::DIV,
+                Token::Operator('%') => AstBinopOp::MOD,
+                Token::DoubleOperator('~', '/') => AstBinopOp::INTDIV,
                 _ => {
                     self.back();
                     break;
                 }
             };
 
-            let right = self.parse_postfix()?;
+            let right = self.parse_unary()?;
 
             left = Box::new(AstBinop::new(left, op, right));
         }
@@ -338,7 +420,7 @@ impl Parser<'_> {
         loop {
             token = self.next();
             let op = match token {
-                Token::BitOperator('&', '&') => AstBinopOp::BITAND,
+                Token::Operator('&') => AstBinopOp::BITAND,
                 _ => {
                     self.back();
                     break;
@@ -353,7 +435,7 @@ impl Parser<'_> {
         Ok(left)
     }
 
-    pub(crate) fn parse_bitor(
+    pub(crate) fn parse_bitxor(
         &mut self,
     ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
         let mut left = self.parse_bitand()?;
@@ -362,7 +444,7 @@ impl Parser<'_> {
         loop {
             token = self.next();
             let op = match token {
-                Token::BitOperator('|', '|') => AstBinopOp::BITOR,
+                Token::Operator('^') => AstBinopOp::BITXOR,
                 _ => {
                     self.back();
                     break;
@@ -377,9 +459,136 @@ impl Parser<'_> {
         Ok(left)
     }
 
+    pub(crate) fn parse_bitor(
+        &mut self,
+    ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
+        let mut left = self.parse_bitxor()?;
+
+        let mut token;
+        loop {
+            token = self.next();
+            let op = match token {
+                Token::Operator('|') => AstBinopOp::BITOR,
+                _ => {
+                    self.back();
+                    break;
+                }
+            };
+
+            let right = self.parse_bitxor()?;
+
+            left = Box::new(AstBinop::new(left, op, right));
+        }
+
+        Ok(left)
+    }
+
+    pub(crate) fn parse_logical_and(
+        &mut self,
+    ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
+        let mut left = self.parse_bitor()?;
+
+        let mut token;
+        loop {
+            token = self.next();
+            if !matches!(token, Token::LogicalOperator('&', '&')) {
+                self.back();
+                break;
+            }
+
+            let right = self.parse_bitor()?;
+
+            left = Box::new(AstLogical::new(left, AstLogicalOp::AND, right));
+        }
+
+        Ok(left)
+    }
+
+    pub(crate) fn parse_logical_or(
+        &mut self,
+    ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
+        let mut left = self.parse_logical_and()?;
+
+        let mut token;
+        loop {
+            token = self.next();
+            if !matches!(token, Token::LogicalOperator('|', '|')) {
+                self.back();
+                break;
+            }
+
+            let right = self.parse_logical_and()?;
+
+            left = Box::new(AstLogical::new(left, AstLogicalOp::OR, right));
+        }
+
+        Ok(left)
+    }
+
+    pub(crate) fn parse_range(
+        &mut self,
+    ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
+        let left = self.parse_logical_or()?;
+
+        let token = self.next();
+        let inclusive = match token {
+            Token::Range(inclusive) => inclusive,
+            _ => {
+                self.back();
+                return Ok(left);
+            }
+        };
+
+        let right = self.parse_logical_or()?;
+        let mut range = AstRange::new(left, right, inclusive);
+
+        // `step` is only a keyword after a range, so it can still be used as a name
+        match self.next() {
+            Token::Identifier(ident) if ident == "step" => {
+                range.step = Some(self.parse_logical_or()?);
+            }
+            _ => self.back(),
+        }
+
+        Ok(Box::new(range))
+    }
+
+    /// Parse the expressions embedded in an interpolated string, each with a tokenizer of its own
+    fn parse_format_string(
+        &mut self,
+        parts: Vec<FormatPart>,
+    ) -> Result<AstFormatString, error::Error> {
+        let mut node = AstFormatString::new();
+        for part in parts {
+            match part {
+                FormatPart::Literal(text) => {
+                    if !text.is_empty() {
+                        node.parts.push(Box::new(AstString::new(text)));
+                    }
+                }
+                FormatPart::Expression(code) => {
+                    let mut tokenizer = Tokenizer::new(code);
+                    let mut parser = Parser::new(&mut tokenizer);
+                    parser.namespaces = self.namespaces.clone();
+                    // errors are located at the string, the expression has no location of its own
+                    let expr = parser
+                        .parse_expression()
+                        .map_err(|error| self.error(error.kind))?;
+                    let end = parser.next();
+                    if !matches!(end, Token::EOF) {
+                        return Err(self.error(error::ErrorKind::FormatStringEnd(end)));
+                    }
+                    node.parts.push(expr);
+                }
+            }
+        }
+
+        Ok(node)
+    }
+
     pub(crate) fn parse_expression(
         &mut self,
     ) -> Result<Box<dyn CompileStatementExpression>, error::Error> {
-        return self.parse_bitor();
+        return self.parse_range();
     }
 }
@@ -0,0 +1,35 @@
+use crate::ast::for_stmt::AstForStmt;
+use crate::parser::*;
+use crate::tokenizer::Token;
+
+impl Parser<'_> {
+    pub(crate) fn parse_for(&mut self) -> Result<AstForStmt, error::Error> {
+        let mut node = AstForStmt::new();
+
+        let name = self.next();
+        node.value_name = match name {
+            Token::Identifier(name) => name,
+            _ => return Err(self.error(error::ErrorKind::ForIdent(name))),
+        };
+
+        let mut token = self.next();
+        if matches!(token, Token::Comma) {
+            let name = self.next();
+            let value_name = match name {
+                Token::Identifier(name) => name,
+                _ => return Err(self.error(error::ErrorKind::ForIdent(name))),
+            };
+            node.key_name = Some(core::mem::replace(&mut node.value_name, value_name));
+            token = self.next();
+        }
+
+        if !matches!(token, Token::In) {
+            return Err(self.error(error::ErrorKind::ForExpectedIn(token)));
+        }
+
+        node.value = Some(self.parse_expression()?);
+        node.body = self.parse_block()?;
+
+        Ok(node)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::ast::function::AstFunction;
 use crate::tokenizer::Token;
 use crate::{FunctionAttribute, parser::*};
+use alloc::format;
 
 impl Parser<'_> {
     pub(crate) fn parse_function(&mut self) -> Result<AstFunction, error::Error> {
@@ -10,7 +11,7 @@ impl Parser<'_> {
             Token::Hashtag => {
                 let lb = self.next();
                 if !matches!(lb, Token::Lbracket) {
-                    return Err(error::Error::FunctionSyntaxHashtagBracket(lb));
+                    return Err(self.error(error::ErrorKind::FunctionSyntaxHashtagBracket(lb)));
                 }
 
                 let mut token = self.next();
@@ -18,14 +19,18 @@ impl Parser<'_> {
                     let name = match token {
                         Token::Identifier(ident) => ident,
                         Token::Rbracket => break,
-                        _ => return Err(error::Error::AttributeExpectedIdentifier(token)),
+                        _ => {
+                            return Err(
+                                self.error(error::ErrorKind::AttributeExpectedIdentifier(token))
+                            );
+                        }
                     };
 
                     let attr = FunctionAttribute::map(&name);
 
                     func.attrs.push(match attr {
                         Some(attr) => attr,
-                        None => return Err(error::Error::UnknownAttribute(name)),
+                        None => return Err(self.error(error::ErrorKind::UnknownAttribute(name))),
                     });
 
                     token = self.next();
@@ -41,22 +46,44 @@ impl Parser<'_> {
         let name = self.next();
         let name = match name {
             Token::Identifier(ident) => ident,
-            _ => return Err(error::Error::FunctionSyntaxName(name)),
+            _ => return Err(self.error(error::ErrorKind::FunctionSyntaxName(name))),
         };
 
         func.name = name;
 
-        // Parse arguments
+        // Parse arguments, destructured ones are unpacked at the start of the body
         loop {
             let token = self.next();
-            if matches!(token, Token::Lbrace) {
+            if matches!(token, Token::Lbrace) && !self.is_dict_pattern() {
                 break;
             }
+            if func.variadic {
+                return Err(self.error(error::ErrorKind::FunctionRestLast(token)));
+            }
+            if matches!(token, Token::Ellipsis) {
+                let name = self.next();
+                match name {
+                    Token::Identifier(name) => func.args.push(name),
+                    _ => return Err(self.error(error::ErrorKind::FunctionSyntaxArg(name))),
+                }
+                func.variadic = true;
+                continue;
+            }
 
-            let arg = match token {
-                Token::Identifier(ident) => ident,
-                _ => return Err(error::Error::FunctionSyntaxArg(token)),
-            };
+            let hidden = format!("#arg{}", func.args.len());
+            let arg =
+                match self.parse_binding_name(token.clone(), hidden, &mut func.block.children)? {
+                    Some(arg) => arg,
+                    None => return Err(self.error(error::ErrorKind::FunctionSyntaxArg(token))),
+                };
+
+            // `name = expr` has a default, the parameters after it need one too
+            if matches!(self.peek(), Token::Assign) {
+                self.next();
+                func.defaults.push(self.parse_expression()?);
+            } else if !func.defaults.is_empty() {
+                return Err(self.error(error::ErrorKind::FunctionDefaultMissing(arg)));
+            }
 
             func.args.push(arg);
         }
@@ -2,28 +2,34 @@ use crate::ast::elif_stmt::AstElifStmt;
 use crate::ast::else_stmt::AstElseStmt;
 use crate::ast::if_chain::AstIfChain;
 use crate::ast::if_stmt::AstIfStmt;
+use crate::ast::node::Compile;
 use crate::parser::*;
 use crate::tokenizer::Token;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 impl Parser<'_> {
     pub(crate) fn parse_if_chain(&mut self) -> Result<AstIfChain, error::Error> {
         let mut chain = AstIfChain::new();
         let mut if_stmt = AstIfStmt::new();
+        // destructures a pattern of `if let` at the start of the block
+        let mut prologue: Vec<Box<dyn Compile>> = Vec::new();
 
         match self.next() {
             Token::Let => {
                 let name = self.next();
-                let name = match name {
-                    Token::Identifier(name) => name,
-                    _ => return Err(error::Error::IfLetIdent(name)),
-                };
+                let name =
+                    match self.parse_binding_name(name.clone(), "#iflet".into(), &mut prologue)? {
+                        Some(name) => name,
+                        None => return Err(self.error(error::ErrorKind::IfLetIdent(name))),
+                    };
 
                 if_stmt.if_let = true;
                 if_stmt.if_let_name = name;
 
                 let eq = self.next();
                 if !matches!(eq, Token::Assign) {
-                    return Err(error::Error::IfLetEq(eq));
+                    return Err(self.error(error::ErrorKind::IfLetEq(eq)));
                 }
 
                 if_stmt.if_let_expr = Some(self.parse_expression()?);
@@ -35,19 +41,26 @@ impl Parser<'_> {
         }
 
         if_stmt.block = self.parse_block()?;
+        prologue.append(&mut if_stmt.block.children);
+        if_stmt.block.children = prologue;
         chain.head = Some(if_stmt);
 
         loop {
             match self.next() {
                 Token::Elif => {
                     let mut stmt = AstElifStmt::new();
+                    let mut prologue: Vec<Box<dyn Compile>> = Vec::new();
 
                     match self.next() {
                         Token::Let => {
                             let name = self.next();
-                            let name = match name {
-                                Token::Identifier(name) => name,
-                                _ => return Err(error::Error::IfLetIdent(name)),
+                            let name = match self.parse_binding_name(
+                                name.clone(),
+                                "#iflet".into(),
+                                &mut prologue,
+                            )? {
+                                Some(name) => name,
+                                None => return Err(self.error(error::ErrorKind::IfLetIdent(name))),
                             };
 
                             stmt.elif_let = true;
@@ -55,7 +68,7 @@ impl Parser<'_> {
 
                             let eq = self.next();
                             if !matches!(eq, Token::Assign) {
-                                return Err(error::Error::IfLetEq(eq));
+                                return Err(self.error(error::ErrorKind::IfLetEq(eq)));
                             }
 
                             stmt.elif_let_expr = Some(self.parse_expression()?);
@@ -66,6 +79,8 @@ impl Parser<'_> {
                         }
                     }
                     stmt.block = self.parse_block()?;
+                    prologue.append(&mut stmt.block.children);
+                    stmt.block.children = prologue;
 
                     chain.elifs.push(stmt);
                 }
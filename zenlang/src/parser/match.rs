@@ -0,0 +1,159 @@
+use crate::ast::boolean::AstBoolean;
+use crate::ast::int::AstInt;
+use crate::ast::match_expr::{AstMatch, AstMatchArm, AstMatchBody, AstPattern};
+use crate::ast::node::Compile;
+use crate::ast::null::AstNull;
+use crate::ast::number::AstNumber;
+use crate::ast::string::AstString;
+use crate::parser::*;
+use crate::tokenizer::Token;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+impl Parser<'_> {
+    pub(crate) fn parse_match(&mut self) -> Result<AstMatch, error::Error> {
+        let mut node = AstMatch::new(self.tokenizer.span());
+        node.value = Some(self.parse_expression()?);
+
+        let lb = self.next();
+        if !matches!(lb, Token::Lbrace) {
+            return Err(self.error(error::ErrorKind::MatchLbrace(lb)));
+        }
+
+        loop {
+            let token = self.next();
+            if matches!(token, Token::Rbrace) {
+                break;
+            }
+            self.back();
+
+            let pattern = self.parse_pattern()?;
+            let mut guard: Option<Box<dyn Compile>> = None;
+            let mut token = self.next();
+            if matches!(token, Token::If) {
+                guard = Some(self.parse_expression()?);
+                token = self.next();
+            }
+            if !matches!(token, Token::FatArrow) {
+                return Err(self.error(error::ErrorKind::MatchArrow(token)));
+            }
+
+            let body = if matches!(self.next(), Token::Lbrace) {
+                self.back();
+                AstMatchBody::Block(self.parse_block()?)
+            } else {
+                self.back();
+                AstMatchBody::Expression(self.parse_expression()?)
+            };
+            let is_block = matches!(body, AstMatchBody::Block(_));
+            node.arms.push(AstMatchArm {
+                pattern: pattern,
+                guard: guard,
+                body: body,
+            });
+
+            // the comma is optional after a block
+            let token = self.next();
+            match token {
+                Token::Comma => {}
+                Token::Rbrace => break,
+                _ if is_block => self.back(),
+                _ => return Err(self.error(error::ErrorKind::MatchComma(token))),
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn parse_pattern(&mut self) -> Result<AstPattern, error::Error> {
+        let token = self.next();
+        let pattern = match token {
+            Token::Identifier(name) if name == "_" => AstPattern::Wildcard,
+            Token::Identifier(name) => {
+                if matches!(self.next(), Token::Lbrace) {
+                    self.parse_dict_pattern(Some(name))?
+                } else {
+                    self.back();
+                    AstPattern::Binding(name)
+                }
+            }
+            Token::Int(int) => AstPattern::Literal(Box::new(AstInt::new(int))),
+            Token::Number(number) => AstPattern::Literal(Box::new(AstNumber::new(number))),
+            Token::String(string) => AstPattern::Literal(Box::new(AstString::new(string))),
+            Token::True => AstPattern::Literal(Box::new(AstBoolean::new(true))),
+            Token::False => AstPattern::Literal(Box::new(AstBoolean::new(false))),
+            Token::Null => AstPattern::Literal(Box::new(AstNull::new())),
+            Token::Operator('-') => {
+                let token = self.next();
+                match token {
+                    Token::Int(int) => AstPattern::Literal(Box::new(AstInt::new(-int))),
+                    Token::Number(number) => AstPattern::Literal(Box::new(AstNumber::new(-number))),
+                    _ => return Err(self.error(error::ErrorKind::MatchPattern(token))),
+                }
+            }
+            Token::Lbracket => {
+                let mut items = Vec::new();
+                let mut rest = false;
+                loop {
+                    let token = self.next();
+                    match token {
+                        Token::Rbracket => break,
+                        // `..` is only allowed last
+                        Token::Range(false) => {
+                            rest = true;
+                            let rb = self.next();
+                            if !matches!(rb, Token::Rbracket) {
+                                return Err(self.error(error::ErrorKind::ArrayIndexRbracket(rb)));
+                            }
+                            break;
+                        }
+                        _ => self.back(),
+                    }
+
+                    items.push(self.parse_pattern()?);
+
+                    let token = self.next();
+                    match token {
+                        Token::Comma => continue,
+                        Token::Rbracket => break,
+                        _ => return Err(self.error(error::ErrorKind::MatchComma(token))),
+                    }
+                }
+                AstPattern::Array(items, rest)
+            }
+            Token::Lbrace => self.parse_dict_pattern(None)?,
+            _ => return Err(self.error(error::ErrorKind::MatchPattern(token))),
+        };
+
+        Ok(pattern)
+    }
+
+    /// Parse `{"key" = pattern, ...}` after the `{`
+    fn parse_dict_pattern(&mut self, typename: Option<String>) -> Result<AstPattern, error::Error> {
+        let mut fields = Vec::new();
+        loop {
+            let token = self.next();
+            let key = match token {
+                Token::Rbrace => break,
+                Token::String(key) => key,
+                _ => return Err(self.error(error::ErrorKind::DictStrKey(token))),
+            };
+
+            let eq = self.next();
+            if !matches!(eq, Token::Assign) {
+                return Err(self.error(error::ErrorKind::DictEqual(eq)));
+            }
+            fields.push((key, self.parse_pattern()?));
+
+            let token = self.next();
+            match token {
+                Token::Comma => continue,
+                Token::Rbrace => break,
+                _ => return Err(self.error(error::ErrorKind::MatchComma(token))),
+            }
+        }
+
+        Ok(AstPattern::Dictionary(typename, fields))
+    }
+}
@@ -2,11 +2,16 @@
 //!
 //! Contains a parser for generating AST
 mod block;
+mod destructure;
 pub mod error;
 mod expression;
+mod r#for;
 mod func;
 mod if_chain;
+mod r#match;
 mod parser;
 mod statement;
+mod try_catch;
+mod r#use;
 mod r#while;
 pub use parser::*;
@@ -1,6 +1,9 @@
 use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use crate::ast::global_var::AstGlobalVar;
+use crate::ast::located::AstLocated;
 use crate::ast::mod_stmt::AstMod;
 use crate::ast::node::Compile;
 use crate::ast::*;
@@ -10,6 +13,8 @@ use crate::tokenizer::*;
 pub struct Parser<'a> {
     pub root: root::AstRoot,
     pub(crate) tokenizer: &'a mut Tokenizer,
+    /// Modules declared with `mod` so far, their functions are accessed as `module.name`
+    pub(crate) namespaces: Vec<String>,
 }
 
 impl<'a> Parser<'_> {
@@ -17,6 +22,7 @@ impl<'a> Parser<'_> {
         return Parser {
             root: root::AstRoot::new(),
             tokenizer: tokenizer,
+            namespaces: Vec::new(),
         };
     }
 
@@ -31,6 +37,17 @@ impl<'a> Parser<'_> {
         self.tokenizer.back();
     }
 
+    /// Creates an error located at the last token
+    pub(crate) fn error(&self, kind: error::ErrorKind) -> error::Error {
+        let span = self.tokenizer.span();
+        return error::Error {
+            kind: kind,
+            span: span,
+            filename: self.tokenizer.filename.clone(),
+            line_text: self.tokenizer.line_text(span.line).into(),
+        };
+    }
+
     /// Steps a token once, then steps back
     pub(crate) fn peek(&mut self) -> Token {
         let token = self.next();
@@ -44,17 +61,18 @@ impl<'a> Parser<'_> {
 
         let mut token = self.next();
         while !matches!(token, Token::EOF) {
+            let span = self.tokenizer.span();
             let node: Box<dyn Compile> = match token {
                 Token::Let => {
                     let name = self.next();
                     let name = match name {
                         Token::Identifier(name) => name,
-                        _ => return Err(error::Error::GlobalLetIdentifier(name)),
+                        _ => return Err(self.error(error::ErrorKind::GlobalLetIdentifier(name))),
                     };
 
                     let semi = self.next();
                     if !matches!(semi, Token::Semicolon) {
-                        return Err(error::Error::StatementSemicolon(semi));
+                        return Err(self.error(error::ErrorKind::StatementSemicolon(semi)));
                     }
 
                     let node = Box::new(AstGlobalVar::new(name));
@@ -64,21 +82,35 @@ impl<'a> Parser<'_> {
                     let name = self.next();
                     let name = match name {
                         Token::Identifier(name) => name,
-                        _ => return Err(error::Error::ModIdentifier(name)),
+                        _ => return Err(self.error(error::ErrorKind::ModIdentifier(name))),
                     };
 
                     let semi = self.next();
                     if !matches!(semi, Token::Semicolon) {
-                        return Err(error::Error::StatementSemicolon(semi));
+                        return Err(self.error(error::ErrorKind::StatementSemicolon(semi)));
                     }
 
+                    self.namespaces.push(name.clone());
                     let node = Box::new(AstMod::new(name));
                     node
                 }
+                Token::Use => Box::new(self.parse_use()?),
+                Token::Pub => {
+                    let fn_token = self.next();
+                    if !matches!(fn_token, Token::Fn) {
+                        return Err(self.error(error::ErrorKind::PubExpectedFn(fn_token)));
+                    }
+
+                    let mut func = self.parse_function()?;
+                    func.public = true;
+                    Box::new(func)
+                }
                 Token::Fn => Box::new(self.parse_function()?),
-                _ => return Err(error::Error::UnexpectedGlobalScopeToken(token)),
+                _ => return Err(self.error(error::ErrorKind::UnexpectedGlobalScopeToken(token))),
             };
-            self.root.children.push(node);
+            self.root
+                .children
+                .push(Box::new(AstLocated::new(node, span)));
             token = self.next();
         }
         Ok(())
@@ -2,10 +2,13 @@ use crate::ast::array_assign::AstArrayAssign;
 use crate::ast::break_stmt::AstBreak;
 use crate::ast::continue_stmt::AstContinue;
 use crate::ast::dynmod_stmt::AstDynmod;
-use crate::ast::node::Compile;
+use crate::ast::int::AstInt;
+use crate::ast::located::AstLocated;
+use crate::ast::node::{Compile, StatementExpression};
 use crate::ast::number::AstNumber;
 use crate::ast::ret::AstReturn;
 use crate::ast::string::AstString;
+use crate::ast::throw_stmt::AstThrow;
 use crate::ast::var_assign::AstAssign;
 use crate::ast::vmcall::AstVmcall;
 use crate::parser::*;
@@ -15,6 +18,7 @@ use alloc::boxed::Box;
 impl Parser<'_> {
     pub(crate) fn parse_statement(&mut self) -> Result<Box<dyn Compile>, error::Error> {
         let token = self.next();
+        let span = self.tokenizer.span();
         let mut require_semicolon = true;
 
         let statement: Box<dyn Compile> = match token {
@@ -23,11 +27,14 @@ impl Parser<'_> {
 
                 node
             }
+            Token::Let if matches!(self.peek(), Token::Lbracket | Token::Lbrace) => {
+                Box::new(self.parse_destructure()?)
+            }
             Token::Let => {
                 let name = self.next();
                 let name = match name {
                     Token::Identifier(ident) => ident,
-                    _ => return Err(error::Error::LetNameSyntax(name)),
+                    _ => return Err(self.error(error::ErrorKind::LetNameSyntax(name))),
                 };
 
                 let mut assign = Box::new(AstAssign::new());
@@ -45,7 +52,9 @@ impl Parser<'_> {
 
                             let rb = self.next();
                             if !matches!(rb, Token::Rbracket) {
-                                return Err(error::Error::LetExpectedRbracket(token));
+                                return Err(
+                                    self.error(error::ErrorKind::LetExpectedRbracket(token))
+                                );
                             }
 
                             token = self.next();
@@ -56,8 +65,9 @@ impl Parser<'_> {
                             let index = self.next();
                             let index: Box<dyn Compile> = match index {
                                 Token::Number(number) => Box::new(AstNumber::new(number)),
+                                Token::Int(int) => Box::new(AstInt::new(int)),
                                 Token::Identifier(ident) => Box::new(AstString::new(ident)),
-                                _ => return Err(error::Error::LetDotSyntax(index)),
+                                _ => return Err(self.error(error::ErrorKind::LetDotSyntax(index))),
                             };
 
                             array_assign.indexes.push(index);
@@ -69,7 +79,7 @@ impl Parser<'_> {
                 }
 
                 if !matches!(token, Token::Assign) {
-                    return Err(error::Error::LetExpectedAssign(token));
+                    return Err(self.error(error::ErrorKind::LetExpectedAssign(token)));
                 }
 
                 let expr = self.parse_expression()?;
@@ -88,7 +98,8 @@ impl Parser<'_> {
                 let id = self.next();
                 let id = match id {
                     Token::Number(number) => number as u8,
-                    _ => return Err(error::Error::VmcallExpectedNumber(id)),
+                    Token::Int(int) => int as u8,
+                    _ => return Err(self.error(error::ErrorKind::VmcallExpectedNumber(id))),
                 };
 
                 let vmcall = Box::new(AstVmcall::new(id));
@@ -114,6 +125,26 @@ impl Parser<'_> {
                 self.back();
                 node
             }
+            Token::For => {
+                require_semicolon = false;
+                let node = Box::new(self.parse_for()?);
+                self.back();
+                node
+            }
+            Token::Match => {
+                require_semicolon = false;
+                let mut node = Box::new(self.parse_match()?);
+                node.disable_push();
+                self.back();
+                node
+            }
+            Token::Try => {
+                require_semicolon = false;
+                let node = Box::new(self.parse_try()?);
+                self.back();
+                node
+            }
+            Token::Throw => Box::new(AstThrow::new(self.parse_expression()?)),
             Token::Break => Box::new(AstBreak::new()),
             Token::Continue => Box::new(AstContinue::new()),
             Token::Dynmod => {
@@ -133,9 +164,9 @@ impl Parser<'_> {
 
         let semi = self.next();
         if !matches!(semi, Token::Semicolon) && require_semicolon {
-            return Err(error::Error::StatementSemicolon(semi));
+            return Err(self.error(error::ErrorKind::StatementSemicolon(semi)));
         }
 
-        Ok(statement)
+        Ok(Box::new(AstLocated::new(statement, span)))
     }
 }
@@ -0,0 +1,24 @@
+use crate::ast::try_catch::AstTryCatch;
+use crate::parser::*;
+use crate::tokenizer::Token;
+
+impl Parser<'_> {
+    pub(crate) fn parse_try(&mut self) -> Result<AstTryCatch, error::Error> {
+        let mut node = AstTryCatch::new();
+        node.body = self.parse_block()?;
+
+        let catch = self.next();
+        if !matches!(catch, Token::Catch) {
+            return Err(self.error(error::ErrorKind::TryExpectedCatch(catch)));
+        }
+
+        match self.next() {
+            Token::Identifier(name) => node.catch_name = Some(name),
+            _ => self.back(),
+        }
+
+        node.catch_block = self.parse_block()?;
+
+        Ok(node)
+    }
+}
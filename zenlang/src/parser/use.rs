@@ -0,0 +1,48 @@
+use crate::ast::use_stmt::AstUse;
+use crate::parser::*;
+use crate::tokenizer::Token;
+
+impl Parser<'_> {
+    /// Parse `module::name;` or `module::{a, b};` after `use`
+    pub(crate) fn parse_use(&mut self) -> Result<AstUse, error::Error> {
+        let module = self.next();
+        let module = match module {
+            Token::Identifier(module) => module,
+            _ => return Err(self.error(error::ErrorKind::UseModule(module))),
+        };
+        let mut node = AstUse::new(module);
+
+        let colons = self.next();
+        if !matches!(colons, Token::DoubleColon) {
+            return Err(self.error(error::ErrorKind::UseDoubleColon(colons)));
+        }
+
+        let token = self.next();
+        match token {
+            Token::Identifier(name) => node.names.push(name),
+            Token::Lbrace => loop {
+                let name = self.next();
+                match name {
+                    Token::Identifier(name) => node.names.push(name),
+                    Token::Rbrace => break,
+                    _ => return Err(self.error(error::ErrorKind::UseName(name))),
+                }
+
+                let token = self.next();
+                match token {
+                    Token::Comma => continue,
+                    Token::Rbrace => break,
+                    _ => return Err(self.error(error::ErrorKind::UseName(token))),
+                }
+            },
+            _ => return Err(self.error(error::ErrorKind::UseName(token))),
+        }
+
+        let semi = self.next();
+        if !matches!(semi, Token::Semicolon) {
+            return Err(self.error(error::ErrorKind::StatementSemicolon(semi)));
+        }
+
+        Ok(node)
+    }
+}
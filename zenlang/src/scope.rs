@@ -3,20 +3,30 @@
 //! Contains: Scope
 
 use crate::value::Value;
+use alloc::rc::Rc;
 use alloc::string::*;
 use alloc::vec::*;
+use core::cell::RefCell;
 
 /// Scope
 ///
-/// Contains variables values and names
+/// Contains variables values and names.
+/// Local variables resolved by the compiler are stored in slots.
+/// Variables captured by a lambda are moved into cells shared with the lambda
 #[derive(Clone, Debug)]
 pub struct Scope {
     pub vars: Vec<(String, Value)>,
+    pub slots: Vec<Value>,
+    pub cells: Vec<(String, Rc<RefCell<Value>>)>,
 }
 
 impl Scope {
     pub fn new() -> Scope {
-        return Scope { vars: Vec::new() };
+        return Scope {
+            vars: Vec::new(),
+            slots: Vec::new(),
+            cells: Vec::new(),
+        };
     }
 
     /// Get a variable reference by a name
@@ -39,6 +49,42 @@ impl Scope {
         return None;
     }
 
+    /// Get a slot mutable reference, growing the slots if needed
+    pub fn get_slot_mut(&mut self, slot: u16) -> &mut Value {
+        let slot = slot as usize;
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, Value::Null());
+        }
+        return &mut self.slots[slot];
+    }
+
+    /// Get a captured variable cell by a name
+    pub fn get_cell(&self, name: &String) -> Option<&Rc<RefCell<Value>>> {
+        for cell in self.cells.iter() {
+            if cell.0 == *name {
+                return Some(&cell.1);
+            }
+        }
+        return None;
+    }
+
+    /// Get a cell shared with a lambda, moving the variable into it if it's not captured yet
+    ///
+    /// Returns the cell and whether it was created
+    pub fn capture(&mut self, name: &String) -> (Rc<RefCell<Value>>, bool) {
+        if let Some(cell) = self.get_cell(name) {
+            return (cell.clone(), false);
+        }
+
+        let mut value = Value::Null();
+        if let Some(i) = self.vars.iter().position(|var| var.0 == *name) {
+            value = self.vars.remove(i).1;
+        }
+        let cell = Rc::new(RefCell::new(value));
+        self.cells.push((name.clone(), cell.clone()));
+        return (cell, true);
+    }
+
     /// Create a variable if doesn't exist
     pub fn create_if_doesnt_exist(&mut self, name: &String) {
         if self.get(name).is_none() {
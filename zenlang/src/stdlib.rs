@@ -1,9 +1,14 @@
 use crate::{
     compiler,
-    module::{Module, ModuleFunction},
+    interop::{interop_err, interop_ok},
+    json,
+    module::{Arity, Module, ModuleFunction},
     opcode::Opcode,
     parser, tokenizer,
+    value::Value,
+    vm::{RuntimeErrorKind, VM, string_size},
 };
+use alloc::format;
 use alloc::string::*;
 
 pub fn compile_stdlib_module() -> Module {
@@ -11,84 +16,80 @@ pub fn compile_stdlib_module() -> Module {
         r#"
 let File;
 
-fn #[naked] print str {
+pub fn #[naked] print str {
     vmcall 1;
     return null;
 } 
-fn #[naked] println str {
+pub fn #[naked] println str {
     vmcall 2;
     return null;
 } 
-fn get_string {
+pub fn get_string {
     return _vmcall_ret_unsafe_1(3);
 }
-fn array_size array {
+pub fn array_size array {
     return _vmcall_ret_unsafe_2(array, 5);
 }
-fn array_push array element {
+pub fn array_push array element {
     return _vmcall_ret_unsafe_3(array, element, 6);
 }
-fn array_pop array {
+pub fn array_pop array {
     return _vmcall_ret_unsafe_2(array, 7);
 }
-fn array_remove array index {
+pub fn array_remove array index {
     return _vmcall_ret_unsafe_3(array, index, 8);
 }
-fn array_insert array index element {
+pub fn array_insert array index element {
     return _vmcall_ret_unsafe_4(array, index, element, 9);
 }
-fn array_count array element {
-    let i = 0;
+pub fn array_count array element {
     let count = 0;
-    let size = array_size(array);
-    while i < size {
+    for i in 0..array_size(array) {
         if array[i] == element {
             let count = count + 1;
         }
-        
-        let i = i + 1;
     }
     return count;
 }
-fn array_last array {
+pub fn array_last array {
     return array[array_size(array) - 1];
 }
-fn str_split str delimiter {
+pub fn str_split str delimiter {
     return _vmcall_ret_unsafe_3(str, delimiter, 10);
 }
-fn err value {
+pub fn err value {
     return {"_typename" = "Result", "_err" = value, "_ok" = null};
 }
-fn ok value {
+pub fn ok value {
     return {"_typename" = "Result", "_err" = null, "_ok" = value};
 }
-fn get_err result {
+pub fn get_err result {
     return result._err;
 }
-fn get_ok result {
+pub fn get_ok result {
     return result._ok;
 }
-fn read_file_bytes path {
+pub fn read_file_bytes path {
     if let bytes = _vmcall_ret_unsafe_2(path, 11){
         return ok(bytes);
     }
     return err("Read failed");
 }
-fn read_file path {
+pub fn read_file path {
     if let bytes = _vmcall_ret_unsafe_2(path, 12){
         return ok(bytes);
     }
     return err("Read failed");
 }
-fn #[naked] write_file_bytes path bytes {
+pub fn #[naked] write_file_bytes path bytes {
     vmcall 13;
     return null;
 }
-fn #[naked] write_file path bytes {
+pub fn #[naked] write_file path bytes {
     vmcall 14;
     return null;
 }
-fn boolean str {
+pub fn boolean str {
     if str == "true" {
         return true;
     } elif str == "false" {
@@ -96,23 +97,23 @@ fn boolean str {
     }
     return null;
 }
-fn ord ch {
+pub fn ord ch {
     if ch == "" {
         return err("ch is empty");
     }
 
     return ok(_vmcall_ret_unsafe_2(ch, 15));
 }
-fn chr ch {
+pub fn chr ch {
     return _vmcall_ret_unsafe_2(ch, 16);
 }
-fn stringify any {
+pub fn stringify any {
     return _vmcall_ret_unsafe_2(any, 17);
 }
-fn number str {
+pub fn number str {
     return _vmcall_ret_unsafe_2(str, 18);
 }
-fn clone obj {
+pub fn clone obj {
     return _vmcall_ret_unsafe_2(obj, 19);
 }
 fn #[ctor] stdlib_init {
@@ -126,6 +127,7 @@ fn #[ctor] stdlib_init {
     "#,
     );
     let mut tokenizer = tokenizer::Tokenizer::new(code);
+    tokenizer.filename = "stdlib".into();
     let mut parser = parser::Parser::new(&mut tokenizer);
     let mut compiler = compiler::Compiler::new(&mut parser);
     if let Err(e) = compiler.compile() {
@@ -138,7 +140,8 @@ fn #[ctor] stdlib_init {
     module.functions.push(ModuleFunction::new(
         "_vmcall_ret_unsafe_1".into(),
         module.opcodes.len(),
-        1,
+        Arity::exact(1),
+        false,
         false,
     ));
     module.opcodes.push(Opcode::Dynvmcall());
@@ -147,7 +150,8 @@ fn #[ctor] stdlib_init {
     module.functions.push(ModuleFunction::new(
         "_vmcall_ret_unsafe_2".into(),
         module.opcodes.len(),
-        2,
+        Arity::exact(2),
+        false,
         false,
     ));
     module.opcodes.push(Opcode::Dynvmcall());
@@ -156,7 +160,8 @@ fn #[ctor] stdlib_init {
     module.functions.push(ModuleFunction::new(
         "_vmcall_ret_unsafe_3".into(),
         module.opcodes.len(),
-        3,
+        Arity::exact(3),
+        false,
         false,
     ));
     module.opcodes.push(Opcode::Dynvmcall());
@@ -165,7 +170,8 @@ fn #[ctor] stdlib_init {
     module.functions.push(ModuleFunction::new(
         "_vmcall_ret_unsafe_4".into(),
         module.opcodes.len(),
-        4,
+        Arity::exact(4),
+        false,
         false,
     ));
     module.opcodes.push(Opcode::Dynvmcall());
@@ -173,3 +179,57 @@ fn #[ctor] stdlib_init {
 
     return core::mem::take(module);
 }
+
+/// Register the stdlib functions implemented as natives, called by [VM::new]
+pub(crate) fn register_natives(vm: &mut VM) {
+    vm.register_native("json_parse", 1, |vm, args| {
+        let text = match &args[0] {
+            Value::String(text) => text,
+            other => {
+                return Err(vm.runtime_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!("json_parse: expected a string, found {}", other.get_type()),
+                ));
+            }
+        };
+        return match json::parse(text) {
+            Err(e) => Ok(interop_err(Value::String(e))),
+            Ok(value) => {
+                if !vm.alloc(VM::deep_size(&value)) {
+                    return Err(vm.error.clone().unwrap());
+                }
+                Ok(interop_ok(value))
+            }
+        };
+    });
+
+    vm.register_native("json_stringify", 2, |vm, args| {
+        let indent = match &args[1] {
+            Value::Int(int) if (0..=json::MAX_INDENT as i64).contains(int) => *int as usize,
+            Value::Number(num) if (0.0..=json::MAX_INDENT as f64).contains(num) => *num as usize,
+            Value::Null() => 0,
+            other => {
+                return Err(vm.runtime_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!(
+                        "json_stringify: indent has to be a number from 0 to {}, found {}",
+                        json::MAX_INDENT,
+                        other
+                    ),
+                ));
+            }
+        };
+        return match json::stringify(&args[0], indent) {
+            Err(e) => Err(vm.runtime_error(
+                RuntimeErrorKind::TypeMismatch,
+                format!("json_stringify: {}", e),
+            )),
+            Ok(text) => {
+                if !vm.alloc(string_size(text.len())) {
+                    return Err(vm.error.clone().unwrap());
+                }
+                Ok(Value::String(text))
+            }
+        };
+    });
+}
@@ -8,17 +8,35 @@ use alloc::{string::*, vec::Vec};
 use libm::pow;
 use unescape;
 
+/// Part of an interpolated string
+#[derive(Debug, Clone)]
+pub enum FormatPart {
+    /// Unescaped text
+    Literal(String),
+    /// Source code of an embedded expression, without the braces
+    Expression(String),
+}
+
 #[derive(Debug, Clone)]
 pub enum Token {
     Fn,
     Return,
     Let,
     Number(f64),
+    /// Integer literal, a number without a decimal part that fits in an i64
+    Int(i64),
     Identifier(String),
     String(String),
+    /// `f"..."` string with `{expr}` parts, `\{` and `\}` are literal braces
+    FormatString(Vec<FormatPart>),
+    /// `f"..."` string with a `{` that is never closed
+    UnterminatedFormatString,
     Operator(char),
     OperatorCmp(char, char),
     BitOperator(char, char),
+    LogicalOperator(char, char),
+    /// `**` and `~/`
+    DoubleOperator(char, char),
     Null,
     True,
     False,
@@ -31,32 +49,97 @@ pub enum Token {
     Semicolon,
     Comma,
     Dot,
+    /// `..`, `..=` if true
+    Range(bool),
+    /// `...`
+    Ellipsis,
     Assign,
+    /// `=>`
+    FatArrow,
+    /// `::`
+    DoubleColon,
     Hashtag,
     If,
     Elif,
     Else,
     While,
+    For,
+    In,
     Break,
     Continue,
     Vmcall,
     Mod,
     Dynmod,
+    Pub,
+    Use,
+    Try,
+    Catch,
+    Throw,
+    Match,
     EOF,
 }
 
+/// Span
+///
+/// Location of a token in the source code
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Span {
+    /// Byte offset of the first character of the token
+    pub offset: usize,
+    /// Length of the token in bytes
+    pub len: usize,
+    /// Line number, starting from 1
+    pub line: usize,
+    /// Column number (in characters), starting from 1
+    pub column: usize,
+}
+
+/// Location of a character in the code, moved forward as tokens are read
+#[derive(Debug, Clone, Copy)]
+struct Cursor {
+    /// Index of the character
+    index: usize,
+    /// Byte offset of the character
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        return Self {
+            index: 0,
+            offset: 0,
+            line: 1,
+            column: 1,
+        };
+    }
+}
+
 #[derive(Debug)]
 pub struct Tokenizer {
+    /// Name of the file the code comes from, used in error messages
+    pub filename: String,
     code: String,
+    /// Characters of the code, so reading the character at `pos` doesn't walk the string
+    chars: Vec<char>,
     pos: usize,
-    prev_positions: Vec<usize>,
+    start: usize,
+    span: Span,
+    cursor: Cursor,
+    prev_positions: Vec<(usize, Span, Cursor)>,
 }
 
 impl Tokenizer {
     pub fn new(code: String) -> Tokenizer {
         return Tokenizer {
+            filename: String::new(),
+            chars: code.chars().collect(),
             code: code,
             pos: 0,
+            start: 0,
+            span: Span::default(),
+            cursor: Cursor::default(),
             prev_positions: Vec::new(),
         };
     }
@@ -74,14 +157,25 @@ impl Tokenizer {
     }
 
     fn number(&mut self) -> Token {
+        if self.chars.get(self.pos).copied() == Some('0')
+            && matches!(self.chars.get(self.pos + 1).copied(), Some('x' | 'X'))
+        {
+            return self.hex_number();
+        }
+
         let mut num = 0.0;
+        let mut int: Option<i64> = Some(0);
 
         let mut decimal_part = false;
         let mut decmial_nums: u64 = 1;
 
-        while self.pos < self.code.len() {
-            let c = self.code.chars().nth(self.pos).unwrap();
+        while self.pos < self.chars.len() {
+            let c = self.chars.get(self.pos).copied().unwrap();
             if c == '.' {
+                // `..` after a number is a range
+                if self.chars.get(self.pos + 1).copied() == Some('.') {
+                    break;
+                }
                 decimal_part = true;
                 self.pos += 1;
                 continue;
@@ -93,6 +187,9 @@ impl Tokenizer {
                 // int part
                 num *= 10.0;
                 num += (c as u8 - '0' as u8) as f64;
+                int = int
+                    .and_then(|int| int.checked_mul(10))
+                    .and_then(|int| int.checked_add((c as u8 - '0' as u8) as i64));
             } else {
                 // decimal part
                 let digit = (c as u8 - '0' as u8) as f64;
@@ -104,13 +201,42 @@ impl Tokenizer {
             self.pos += 1;
         }
 
+        // integers too large for an i64 stay numbers
+        if let (false, Some(int)) = (decimal_part, int) {
+            return Token::Int(int);
+        }
         return Token::Number(num);
     }
 
+    /// Hexadecimal integer like `0xff`, all 64 bits are usable so `0xffffffffffffffff` is -1
+    fn hex_number(&mut self) -> Token {
+        self.pos += 2;
+
+        let mut num = 0.0;
+        let mut bits: Option<u64> = Some(0);
+        while let Some(digit) = self
+            .chars
+            .get(self.pos)
+            .copied()
+            .and_then(|c| c.to_digit(16))
+        {
+            num = num * 16.0 + digit as f64;
+            bits = bits
+                .and_then(|bits| bits.checked_mul(16))
+                .and_then(|bits| bits.checked_add(digit as u64));
+            self.pos += 1;
+        }
+
+        return match bits {
+            Some(bits) => Token::Int(bits as i64),
+            None => Token::Number(num),
+        };
+    }
+
     fn identifier(&mut self) -> Token {
         let mut identifier = String::new();
-        while self.pos < self.code.len() {
-            let c = self.code.chars().nth(self.pos).unwrap();
+        while self.pos < self.chars.len() {
+            let c = self.chars.get(self.pos).copied().unwrap();
             if !self.is_identifier_letter(c) && !self.is_digit(c) {
                 break;
             }
@@ -123,48 +249,154 @@ impl Tokenizer {
         return Token::Identifier(identifier);
     }
 
-    fn string(&mut self) -> Token {
+    /// Read a string, `{expr}` parts are interpolated only in `f"..."` strings
+    fn string(&mut self, format: bool) -> Token {
         let mut string = String::new();
+        let mut parts = Vec::new();
         self.pos += 1;
-        while self.pos < self.code.len() {
-            let c = self.code.chars().nth(self.pos).unwrap();
+        while self.pos < self.chars.len() {
+            let c = self.chars.get(self.pos).copied().unwrap();
             if c == '"' {
                 self.pos += 1;
                 break;
             }
 
+            if format && c == '{' {
+                self.pos += 1;
+                parts.push(FormatPart::Literal(Self::unescape(&string)));
+                match self.embedded_expression() {
+                    Some(expression) => parts.push(FormatPart::Expression(expression)),
+                    None => return Token::UnterminatedFormatString,
+                }
+                string.clear();
+                continue;
+            }
+
+            // keep escapes for unescape, so `\{` and `\"` don't end the text
             string.push(c);
+            if c == '\\' {
+                if let Some(escaped) = self.chars.get(self.pos + 1).copied() {
+                    string.push(escaped);
+                    self.pos += 1;
+                }
+            }
 
             self.pos += 1;
         }
 
-        if let Some(s) = unescape::unescape(string.as_str()) {
-            string = s;
+        if parts.is_empty() {
+            return Token::String(Self::unescape(&string));
         }
-        return Token::String(string);
+        parts.push(FormatPart::Literal(Self::unescape(&string)));
+        return Token::FormatString(parts);
     }
 
-    pub fn get_line(&self) -> u64 {
-        let mut lines = 1;
-        for i in 0..self.code.len() {
-            if i >= self.pos {
-                break;
+    fn unescape(string: &str) -> String {
+        return unescape::unescape(string).unwrap_or_else(|| string.into());
+    }
+
+    /// Read the source of an expression embedded in a string up to the matching `}`,
+    /// strings inside the expression are skipped so their braces don't count
+    ///
+    /// None if the source ends before the `}`
+    fn embedded_expression(&mut self) -> Option<String> {
+        let mut expression = String::new();
+        let mut depth = 0;
+        let mut in_string = false;
+        while self.pos < self.chars.len() {
+            let c = self.chars.get(self.pos).copied().unwrap();
+            self.pos += 1;
+            if !in_string && c == '}' {
+                if depth == 0 {
+                    return Some(expression);
+                }
+                depth -= 1;
+            } else if !in_string && c == '{' {
+                depth += 1;
+            } else if c == '"' {
+                in_string = !in_string;
+            } else if in_string && c == '\\' {
+                expression.push(c);
+                if let Some(escaped) = self.chars.get(self.pos).copied() {
+                    expression.push(escaped);
+                    self.pos += 1;
+                }
+                continue;
             }
+            expression.push(c);
+        }
+        return None;
+    }
 
-            if self.code.chars().nth(i).unwrap() == '\n' {
-                lines += 1;
+    pub fn get_line(&self) -> u64 {
+        let end = self.pos.min(self.chars.len());
+        let newlines = self.chars[..end].iter().filter(|c| **c == '\n').count();
+        return newlines as u64 + 1;
+    }
+
+    /// Get the span of the last token returned by next()
+    pub fn span(&self) -> Span {
+        return self.span;
+    }
+
+    /// Get the text of a line (starting from 1), without the line terminator
+    pub fn line_text(&self, line: usize) -> &str {
+        return self.code.lines().nth(line.wrapping_sub(1)).unwrap_or("");
+    }
+
+    /// Move the cursor forward to the token start and measure the token, so spans take
+    /// time proportional to the distance from the previous token
+    fn make_span(&mut self, start: usize, end: usize) -> Span {
+        let end = end.min(self.chars.len());
+        while self.cursor.index < start.min(end) {
+            let c = self.chars[self.cursor.index];
+            self.cursor.index += 1;
+            self.cursor.offset += c.len_utf8();
+            if c == '\n' {
+                self.cursor.line += 1;
+                self.cursor.column = 1;
+            } else {
+                self.cursor.column += 1;
             }
         }
-        return lines;
+
+        let len = self.chars[self.cursor.index.min(end)..end]
+            .iter()
+            .map(|c| c.len_utf8())
+            .sum();
+
+        return Span {
+            offset: self.cursor.offset,
+            len: len,
+            line: self.cursor.line,
+            column: self.cursor.column,
+        };
     }
 
     pub fn next(&mut self) -> Token {
-        self.prev_positions.push(self.pos);
-        while self.pos < self.code.len() {
-            let c = self.code.chars().nth(self.pos).unwrap();
+        self.prev_positions.push((self.pos, self.span, self.cursor));
+        let token = self.next_token();
+        self.span = self.make_span(self.start, self.pos);
+        return token;
+    }
+
+    /// Get a next token and the span of it
+    pub fn next_spanned(&mut self) -> (Token, Span) {
+        let token = self.next();
+        return (token, self.span);
+    }
+
+    fn next_token(&mut self) -> Token {
+        while self.pos < self.chars.len() {
+            self.start = self.pos;
+            let c = self.chars.get(self.pos).copied().unwrap();
             if self.is_digit(c) {
                 let token = self.number();
                 return token;
+            } else if c == 'f' && self.chars.get(self.pos + 1).copied() == Some('"') {
+                self.pos += 1;
+                let token = self.string(true);
+                return token;
             } else if self.is_identifier_letter(c) {
                 let mut token = self.identifier();
                 if let Token::Identifier(ref name) = token {
@@ -188,6 +420,10 @@ impl Tokenizer {
                         token = Token::Else;
                     } else if name == "while" {
                         token = Token::While;
+                    } else if name == "for" {
+                        token = Token::For;
+                    } else if name == "in" {
+                        token = Token::In;
                     } else if name == "break" {
                         token = Token::Break;
                     } else if name == "continue" {
@@ -198,11 +434,23 @@ impl Tokenizer {
                         token = Token::Mod;
                     } else if name == "dynmod" {
                         token = Token::Dynmod;
+                    } else if name == "pub" {
+                        token = Token::Pub;
+                    } else if name == "use" {
+                        token = Token::Use;
+                    } else if name == "try" {
+                        token = Token::Try;
+                    } else if name == "catch" {
+                        token = Token::Catch;
+                    } else if name == "throw" {
+                        token = Token::Throw;
+                    } else if name == "match" {
+                        token = Token::Match;
                     }
                 }
                 return token;
             } else if c == '"' {
-                let token = self.string();
+                let token = self.string(false);
                 return token;
             } else if c == '{' {
                 self.pos += 1;
@@ -218,14 +466,30 @@ impl Tokenizer {
                 return Token::Comma;
             } else if c == '.' {
                 self.pos += 1;
+                if self.chars.get(self.pos).copied() == Some('.') {
+                    self.pos += 1;
+                    if self.chars.get(self.pos).copied() == Some('=') {
+                        self.pos += 1;
+                        return Token::Range(true);
+                    } else if self.chars.get(self.pos).copied() == Some('.') {
+                        self.pos += 1;
+                        return Token::Ellipsis;
+                    }
+                    return Token::Range(false);
+                }
                 return Token::Dot;
-            } else if ['+', '-', '*', '/'].contains(&c) {
+            } else if ['+', '-', '*', '/', '%'].contains(&c) {
                 self.pos += 1;
-                if self.pos < self.code.len() {
-                    let c = self.code.chars().nth(self.pos).unwrap();
+                let next = self.chars.get(self.pos).copied();
+                if c == '*' && next == Some('*') {
+                    self.pos += 1;
+                    return Token::DoubleOperator('*', '*');
+                }
+                if self.pos < self.chars.len() {
+                    let c = self.chars.get(self.pos).copied().unwrap();
                     if c == '/' {
-                        while self.pos < self.code.len() {
-                            if self.code.chars().nth(self.pos).unwrap() == '\n' {
+                        while self.pos < self.chars.len() {
+                            if self.chars.get(self.pos).copied().unwrap() == '\n' {
                                 self.pos += 1;
                                 break;
                             }
@@ -235,12 +499,19 @@ impl Tokenizer {
                     }
                 }
                 return Token::Operator(c);
-            } else if c == '|' {
+            } else if c == '|' || c == '&' {
                 self.pos += 1;
-                return Token::BitOperator('|', '|');
-            } else if c == '&' {
+                if self.chars.get(self.pos).copied() == Some(c) {
+                    self.pos += 1;
+                    return Token::LogicalOperator(c, c);
+                }
+                return Token::Operator(c);
+            } else if c == '~' && self.chars.get(self.pos + 1).copied() == Some('/') {
+                self.pos += 2;
+                return Token::DoubleOperator('~', '/');
+            } else if c == '^' || c == '~' {
                 self.pos += 1;
-                return Token::BitOperator('&', '&');
+                return Token::Operator(c);
             } else if c == '(' {
                 self.pos += 1;
                 return Token::Lparen;
@@ -256,20 +527,26 @@ impl Tokenizer {
             } else if c == '#' {
                 self.pos += 1;
                 return Token::Hashtag;
+            } else if c == ':' && self.chars.get(self.pos + 1).copied() == Some(':') {
+                self.pos += 2;
+                return Token::DoubleColon;
             } else if c == '=' {
                 self.pos += 1;
-                if self.pos < self.code.len() {
-                    let c = self.code.chars().nth(self.pos).unwrap();
+                if self.pos < self.chars.len() {
+                    let c = self.chars.get(self.pos).copied().unwrap();
                     if c == '=' {
                         self.pos += 1;
                         return Token::OperatorCmp('=', '=');
+                    } else if c == '>' {
+                        self.pos += 1;
+                        return Token::FatArrow;
                     }
                 }
                 return Token::Assign;
             } else if c == '>' {
                 self.pos += 1;
-                if self.pos < self.code.len() {
-                    let c = self.code.chars().nth(self.pos).unwrap();
+                if self.pos < self.chars.len() {
+                    let c = self.chars.get(self.pos).copied().unwrap();
                     if c == '=' {
                         self.pos += 1;
                         return Token::OperatorCmp('>', '=');
@@ -281,8 +558,8 @@ impl Tokenizer {
                 return Token::OperatorCmp('>', '>');
             } else if c == '<' {
                 self.pos += 1;
-                if self.pos < self.code.len() {
-                    let c = self.code.chars().nth(self.pos).unwrap();
+                if self.pos < self.chars.len() {
+                    let c = self.chars.get(self.pos).copied().unwrap();
                     if c == '=' {
                         self.pos += 1;
                         return Token::OperatorCmp('<', '=');
@@ -294,8 +571,8 @@ impl Tokenizer {
                 return Token::OperatorCmp('<', '<');
             } else if c == '!' {
                 self.pos += 1;
-                if self.pos < self.code.len() {
-                    let c = self.code.chars().nth(self.pos).unwrap();
+                if self.pos < self.chars.len() {
+                    let c = self.chars.get(self.pos).copied().unwrap();
                     if c == '=' {
                         self.pos += 1;
                         return Token::OperatorCmp('!', '=');
@@ -306,10 +583,11 @@ impl Tokenizer {
 
             self.pos += 1;
         }
+        self.start = self.pos;
         return Token::EOF;
     }
 
     pub fn back(&mut self) {
-        self.pos = self.prev_positions.pop().unwrap_or(0);
+        (self.pos, self.span, self.cursor) = self.prev_positions.pop().unwrap_or_default();
     }
 }
@@ -0,0 +1,280 @@
+use crate::value::*;
+use ::serde::de::{self, DeserializeOwned, Error as _, IntoDeserializer, Visitor};
+use ::serde::{Deserialize, forward_to_deserialize_any};
+use alloc::format;
+
+/// Convert a value into any deserializable Rust type
+///
+/// The inverse of [to_value]. Whole numbers can be read into integer types
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T, ValueError> {
+    return T::deserialize(value.clone());
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        return deserializer.deserialize_any(ValueVisitor);
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        return Ok(Value::Boolean(v));
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        return Ok(Value::Int(v));
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        return match i64::try_from(v) {
+            Ok(int) => Ok(Value::Int(int)),
+            Err(_) => Ok(Value::Number(v as f64)),
+        };
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        return Ok(Value::Number(v));
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        return Ok(Value::String(v.into()));
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        return Ok(Value::String(v));
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        return Ok(Value::Null());
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        return Value::deserialize(deserializer);
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        return Ok(Value::Null());
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut array = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            array.push(element);
+        }
+        return Ok(Value::Object(Rc::new(RefCell::new(Object::Array(array)))));
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut dict = BTreeMap::new();
+        while let Some((key, value)) = map.next_entry::<String, Value>()? {
+            dict.insert(key, value);
+        }
+        return Ok(Value::Object(Rc::new(RefCell::new(Object::Dictionary(
+            dict,
+        )))));
+    }
+}
+
+impl Value {
+    fn expect_number(&self) -> Result<f64, ValueError> {
+        if let Some(num) = self.as_number() {
+            return Ok(num);
+        }
+        return Err(ValueError::new(format!(
+            "expected a number, found {}",
+            self.get_type()
+        )));
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($($method:ident $visit:ident $t:ty),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+                if let Value::Int(int) = self {
+                    return match <$t>::try_from(int) {
+                        Ok(int) => visitor.$visit(int),
+                        Err(_) => Err(ValueError::new(format!("{} is not a valid {}", int, stringify!($t)))),
+                    };
+                }
+                let num = self.expect_number()?;
+                if num != libm::trunc(num) || num < <$t>::MIN as f64 || num > <$t>::MAX as f64 {
+                    return Err(ValueError::new(format!("{} is not a valid {}", num, stringify!($t))));
+                }
+                return visitor.$visit(num as $t);
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::Number(num) => {
+                // whole numbers are visited as integers, so integer-only visitors accept them
+                if num == libm::trunc(num) && num >= i64::MIN as f64 && num < i64::MAX as f64 {
+                    return visitor.visit_i64(num as i64);
+                }
+                return visitor.visit_f64(num);
+            }
+            Value::Int(int) => visitor.visit_i64(int),
+            Value::String(string) => visitor.visit_string(string),
+            Value::Boolean(boolean) => visitor.visit_bool(boolean),
+            Value::Null() => visitor.visit_unit(),
+            Value::Object(obj) => match &*obj.borrow() {
+                Object::Array(array) => {
+                    let mut seq = de::value::SeqDeserializer::new(array.clone().into_iter());
+                    let value = visitor.visit_seq(&mut seq)?;
+                    seq.end()?;
+                    Ok(value)
+                }
+                Object::Dictionary(dict) => {
+                    let mut map = de::value::MapDeserializer::new(dict.clone().into_iter());
+                    let value = visitor.visit_map(&mut map)?;
+                    map.end()?;
+                    Ok(value)
+                }
+            },
+            _ => Err(ValueError::new(format!(
+                "cannot deserialize a {}",
+                self.get_type()
+            ))),
+        }
+    }
+
+    deserialize_integer!(
+        deserialize_i8 visit_i8 i8,
+        deserialize_i16 visit_i16 i16,
+        deserialize_i32 visit_i32 i32,
+        deserialize_i64 visit_i64 i64,
+        deserialize_u8 visit_u8 u8,
+        deserialize_u16 visit_u16 u16,
+        deserialize_u32 visit_u32 u32,
+        deserialize_u64 visit_u64 u64
+    );
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        return visitor.visit_f32(self.expect_number()? as f32);
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        return visitor.visit_f64(self.expect_number()?);
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        if let Value::Null() = self {
+            return visitor.visit_none();
+        }
+        return visitor.visit_some(self);
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        return visitor.visit_newtype_struct(self);
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        // unit variants are strings, other variants are a dictionary with a single key
+        if let Value::String(variant) = self {
+            return visitor.visit_enum(variant.into_deserializer());
+        }
+        if let Value::Object(obj) = &self {
+            if let Object::Dictionary(dict) = &*obj.borrow() {
+                if dict.len() == 1 {
+                    let (variant, value) = dict.iter().next().unwrap();
+                    return visitor.visit_enum(EnumDeserializer {
+                        variant: variant.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        return Err(ValueError::new(format!(
+            "expected a string or a dictionary with a single key, found {}",
+            self.get_type()
+        )));
+    }
+
+    forward_to_deserialize_any! {
+        bool i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        return self;
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = ValueError;
+    type Variant = Value;
+
+    fn variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<(T::Value, Value), ValueError> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        return Ok((variant, self.value));
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Value {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        if let Value::Null() = self {
+            return Ok(());
+        }
+        return Err(ValueError::custom("expected a unit variant"));
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, ValueError> {
+        return seed.deserialize(self);
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        return de::Deserializer::deserialize_any(self, visitor);
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        return de::Deserializer::deserialize_any(self, visitor);
+    }
+}
@@ -0,0 +1,34 @@
+use alloc::string::*;
+use core::fmt::Display;
+
+/// Error of a serde conversion between Rust types and values
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueError {
+    pub message: String,
+}
+
+impl ValueError {
+    pub fn new(message: String) -> ValueError {
+        return ValueError { message: message };
+    }
+}
+
+impl Display for ValueError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ::serde::ser::StdError for ValueError {}
+
+impl ::serde::ser::Error for ValueError {
+    fn custom<T: Display>(msg: T) -> Self {
+        return ValueError::new(msg.to_string());
+    }
+}
+
+impl ::serde::de::Error for ValueError {
+    fn custom<T: Display>(msg: T) -> Self {
+        return ValueError::new(msg.to_string());
+    }
+}
@@ -1,7 +1,11 @@
 //! Value
 //!
 //! ZenLang variable value
+//!
+//! Rust types implementing serde's traits are converted with [to_value] and [from_value]
+use crate::module::Arity;
 use crate::scope::Scope;
+use crate::vm::NativeFunction;
 use crate::vm::ProgramCounter;
 use crate::vm::VM;
 use alloc::collections::btree_map::BTreeMap;
@@ -11,6 +15,15 @@ use alloc::vec::*;
 use core::cell::RefCell;
 use core::fmt::Display;
 
+mod de;
+mod error;
+mod range;
+mod ser;
+pub use de::*;
+pub use error::*;
+pub use range::*;
+pub use ser::*;
+
 /// Object
 #[derive(Clone, Debug)]
 pub enum Object {
@@ -22,51 +35,58 @@ pub enum Object {
 #[derive(Clone, Debug)]
 pub enum Value {
     Number(f64),
+    Int(i64),
     String(String),
     Boolean(bool),
-    FunctionRef(ProgramCounter, usize),
+    FunctionRef(ProgramCounter, Arity),
     Lambda(ProgramCounter, Rc<RefCell<Scope>>, usize),
+    Native(Rc<NativeFunction>),
     Object(Rc<RefCell<Object>>),
+    Range(Range),
     Null(),
 }
 
 impl Value {
     /// Perform a less than (<) operation. False if unsupported operands
     pub fn lt(&self, other: &Value) -> bool {
-        if let Value::Number(a) = self {
-            if let Value::Number(b) = other {
-                return a < b;
-            }
+        if let (Value::Int(a), Value::Int(b)) = (self, other) {
+            return a < b;
+        }
+        if let (Some(a), Some(b)) = (self.as_number(), other.as_number()) {
+            return a < b;
         }
         return false;
     }
 
     /// Perform a greater than (>) operation. False if unsupported operands
     pub fn gt(&self, other: &Value) -> bool {
-        if let Value::Number(a) = self {
-            if let Value::Number(b) = other {
-                return a > b;
-            }
+        if let (Value::Int(a), Value::Int(b)) = (self, other) {
+            return a > b;
+        }
+        if let (Some(a), Some(b)) = (self.as_number(), other.as_number()) {
+            return a > b;
         }
         return false;
     }
 
     /// Perform a less than or equal (<=) operation. False if unsupported operands
     pub fn le(&self, other: &Value) -> bool {
-        if let Value::Number(a) = self {
-            if let Value::Number(b) = other {
-                return a <= b;
-            }
+        if let (Value::Int(a), Value::Int(b)) = (self, other) {
+            return a <= b;
+        }
+        if let (Some(a), Some(b)) = (self.as_number(), other.as_number()) {
+            return a <= b;
         }
         return false;
     }
 
     /// Perform a greater than or equal (>=) operation. False if unsupported operands
     pub fn ge(&self, other: &Value) -> bool {
-        if let Value::Number(a) = self {
-            if let Value::Number(b) = other {
-                return a >= b;
-            }
+        if let (Value::Int(a), Value::Int(b)) = (self, other) {
+            return a >= b;
+        }
+        if let (Some(a), Some(b)) = (self.as_number(), other.as_number()) {
+            return a >= b;
         }
         return false;
     }
@@ -75,6 +95,10 @@ impl Value {
     pub fn equal(&self, other: &Value, vm: &VM) -> bool {
         match (self, other) {
             (Value::Number(x), Value::Number(y)) => x == y,
+            (Value::Int(x), Value::Int(y)) => x == y,
+            (Value::Int(x), Value::Number(y)) | (Value::Number(y), Value::Int(x)) => {
+                return Value::Number(*y).as_int() == Some(*x);
+            }
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::Object(obja), Value::Object(objb)) => {
@@ -115,6 +139,12 @@ impl Value {
             (Value::Lambda(a, _, _), Value::Lambda(b, _, _)) => {
                 return a == b;
             }
+            (Value::Native(a), Value::Native(b)) => {
+                return Rc::ptr_eq(a, b);
+            }
+            (Value::Range(a), Value::Range(b)) => {
+                return a == b;
+            }
             (Value::Null(), Value::Null()) => {
                 return true;
             }
@@ -122,14 +152,47 @@ impl Value {
         }
     }
 
+    /// Get a number or an integer as a f64
+    pub fn as_number(&self) -> Option<f64> {
+        return match self {
+            Value::Number(num) => Some(*num),
+            Value::Int(int) => Some(*int as f64),
+            _ => None,
+        };
+    }
+
+    /// Get an integer, or a number without a fractional part that fits in an i64
+    pub fn as_int(&self) -> Option<i64> {
+        return match self {
+            Value::Int(int) => Some(*int),
+            // i64::MAX isn't representable as a f64, 2^63 is the first value out of range
+            Value::Number(num)
+                if *num == libm::trunc(*num)
+                    && *num >= -9223372036854775808.0
+                    && *num < 9223372036854775808.0 =>
+            {
+                Some(*num as i64)
+            }
+            _ => None,
+        };
+    }
+
+    /// Get a non-negative integer usable as an index
+    pub fn as_index(&self) -> Option<usize> {
+        return self.as_int().and_then(|int| usize::try_from(int).ok());
+    }
+
     pub fn get_type(&self) -> &'static str {
         match self {
             Value::Number(_) => "number",
+            Value::Int(_) => "int",
             Value::String(_) => "string",
             Value::Boolean(_) => "bool",
             Value::FunctionRef(_, _) => "function",
             Value::Lambda(_, _, _) => "lambda",
+            Value::Native(_) => "native",
             Value::Object(_) => "object",
+            Value::Range(_) => "range",
             Value::Null() => "null",
         }
     }
@@ -141,6 +204,9 @@ impl Display for Value {
             Value::Number(num) => {
                 return write!(f, "{}", num);
             }
+            Value::Int(int) => {
+                return write!(f, "{}", int);
+            }
             Value::String(string) => {
                 return write!(f, "{}", string);
             }
@@ -203,12 +269,30 @@ impl Display for Value {
                     }
                 }
             }
-            Value::FunctionRef(addr, args_count) => {
-                return write!(f, "[function at {} with {} arguments]", addr, args_count);
+            Value::FunctionRef(addr, arity) => {
+                return write!(f, "[function at {} with {} arguments]", addr, arity);
             }
             Value::Lambda(addr, _, args) => {
                 return write!(f, "[lambda at {} with {} arguments]", addr, args);
             }
+            Value::Native(native) => {
+                return write!(
+                    f,
+                    "[native function {} with {} arguments]",
+                    native.name, native.arity
+                );
+            }
+            Value::Range(range) => {
+                let _ = write!(f, "{}..", range.start);
+                if range.inclusive {
+                    let _ = write!(f, "=");
+                }
+                let _ = write!(f, "{}", range.end);
+                if range.step != 1.0 {
+                    let _ = write!(f, " step {}", range.step);
+                }
+                Ok(())
+            }
             Value::Null() => {
                 return write!(f, "null");
             }
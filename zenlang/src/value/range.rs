@@ -0,0 +1,91 @@
+use super::Value;
+use libm::{ceil, floor};
+
+/// Range
+///
+/// A range of numbers made by `start..end` or `start..=end`, with an optional step.
+/// Its numbers are computed when needed, so iterating it doesn't allocate.
+///
+/// Slicing an array or a string with a range keeps only the numbers that are valid indexes,
+/// so `[1, 2, 3][1..10]` is `[2, 3]`, unlike indexing past the end
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+    /// Is the end included in the range
+    pub inclusive: bool,
+    /// Are the start and the step integers, so the numbers of the range are integers
+    pub integer: bool,
+}
+
+impl Range {
+    pub fn new(start: f64, end: f64, step: f64, inclusive: bool) -> Range {
+        return Range {
+            start: start,
+            end: end,
+            step: step,
+            inclusive: inclusive,
+            integer: false,
+        };
+    }
+
+    /// Count of the numbers in the range, may be infinite
+    fn count(&self) -> f64 {
+        let steps = (self.end - self.start) / self.step;
+        let count = if self.inclusive {
+            floor(steps) + 1.0
+        } else {
+            ceil(steps)
+        };
+
+        // NaN fails the comparison too
+        if !(count > 0.0) {
+            return 0.0;
+        }
+        return count;
+    }
+
+    /// Count of the numbers in the range, saturated to usize::MAX
+    pub fn len(&self) -> usize {
+        return self.count() as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// Get a number of the range by its index
+    pub fn get(&self, index: usize) -> Option<f64> {
+        if index as f64 >= self.count() {
+            return None;
+        }
+        return Some(self.start + index as f64 * self.step);
+    }
+
+    /// Get a number of the range by its index as a value, an integer if the range is made of integers
+    pub fn get_value(&self, index: usize) -> Option<Value> {
+        let number = self.get(index)?;
+        if self.integer {
+            return Some(Value::Int(number as i64));
+        }
+        return Some(Value::Number(number));
+    }
+
+    /// Get the indexes of a collection with `len` elements that the range selects, in order,
+    /// numbers outside of `0..len` are skipped
+    pub fn indexes(&self, len: usize) -> impl Iterator<Item = usize> + '_ {
+        // skip the numbers before the first valid index without visiting them
+        let first = if self.step > 0.0 {
+            ceil(-self.start / self.step)
+        } else {
+            ceil((self.start - (len as f64 - 1.0)) / -self.step)
+        };
+        let first = if first > 0.0 { first as usize } else { 0 };
+
+        return (first..self.len())
+            .map(|index| self.start + index as f64 * self.step)
+            .take_while(move |number| *number >= 0.0 && *number < len as f64)
+            .map(|number| number as usize);
+    }
+}
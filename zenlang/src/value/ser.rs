@@ -0,0 +1,427 @@
+use crate::json::MAX_DEPTH;
+use crate::value::*;
+use ::serde::Serialize;
+use ::serde::ser::{self, Error as _};
+use alloc::format;
+
+/// Convert any serializable Rust value into a value
+///
+/// Structs and maps become dictionaries, sequences and tuples become arrays.
+/// Enum variants with data become a dictionary with a single key, the variant name
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, ValueError> {
+    return value.serialize(Serializer);
+}
+
+fn make_object(object: Object) -> Value {
+    return Value::Object(Rc::new(RefCell::new(object)));
+}
+
+fn make_variant(variant: &str, value: Value) -> Value {
+    let mut dict = BTreeMap::new();
+    dict.insert(variant.into(), value);
+    return make_object(Object::Dictionary(dict));
+}
+
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return Nested {
+            value: self,
+            depth: 0,
+        }
+        .serialize(serializer);
+    }
+}
+
+impl Serialize for Object {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serialize_object(self, 1, serializer);
+    }
+}
+
+/// A value inside `depth` arrays and dictionaries, the depth is limited so a value
+/// that contains itself gives an error instead of overflowing the stack
+struct Nested<'a> {
+    value: &'a Value,
+    depth: usize,
+}
+
+impl Serialize for Nested<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value {
+            Value::Number(num) => serializer.serialize_f64(*num),
+            Value::Int(int) => serializer.serialize_i64(*int),
+            Value::String(string) => serializer.serialize_str(string),
+            Value::Boolean(boolean) => serializer.serialize_bool(*boolean),
+            Value::Object(obj) => {
+                if self.depth >= MAX_DEPTH {
+                    return Err(S::Error::custom("maximum nesting depth exceeded"));
+                }
+                serialize_object(&obj.borrow(), self.depth + 1, serializer)
+            }
+            Value::Null() => serializer.serialize_unit(),
+            _ => Err(S::Error::custom(format!(
+                "cannot serialize a {}",
+                self.value.get_type()
+            ))),
+        }
+    }
+}
+
+fn serialize_object<S: ser::Serializer>(
+    object: &Object,
+    depth: usize,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let nested = |value| Nested {
+        value: value,
+        depth: depth,
+    };
+    match object {
+        Object::Array(array) => serializer.collect_seq(array.iter().map(nested)),
+        Object::Dictionary(dict) => {
+            serializer.collect_map(dict.iter().map(|(key, value)| (key, nested(value))))
+        }
+    }
+}
+
+/// Serializer producing values, see [to_value]
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = ValueError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeDictionary;
+    type SerializeStruct = SerializeDictionary;
+    type SerializeStructVariant = SerializeDictionary;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, ValueError> {
+        return Ok(Value::Boolean(v));
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, ValueError> {
+        return Ok(Value::Int(v as i64));
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, ValueError> {
+        return match i64::try_from(v) {
+            Ok(int) => Ok(Value::Int(int)),
+            Err(_) => Ok(Value::Number(v as f64)),
+        };
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, ValueError> {
+        return Ok(Value::Number(v as f64));
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, ValueError> {
+        return Ok(Value::Number(v));
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, ValueError> {
+        return Ok(Value::String(v.into()));
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, ValueError> {
+        return Ok(Value::String(v.into()));
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, ValueError> {
+        let array = v.iter().map(|byte| Value::Int(*byte as i64)).collect();
+        return Ok(make_object(Object::Array(array)));
+    }
+
+    fn serialize_none(self) -> Result<Value, ValueError> {
+        return Ok(Value::Null());
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, ValueError> {
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<Value, ValueError> {
+        return Ok(Value::Null());
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, ValueError> {
+        return Ok(Value::Null());
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, ValueError> {
+        return Ok(Value::String(variant.into()));
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, ValueError> {
+        return value.serialize(self);
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, ValueError> {
+        return Ok(make_variant(variant, value.serialize(self)?));
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, ValueError> {
+        return Ok(SerializeArray {
+            array: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        });
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, ValueError> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, ValueError> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, ValueError> {
+        return Ok(SerializeArray {
+            array: Vec::with_capacity(len),
+            variant: Some(variant),
+        });
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeDictionary, ValueError> {
+        return Ok(SerializeDictionary {
+            dict: BTreeMap::new(),
+            key: None,
+            variant: None,
+        });
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeDictionary, ValueError> {
+        return self.serialize_map(Some(len));
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeDictionary, ValueError> {
+        return Ok(SerializeDictionary {
+            dict: BTreeMap::new(),
+            key: None,
+            variant: Some(variant),
+        });
+    }
+}
+
+/// Builds an array, optionally wrapped in an enum variant
+pub struct SerializeArray {
+    array: Vec<Value>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray {
+    fn finish(self) -> Result<Value, ValueError> {
+        let value = make_object(Object::Array(self.array));
+        return match self.variant {
+            Some(variant) => Ok(make_variant(variant, value)),
+            None => Ok(value),
+        };
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.array.push(value.serialize(Serializer)?);
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        return ser::SerializeSeq::serialize_element(self, value);
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        return ser::SerializeSeq::serialize_element(self, value);
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        return ser::SerializeSeq::serialize_element(self, value);
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
+
+/// Builds a dictionary, optionally wrapped in an enum variant
+pub struct SerializeDictionary {
+    dict: BTreeMap<String, Value>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeDictionary {
+    fn finish(self) -> Result<Value, ValueError> {
+        let value = make_object(Object::Dictionary(self.dict));
+        return match self.variant {
+            Some(variant) => Ok(make_variant(variant, value)),
+            None => Ok(value),
+        };
+    }
+}
+
+impl ser::SerializeMap for SerializeDictionary {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ValueError> {
+        // dictionary keys are strings, numbers and booleans are converted
+        let key = match key.serialize(Serializer)? {
+            Value::String(string) => string,
+            Value::Number(num) => format!("{}", num),
+            Value::Int(int) => format!("{}", int),
+            Value::Boolean(boolean) => format!("{}", boolean),
+            value => {
+                return Err(ValueError::new(format!(
+                    "dictionary key must be a string, found {}",
+                    value.get_type()
+                )));
+            }
+        };
+        self.key = Some(key);
+        return Ok(());
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        let key = match self.key.take() {
+            Some(key) => key,
+            None => {
+                return Err(ValueError::new(
+                    "serialize_value called before serialize_key".into(),
+                ));
+            }
+        };
+        self.dict.insert(key, value.serialize(Serializer)?);
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeStruct for SerializeDictionary {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        self.dict.insert(key.into(), value.serialize(Serializer)?);
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeDictionary {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        return ser::SerializeStruct::serialize_field(self, key, value);
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        return self.finish();
+    }
+}
@@ -0,0 +1,90 @@
+use crate::module::SourceLocation;
+use crate::vm::{ProgramCounter, RuntimeError, VM};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+/// BacktraceFrame
+///
+/// A single frame of a backtrace, resolved to names and a source location
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktraceFrame {
+    pub pc: ProgramCounter,
+    pub module_name: Option<String>,
+    pub function_name: Option<String>,
+    /// Instruction offset relative to the function address
+    pub offset: usize,
+    /// Source file name, if the module has debug information
+    pub filename: Option<String>,
+    /// Source location, if the module has debug information
+    pub location: Option<SourceLocation>,
+}
+
+impl Display for BacktraceFrame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}::{}+{} at {}",
+            self.module_name.as_deref().unwrap_or("<unknown module>"),
+            self.function_name
+                .as_deref()
+                .unwrap_or("<unknown function>"),
+            self.offset,
+            self.pc
+        )
+    }
+}
+
+impl VM {
+    /// Resolve a pc to a backtrace frame
+    pub fn resolve_frame(&self, pc: &ProgramCounter) -> BacktraceFrame {
+        let mut frame = BacktraceFrame {
+            pc: *pc,
+            module_name: None,
+            function_name: None,
+            offset: pc.inst,
+            filename: None,
+            location: None,
+        };
+
+        if let Some(module) = self.modules.get(pc.module) {
+            frame.module_name = Some(module.name.clone());
+            for function in module.functions.iter().rev() {
+                if pc.inst >= function.addr {
+                    frame.function_name = Some(function.name.clone());
+                    frame.offset = pc.inst - function.addr;
+                    break;
+                }
+            }
+
+            frame.filename = module.get_source_filename().map(|name| name.into());
+            frame.location = module.get_source_location(pc.inst);
+        }
+
+        return frame;
+    }
+
+    /// Resolve a pc and the return addresses leading to it, most recent call first
+    pub fn resolve_frames(
+        &self,
+        pc: &ProgramCounter,
+        call_stack: &[ProgramCounter],
+    ) -> Vec<BacktraceFrame> {
+        let mut frames = Vec::new();
+        frames.push(self.resolve_frame(pc));
+        for return_pc in call_stack.iter().rev() {
+            frames.push(self.resolve_frame(return_pc));
+        }
+        return frames;
+    }
+
+    /// Get the backtrace of the current VM state, most recent call first
+    pub fn backtrace(&self) -> Vec<BacktraceFrame> {
+        return self.resolve_frames(&self.pc, &self.call_stack);
+    }
+
+    /// Get the backtrace captured by a runtime error, most recent call first
+    pub fn error_backtrace(&self, error: &RuntimeError) -> Vec<BacktraceFrame> {
+        return self.resolve_frames(&error.pc, &error.call_stack);
+    }
+}
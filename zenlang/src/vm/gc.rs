@@ -0,0 +1,293 @@
+use crate::scope::Scope;
+use crate::value::*;
+use crate::vm::VM;
+use alloc::collections::btree_map::BTreeMap;
+use alloc::collections::btree_set::BTreeSet;
+use alloc::rc::*;
+use alloc::vec;
+use alloc::vec::*;
+use core::cell::RefCell;
+
+/// Default number of tracked objects that triggers a collection
+pub static GC_THRESHOLD: usize = 10000;
+
+/// A heap allocation tracked by the garbage collector
+#[derive(Debug, Clone)]
+pub enum GcNode {
+    Object(Weak<RefCell<Object>>),
+    Scope(Weak<RefCell<Scope>>),
+    /// Variable captured by a lambda
+    Cell(Weak<RefCell<Value>>),
+}
+
+/// Garbage collector statistics
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GcStats {
+    /// Number of collections performed
+    pub collections: usize,
+    /// Objects, scopes and cells freed by all collections
+    pub collected: usize,
+    /// Objects and scopes freed by the last collection
+    pub last_collected: usize,
+    /// Objects and scopes alive after the last collection
+    pub live: usize,
+    /// Objects and scopes tracked right now, including ones already freed
+    pub tracked: usize,
+}
+
+enum Node {
+    Object(Rc<RefCell<Object>>),
+    Scope(Rc<RefCell<Scope>>),
+    Cell(Rc<RefCell<Value>>),
+}
+
+impl Node {
+    fn strong_count(&self) -> usize {
+        match self {
+            Node::Object(obj) => Rc::strong_count(obj),
+            Node::Scope(scope) => Rc::strong_count(scope),
+            Node::Cell(cell) => Rc::strong_count(cell),
+        }
+    }
+
+    /// Call `f` with the address of every tracked allocation this node references.
+    /// Returns false if the node is borrowed and can't be inspected
+    fn for_each_child(&self, f: &mut impl FnMut(usize)) -> bool {
+        match self {
+            Node::Object(obj) => match obj.try_borrow().as_deref() {
+                Ok(Object::Array(array)) => array.iter().for_each(|value| value_child(value, f)),
+                Ok(Object::Dictionary(dict)) => {
+                    dict.values().for_each(|value| value_child(value, f))
+                }
+                Err(_) => return false,
+            },
+            Node::Scope(scope) => match scope.try_borrow() {
+                Ok(scope) => {
+                    scope
+                        .vars
+                        .iter()
+                        .for_each(|(_, value)| value_child(value, f));
+                    scope.slots.iter().for_each(|value| value_child(value, f));
+                    scope
+                        .cells
+                        .iter()
+                        .for_each(|(_, cell)| f(cell.as_ptr() as usize));
+                }
+                Err(_) => return false,
+            },
+            Node::Cell(cell) => match cell.try_borrow() {
+                Ok(value) => value_child(&value, f),
+                Err(_) => return false,
+            },
+        }
+        return true;
+    }
+
+    /// Drop the references held by the node, returning them so they're dropped after the collection
+    fn clear(&self) -> Vec<Value> {
+        match self {
+            Node::Object(obj) => match &mut *obj.borrow_mut() {
+                Object::Array(array) => core::mem::take(array),
+                Object::Dictionary(dict) => core::mem::take(dict)
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect(),
+            },
+            Node::Scope(scope) => {
+                let mut scope = scope.borrow_mut();
+                let cells = core::mem::take(&mut scope.cells);
+                let mut values: Vec<Value> = core::mem::take(&mut scope.vars)
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect();
+                values.append(&mut scope.slots);
+                // the cells are dropped along with the values
+                values.extend(
+                    cells
+                        .into_iter()
+                        .map(|(_, cell)| core::mem::take(&mut *cell.borrow_mut())),
+                );
+                values
+            }
+            Node::Cell(cell) => vec![core::mem::take(&mut *cell.borrow_mut())],
+        }
+    }
+}
+
+fn value_child(value: &Value, f: &mut impl FnMut(usize)) {
+    match value {
+        Value::Object(obj) => f(obj.as_ptr() as usize),
+        Value::Lambda(_, scope, _) => f(scope.as_ptr() as usize),
+        _ => {}
+    }
+}
+
+impl VM {
+    /// Track an object created by the VM
+    pub(crate) fn track_object(&mut self, obj: &Rc<RefCell<Object>>) {
+        self.gc_tracked.push(GcNode::Object(Rc::downgrade(obj)));
+    }
+
+    /// Track a closure scope created by the VM
+    pub(crate) fn track_scope(&mut self, scope: &Rc<RefCell<Scope>>) {
+        self.gc_tracked.push(GcNode::Scope(Rc::downgrade(scope)));
+    }
+
+    /// Track a cell of a captured variable
+    pub(crate) fn track_cell(&mut self, cell: &Rc<RefCell<Value>>) {
+        self.gc_tracked.push(GcNode::Cell(Rc::downgrade(cell)));
+    }
+
+    /// Track a value and everything reachable from it, used for values created outside the VM
+    pub fn track_value(&mut self, value: &Value) {
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![value.clone()];
+        while let Some(value) = pending.pop() {
+            match &value {
+                Value::Object(obj) => {
+                    if !visited.insert(obj.as_ptr() as usize) {
+                        continue;
+                    }
+                    self.track_object(obj);
+                    match obj.try_borrow().as_deref() {
+                        Ok(Object::Array(array)) => pending.extend(array.iter().cloned()),
+                        Ok(Object::Dictionary(dict)) => pending.extend(dict.values().cloned()),
+                        Err(_) => {}
+                    }
+                }
+                Value::Lambda(_, scope, _) => {
+                    if !visited.insert(scope.as_ptr() as usize) {
+                        continue;
+                    }
+                    self.track_scope(scope);
+                    if let Ok(scope) = scope.try_borrow() {
+                        pending.extend(scope.vars.iter().map(|(_, value)| value.clone()));
+                        pending.extend(scope.slots.iter().cloned());
+                        for (_, cell) in scope.cells.iter() {
+                            if visited.insert(cell.as_ptr() as usize) {
+                                self.track_cell(cell);
+                                pending.push(cell.borrow().clone());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Get the garbage collector statistics
+    pub fn gc_stats(&self) -> GcStats {
+        let mut stats = self.gc_stats;
+        stats.tracked = self.gc_tracked.len();
+        return stats;
+    }
+
+    /// Collect when the number of tracked allocations reached the threshold
+    pub(crate) fn maybe_collect(&mut self) {
+        // allow the heap to double before the next collection
+        let next = core::cmp::max(self.gc_threshold, self.gc_stats.live * 2);
+        if self.gc_threshold != 0 && self.gc_tracked.len() >= next {
+            self.collect();
+        }
+    }
+
+    /// Free objects, closure scopes and captured variables that are only referenced by reference cycles
+    ///
+    /// Values are reference counted, so everything else is freed as soon as it's dropped.
+    /// For every tracked allocation the references from other tracked allocations are subtracted
+    /// from its reference count. Allocations that still have references left are used by the VM
+    /// (or by the host), everything they reach is alive and the rest is garbage.
+    /// Returns the number of freed objects and scopes
+    pub fn collect(&mut self) -> usize {
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut index: BTreeMap<usize, usize> = BTreeMap::new();
+
+        for tracked in core::mem::take(&mut self.gc_tracked) {
+            let (node, addr) = match tracked {
+                GcNode::Object(weak) => match weak.upgrade() {
+                    Some(obj) => {
+                        let addr = obj.as_ptr() as usize;
+                        (Node::Object(obj), addr)
+                    }
+                    None => continue,
+                },
+                GcNode::Scope(weak) => match weak.upgrade() {
+                    Some(scope) => {
+                        let addr = scope.as_ptr() as usize;
+                        (Node::Scope(scope), addr)
+                    }
+                    None => continue,
+                },
+                GcNode::Cell(weak) => match weak.upgrade() {
+                    Some(cell) => {
+                        let addr = cell.as_ptr() as usize;
+                        (Node::Cell(cell), addr)
+                    }
+                    None => continue,
+                },
+            };
+            if index.contains_key(&addr) {
+                continue;
+            }
+            index.insert(addr, nodes.len());
+            nodes.push(node);
+        }
+
+        // references from outside of the tracked allocations, the upgraded Rc doesn't count
+        let mut external: Vec<isize> = nodes
+            .iter()
+            .map(|node| node.strong_count() as isize - 1)
+            .collect();
+        let mut alive = Vec::new();
+        for (i, node) in nodes.iter().enumerate() {
+            let inspected = node.for_each_child(&mut |addr| {
+                if let Some(child) = index.get(&addr) {
+                    external[*child] -= 1;
+                }
+            });
+            if !inspected {
+                alive.push(i);
+            }
+        }
+
+        let mut reachable = vec![false; nodes.len()];
+        alive.extend((0..nodes.len()).filter(|i| external[*i] > 0));
+        while let Some(i) = alive.pop() {
+            if reachable[i] {
+                continue;
+            }
+            reachable[i] = true;
+            nodes[i].for_each_child(&mut |addr| {
+                if let Some(child) = index.get(&addr) {
+                    if !reachable[*child] {
+                        alive.push(*child);
+                    }
+                }
+            });
+        }
+
+        let mut garbage = Vec::new();
+        let mut collected = 0;
+        for (i, node) in nodes.iter().enumerate() {
+            if reachable[i] {
+                self.gc_tracked.push(match node {
+                    Node::Object(obj) => GcNode::Object(Rc::downgrade(obj)),
+                    Node::Scope(scope) => GcNode::Scope(Rc::downgrade(scope)),
+                    Node::Cell(cell) => GcNode::Cell(Rc::downgrade(cell)),
+                });
+            } else {
+                garbage.push(node.clear());
+                collected += 1;
+            }
+        }
+        drop(garbage);
+
+        let live = self.gc_tracked.len();
+        self.gc_stats.collections += 1;
+        self.gc_stats.collected += collected;
+        self.gc_stats.last_collected = collected;
+        self.gc_stats.live = live;
+        return collected;
+    }
+}
@@ -0,0 +1,174 @@
+use crate::scope::Scope;
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM};
+use alloc::collections::btree_set::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+
+/// Estimated size of a string with `len` bytes
+pub fn string_size(len: usize) -> usize {
+    return len;
+}
+
+/// Estimated size of an array with `len` elements
+pub fn array_size(len: usize) -> usize {
+    return len * core::mem::size_of::<Value>();
+}
+
+/// Estimated size of a dictionary entry, excluding the value itself
+pub fn dictionary_entry_size(key: &str) -> usize {
+    return core::mem::size_of::<String>() + core::mem::size_of::<Value>() + key.len();
+}
+
+/// Estimated size owned by a value, excluding the objects it points to
+pub fn value_size(value: &Value) -> usize {
+    match value {
+        Value::String(string) => string_size(string.len()),
+        Value::Object(obj) => match obj.try_borrow().as_deref() {
+            Ok(Object::Array(array)) => array_size(array.len()),
+            Ok(Object::Dictionary(dict)) => dict.keys().map(|key| dictionary_entry_size(key)).sum(),
+            // being modified by the current opcode
+            Err(_) => 0,
+        },
+        _ => 0,
+    }
+}
+
+impl VM {
+    /// Limit the estimated heap size of the VM to `bytes`
+    ///
+    /// Allocations going over the limit raise an [RuntimeErrorKind::OutOfMemory] error
+    pub fn set_memory_limit(&mut self, bytes: usize) {
+        self.memory_limit = Some(bytes);
+    }
+
+    /// Remove the memory limit
+    pub fn remove_memory_limit(&mut self) {
+        self.memory_limit = None;
+    }
+
+    /// Account an allocation of `bytes`
+    ///
+    /// Memory freed by dropped values isn't tracked, so when going over the limit
+    /// the live heap is measured again before failing. Measuring walks the whole heap, so it's
+    /// only done once a sixteenth of the last measured size was allocated since the previous
+    /// walk, an allocation can then fail slightly before the limit. Returns false and sets
+    /// the error if the allocation doesn't fit
+    pub(crate) fn alloc(&mut self, bytes: usize) -> bool {
+        let limit = match self.memory_limit {
+            Some(limit) => limit,
+            None => return true,
+        };
+
+        self.memory_used = self.memory_used.saturating_add(bytes);
+        if self.memory_used <= limit {
+            return true;
+        }
+
+        let allocated = self.memory_used.saturating_sub(self.memory_measured);
+        if allocated >= self.memory_measured / 16 {
+            self.memory_measured = self.heap_size();
+            self.memory_used = self.memory_measured.saturating_add(bytes);
+            if self.memory_used <= limit {
+                return true;
+            }
+        }
+
+        self.memory_used -= bytes;
+        self.set_error(
+            RuntimeErrorKind::OutOfMemory,
+            format!(
+                "memory limit exceeded: allocating {} bytes with {} of {} bytes used",
+                bytes, self.memory_used, limit
+            ),
+        );
+        return false;
+    }
+
+    /// Measure the estimated size of a value and all values reachable from it
+    pub fn deep_size(value: &Value) -> usize {
+        return measure_value(value, &mut BTreeSet::new());
+    }
+
+    /// Measure the estimated size of all values reachable from the VM
+    pub fn heap_size(&self) -> usize {
+        let mut visited = BTreeSet::new();
+        let mut size = 0;
+
+        for value in self.stack.iter() {
+            size += measure_value(value, &mut visited);
+        }
+        for args in self.args.iter() {
+            for value in args.iter() {
+                size += measure_value(value, &mut visited);
+            }
+        }
+        for scope in self.scopes.iter() {
+            size += measure_scope(scope, &mut visited);
+        }
+        size += measure_scope(&self.global_scope, &mut visited);
+        size += measure_value(&self.ret, &mut visited);
+        size += measure_value(&self.self_var, &mut visited);
+        size += measure_value(&self.thrown, &mut visited);
+        return size;
+    }
+}
+
+fn measure_scope(scope: &Scope, visited: &mut BTreeSet<usize>) -> usize {
+    let mut size = 0;
+    for (name, value) in scope.vars.iter() {
+        size += dictionary_entry_size(name);
+        size += measure_value(value, visited);
+    }
+    size += array_size(scope.slots.len());
+    for value in scope.slots.iter() {
+        size += measure_value(value, visited);
+    }
+    for (name, cell) in scope.cells.iter() {
+        size += dictionary_entry_size(name);
+        // shared cells are counted once
+        if visited.insert(cell.as_ptr() as usize) {
+            if let Ok(value) = cell.try_borrow() {
+                size += measure_value(&value, visited);
+            }
+        }
+    }
+    return size;
+}
+
+fn measure_value(value: &Value, visited: &mut BTreeSet<usize>) -> usize {
+    match value {
+        Value::String(string) => string_size(string.len()),
+        Value::Object(obj) => {
+            // shared and cyclic objects are counted once
+            if !visited.insert(obj.as_ptr() as usize) {
+                return 0;
+            }
+            let mut size = value_size(value);
+            match obj.try_borrow().as_deref() {
+                Ok(Object::Array(array)) => {
+                    for element in array.iter() {
+                        size += measure_value(element, visited);
+                    }
+                }
+                Ok(Object::Dictionary(dict)) => {
+                    for element in dict.values() {
+                        size += measure_value(element, visited);
+                    }
+                }
+                Err(_) => {}
+            }
+            return size;
+        }
+        Value::Lambda(_, scope, _) => {
+            if !visited.insert(scope.as_ptr() as usize) {
+                return 0;
+            }
+            return match scope.try_borrow() {
+                Ok(scope) => measure_scope(&scope, visited),
+                Err(_) => 0,
+            };
+        }
+        _ => 0,
+    }
+}
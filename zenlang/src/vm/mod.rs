@@ -1,13 +1,24 @@
 //! VM
 //!
 //! Contains the virtual machine for ZenLang
+mod backtrace;
+mod gc;
+mod memory;
+mod native;
 pub mod opcodes;
 mod program_counter;
+mod runtime_error;
 mod stop_reason;
 mod vm;
 mod vm_compute;
 mod vm_opcode;
 mod vmcall;
+pub use backtrace::*;
+pub use gc::*;
+pub use memory::*;
+pub use native::*;
 pub use program_counter::*;
+pub use runtime_error::*;
 pub use stop_reason::*;
 pub use vm::*;
+pub(crate) use vm_compute::{floor_div, int_floor_div, int_modulo, int_pow, modulo};
@@ -0,0 +1,96 @@
+use crate::interop::{FromArgs, ToValue};
+use crate::value::Value;
+use crate::vm::{RuntimeError, RuntimeErrorKind, VM};
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+
+/// Signature of a native function
+pub type NativeFn = dyn Fn(&mut VM, &[Value]) -> Result<Value, RuntimeError>;
+
+/// NativeFunction
+///
+/// A Rust function that can be called from ZenLang
+pub struct NativeFunction {
+    pub name: String,
+    /// Argument count
+    pub arity: usize,
+    pub func: Rc<NativeFn>,
+}
+
+impl core::fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NativeFunction")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
+impl VM {
+    /// Register a native function, which is resolved by name like a module function
+    ///
+    /// Registering a function with the same name again replaces it. Modules loaded afterwards
+    /// can't define a function with the same name, it would hide the native
+    /// ```ignore
+    /// vm.register_native("add", 2, |_vm, args| match (&args[0], &args[1]) {
+    ///     (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+    ///     _ => Err(_vm.runtime_error(RuntimeErrorKind::TypeMismatch, "add: expected integers".into())),
+    /// });
+    /// ```
+    pub fn register_native<F>(&mut self, name: &str, arity: usize, func: F)
+    where
+        F: Fn(&mut VM, &[Value]) -> Result<Value, RuntimeError> + 'static,
+    {
+        let native = Rc::new(NativeFunction {
+            name: name.into(),
+            arity: arity,
+            func: Rc::new(func),
+        });
+
+        for existing in self.natives.iter_mut() {
+            if existing.name == name {
+                *existing = native;
+                return;
+            }
+        }
+        self.natives.push(native);
+    }
+
+    /// Register a native function taking and returning Rust types
+    ///
+    /// Arguments are converted with [crate::interop::FromValue], a failed conversion is a type mismatch error
+    /// ```ignore
+    /// vm.register_fn("add", |(a, b): (f64, f64)| a + b);
+    /// ```
+    pub fn register_fn<A, R, F>(&mut self, name: &str, func: F)
+    where
+        A: FromArgs,
+        R: ToValue,
+        F: Fn(A) -> R + 'static,
+    {
+        let fn_name: String = name.into();
+        self.register_native(name, A::ARITY, move |vm, args| match A::from_args(args) {
+            Ok(args) => Ok(func(args).to_value()),
+            Err(e) => Err(vm.runtime_error(
+                RuntimeErrorKind::TypeMismatch,
+                format!("{}: {}", fn_name, e),
+            )),
+        });
+    }
+
+    /// Get a native function by a name
+    pub fn get_native(&self, name: &str) -> Option<Rc<NativeFunction>> {
+        for native in self.natives.iter() {
+            if native.name == name {
+                return Some(native.clone());
+            }
+        }
+        return None;
+    }
+
+    /// Create a runtime error at the current pc, for returning it from native functions
+    pub fn runtime_error(&self, kind: RuntimeErrorKind, message: String) -> RuntimeError {
+        return RuntimeError::new(kind, message, self.pc, self.call_stack.clone());
+    }
+}
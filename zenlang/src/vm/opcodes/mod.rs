@@ -6,9 +6,16 @@ mod vm_cafse;
 mod vm_call;
 mod vm_cdfse;
 mod vm_cmp;
+mod vm_csfse;
 mod vm_iafs;
+mod vm_iter;
 mod vm_lambda;
 mod vm_load_constants;
+mod vm_match;
+mod vm_range;
 mod vm_ret;
+mod vm_try;
+mod vm_unary;
+mod vm_unpack;
 mod vm_vars;
 mod vm_vmcall;
@@ -1,5 +1,5 @@
 use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM, array_size, dictionary_entry_size};
 use alloc::format;
 use alloc::string::*;
 
@@ -12,18 +12,35 @@ impl VM {
             Value::Object(obj) => match &mut *obj.borrow_mut() {
                 Object::Array(array) => {
                     let usz_index: usize;
-                    if let Value::Number(index) = index {
-                        usz_index = index as usize;
+                    if let Value::Number(_) | Value::Int(_) = index {
+                        usz_index = match index.as_index() {
+                            Some(index) => index,
+                            None => {
+                                self.set_error(
+                                    RuntimeErrorKind::IndexOutOfBounds,
+                                    format!("aiafs failed: invalid index {}", index),
+                                );
+                                return;
+                            }
+                        };
                     } else {
-                        self.error =
-                            format!("aiafs failed: expected number when indexing an array");
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            format!("aiafs failed: expected number when indexing an array"),
+                        );
                         return;
                     }
 
                     if usz_index > array.len() {
-                        self.error = format!("aiafs failed: index outside bounds");
+                        self.set_error(
+                            RuntimeErrorKind::IndexOutOfBounds,
+                            format!("aiafs failed: index outside bounds"),
+                        );
                         return;
                     } else if usz_index == array.len() {
+                        if !self.alloc(array_size(1)) {
+                            return;
+                        }
                         array.push(set_to);
                         return;
                     }
@@ -35,16 +52,25 @@ impl VM {
                     if let Value::String(s) = index {
                         s_index = s;
                     } else {
-                        self.error =
-                            format!("aiafs failed: expected string when indexing a dictionary");
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            format!("aiafs failed: expected string when indexing a dictionary"),
+                        );
                         return;
                     }
 
+                    if !dict.contains_key(&s_index) && !self.alloc(dictionary_entry_size(&s_index))
+                    {
+                        return;
+                    }
                     dict.insert(s_index, set_to);
                 }
             },
             _ => {
-                self.error = format!("aiafs failed: value is not an object");
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!("aiafs failed: value is not an object"),
+                );
             }
         }
         return;
@@ -58,21 +84,30 @@ impl VM {
         if let Some(value) = self.stack.pop() {
             index = value;
         } else {
-            self.error = format!("aiafs failed: no more values on stack for index");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("aiafs failed: no more values on stack for index"),
+            );
             return;
         }
 
         if let Some(value) = self.stack.pop() {
             set_value = value;
         } else {
-            self.error = format!("aiafs failed: no more values on stack for set value");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("aiafs failed: no more values on stack for set value"),
+            );
             return;
         }
 
         if let Some(value) = self.stack.pop() {
             object = value;
         } else {
-            self.error = format!("aiafs failed: no more values on stack for object");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("aiafs failed: no more values on stack for object"),
+            );
             return;
         }
 
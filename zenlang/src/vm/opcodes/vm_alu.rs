@@ -18,4 +18,16 @@ impl VM {
         let value = self.compute_stack_values(AstBinopOp::DIV);
         self.stack.push(value);
     }
+    pub fn op_mod(&mut self) {
+        let value = self.compute_stack_values(AstBinopOp::MOD);
+        self.stack.push(value);
+    }
+    pub fn op_intdiv(&mut self) {
+        let value = self.compute_stack_values(AstBinopOp::INTDIV);
+        self.stack.push(value);
+    }
+    pub fn op_pow(&mut self) {
+        let value = self.compute_stack_values(AstBinopOp::POW);
+        self.stack.push(value);
+    }
 }
@@ -21,4 +21,9 @@ impl VM {
         let value = self.compute_stack_values(AstBinopOp::BITOR);
         self.stack.push(value);
     }
+
+    pub fn op_bxor(&mut self) {
+        let value = self.compute_stack_values(AstBinopOp::BITXOR);
+        self.stack.push(value);
+    }
 }
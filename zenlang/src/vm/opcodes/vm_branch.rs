@@ -1,5 +1,5 @@
 use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM};
 use alloc::format;
 
 impl VM {
@@ -7,23 +7,35 @@ impl VM {
         if let Some(value) = self.stack.pop() {
             if let Value::Boolean(flag) = value {
                 if flag {
-                    self.pc.inst = addr - 1;
+                    self.pc.inst = addr.wrapping_sub(1);
                 }
                 return;
             }
             if let Value::Number(num) = value {
                 if num != 0.0 {
-                    self.pc.inst = addr - 1;
+                    self.pc.inst = addr.wrapping_sub(1);
+                }
+                return;
+            }
+            if let Value::Int(int) = value {
+                if int != 0 {
+                    self.pc.inst = addr.wrapping_sub(1);
                 }
                 return;
             }
 
-            self.error = format!(
-                "bst failed: value is not of an acceptable type ({:?})",
-                value
+            self.set_error(
+                RuntimeErrorKind::TypeMismatch,
+                format!(
+                    "bst failed: value is not of an acceptable type ({:?})",
+                    value
+                ),
             );
         } else {
-            self.error = "bst failed: no value on stack".into();
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                "bst failed: no value on stack".into(),
+            );
         }
     }
 
@@ -32,13 +44,20 @@ impl VM {
             if let Value::Null() = value {
                 return;
             }
-            self.pc.inst = addr - 1;
+            self.pc.inst = addr.wrapping_sub(1);
         } else {
-            self.error = "bsnn failed: no value on stack".into();
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                "bsnn failed: no value on stack".into(),
+            );
         }
     }
 
+    /// Branch to `addr`, step adds 1 to the pc after every opcode
+    ///
+    /// A loop at the start of the module branches to 0, the subtraction wraps around and step's
+    /// `wrapping_add` brings it back to 0
     pub fn op_branch(&mut self, addr: usize) {
-        self.pc.inst = addr - 1;
+        self.pc.inst = addr.wrapping_sub(1);
     }
 }
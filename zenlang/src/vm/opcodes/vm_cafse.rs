@@ -1,5 +1,5 @@
 use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM, array_size};
 use alloc::format;
 use alloc::rc::*;
 use alloc::vec::*;
@@ -7,16 +7,24 @@ use core::cell::*;
 
 impl VM {
     pub fn op_cafse(&mut self, items: u64) {
+        if !self.alloc(array_size(items as usize)) {
+            return;
+        }
+
         let mut vec = Vec::<Value>::new();
         for _ in 0..items {
             if let Some(stack_value) = self.stack.pop() {
                 vec.insert(0, stack_value);
             } else {
-                self.error = format!("cafse failed: no more values on stack");
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    format!("cafse failed: no more values on stack"),
+                );
                 return;
             }
         }
         let obj = Rc::new(RefCell::new(Object::Array(vec)));
+        self.track_object(&obj);
 
         let v = Value::Object(obj);
 
@@ -1,8 +1,11 @@
+use crate::module::Arity;
 use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM, array_size};
 use alloc::format;
+use alloc::rc::*;
 use alloc::string::*;
 use alloc::vec::*;
+use core::cell::*;
 
 impl VM {
     pub fn op_beginargs(&mut self) {
@@ -13,56 +16,143 @@ impl VM {
         match self.args.last_mut() {
             Some(args) => args,
             None => {
-                self.error = "pusharg: beginargs wasn't called".into();
+                self.set_error(
+                    RuntimeErrorKind::InvalidBytecode,
+                    "pusharg: beginargs wasn't called".into(),
+                );
                 return;
             }
         }
         .push(match self.stack.pop() {
             Some(value) => value,
             None => {
-                self.error = "pusharg: no values on stack".into();
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    "pusharg: no values on stack".into(),
+                );
                 return;
             }
         });
     }
 
-    pub fn op_storearg(&mut self, name: &String) {
+    /// Pop the next argument of the call being entered
+    fn pop_arg(&mut self, opcode_name: &str) -> Option<Value> {
         let value = match match self.args.last_mut() {
             Some(args) => args,
             None => {
-                self.error = "storearg: beginargs wasn't called".into();
-                return;
+                self.set_error(
+                    RuntimeErrorKind::InvalidBytecode,
+                    format!("{}: beginargs wasn't called", opcode_name),
+                );
+                return None;
             }
         }
         .pop()
         {
             Some(value) => value,
             None => {
-                self.error = "storearg: no more arguments".into();
-                return;
+                self.set_error(
+                    RuntimeErrorKind::ArgumentCount,
+                    format!("{}: no more arguments", opcode_name),
+                );
+                return None;
             }
         };
 
         if self.args.last().unwrap().is_empty() {
             self.args.pop();
         }
+        return Some(value);
+    }
+
+    pub fn op_storearg(&mut self, name: &String) {
+        let value = match self.pop_arg("storearg") {
+            Some(value) => value,
+            None => return,
+        };
 
         let scope = self.scopes.last_mut().unwrap();
         scope.create_if_doesnt_exist(&name);
         *scope.get_mut(&name).unwrap() = value;
     }
 
+    pub fn op_storearg_local(&mut self, slot: u16) {
+        let value = match self.pop_arg("storeargl") {
+            Some(value) => value,
+            None => return,
+        };
+
+        match self.scopes.last_mut() {
+            Some(scope) => *scope.get_slot_mut(slot) = value,
+            None => self.set_error(
+                RuntimeErrorKind::InvalidBytecode,
+                "storeargl: scopes is empty".into(),
+            ),
+        }
+    }
+
+    /// Make the arguments of the call being entered match the parameters of the function
+    ///
+    /// Missing arguments are null, so the function gives them their defaults, and the
+    /// extra ones are collected into an array for the rest parameter
+    fn fit_args(&mut self, arity: Arity) -> bool {
+        let args = self.args.last_mut().unwrap();
+        let rest = if arity.variadic && args.len() > arity.max {
+            args.split_off(arity.max)
+        } else {
+            Vec::new()
+        };
+        args.resize(arity.max, Value::Null());
+        if !arity.variadic {
+            return true;
+        }
+
+        if !self.alloc(array_size(rest.len())) {
+            return false;
+        }
+        let obj = Rc::new(RefCell::new(Object::Array(rest)));
+        self.track_object(&obj);
+        self.args.last_mut().unwrap().push(Value::Object(obj));
+        return true;
+    }
+
     pub fn op_call(&mut self) {
         let value = match self.stack.pop() {
             Some(value) => value,
             None => {
-                self.error = "call: stack is empty".into();
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    "call: stack is empty".into(),
+                );
                 return;
             }
         };
 
         match value {
-            Value::FunctionRef(addr, args_count) => {
+            Value::FunctionRef(addr, arity) => {
+                let diff = match self.args.last() {
+                    Some(args) => args,
+                    None => {
+                        self.set_error(
+                            RuntimeErrorKind::InvalidBytecode,
+                            "call: beginargs wasn't called".into(),
+                        );
+                        return;
+                    }
+                }
+                .len();
+
+                if !arity.accepts(diff) {
+                    self.set_error(RuntimeErrorKind::ArgumentCount, format!(
+                        "call: expected {} arguments, but provided {} (trying to call a function at {})",
+                        arity, diff, addr,
+                    ));
+                    return;
+                }
+                if !self.fit_args(arity) {
+                    return;
+                }
+
                 self.call_stack.push(self.pc);
                 self.check_stack_overflow();
                 self.pc = addr;
@@ -73,51 +163,80 @@ impl VM {
                 let scope = self.scopes.last_mut().unwrap();
                 scope.create_if_doesnt_exist(this_name);
                 *scope.get_mut(this_name).unwrap() = core::mem::take(&mut self.self_var);
-
+            }
+            Value::Lambda(pc, scope, args_count) => {
                 let diff = match self.args.last() {
                     Some(args) => args,
                     None => {
-                        self.error = "call: beginargs wasn't called".into();
+                        self.set_error(
+                            RuntimeErrorKind::InvalidBytecode,
+                            "call: beginargs wasn't called".into(),
+                        );
                         return;
                     }
                 }
                 .len();
 
                 if diff != args_count {
-                    self.error = format!(
-                        "call: expected exactly {} arguments, but provided {} (trying to call a function at {})",
-                        args_count, diff, self.pc,
-                    );
+                    self.set_error(RuntimeErrorKind::ArgumentCount, format!(
+                        "call: expected exactly {} arguments, but provided {} (trying to call a lambda at {})",
+                        args_count, diff, pc,
+                    ));
+                    return;
                 }
-            }
-            Value::Lambda(pc, scope, args_count) => {
+
                 self.call_stack.push(self.pc);
                 self.check_stack_overflow();
                 self.pc = pc;
                 self.pc.inst = self.pc.inst.wrapping_sub(1);
 
                 self.scopes.push((&*scope.borrow()).clone());
-
-                let diff = match self.args.last() {
+            }
+            Value::Native(native) => {
+                let args = match self.args.pop() {
                     Some(args) => args,
                     None => {
-                        self.error = "call: beginargs wasn't called".into();
+                        self.set_error(
+                            RuntimeErrorKind::InvalidBytecode,
+                            "call: beginargs wasn't called".into(),
+                        );
                         return;
                     }
-                }
-                .len();
+                };
 
-                if diff != args_count {
-                    self.error = format!(
-                        "call: expected exactly {} arguments, but provided {} (trying to call a lambda at {})",
-                        args_count, diff, self.pc,
+                if args.len() != native.arity {
+                    self.set_error(
+                        RuntimeErrorKind::ArgumentCount,
+                        format!(
+                            "call: expected exactly {} arguments, but provided {} (trying to call native function {})",
+                            native.arity,
+                            args.len(),
+                            native.name,
+                        ),
                     );
+                    return;
+                }
+
+                self.self_var = Value::Null();
+                match (native.func)(self, &args) {
+                    Ok(value) => {
+                        self.track_value(&value);
+                        self.ret = value;
+                    }
+                    Err(error) => {
+                        if self.error.is_none() {
+                            self.error = Some(error);
+                        }
+                    }
                 }
             }
             _ => {
-                self.error = format!(
-                    "call: value on stack is not a function reference or a lambda ({})",
-                    value.get_type()
+                self.set_error(
+                    RuntimeErrorKind::NotCallable,
+                    format!(
+                        "call: value on stack is not a function reference, a lambda or a native function ({})",
+                        value.get_type()
+                    ),
                 );
             }
         }
@@ -1,5 +1,5 @@
 use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM, dictionary_entry_size};
 use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::rc::*;
@@ -9,6 +9,11 @@ use core::cell::*;
 
 impl VM {
     pub fn op_cdfse(&mut self, names: &Vec<String>) {
+        let size = names.iter().map(|name| dictionary_entry_size(name)).sum();
+        if !self.alloc(size) {
+            return;
+        }
+
         let mut dict = BTreeMap::<String, Value>::new();
         for i in 0..names.len() {
             if let Some(stack_value) = self.stack.pop() {
@@ -16,12 +21,16 @@ impl VM {
                 //items.insert(0, (names[names.len() - i - 1].clone(), stack_value));
                 dict.insert(names[names.len() - i - 1].clone(), stack_value);
             } else {
-                self.error = format!("cdfse failed: no more values on stack");
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    format!("cdfse failed: no more values on stack"),
+                );
                 return;
             }
         }
 
         let obj = Rc::new(RefCell::new(Object::Dictionary(dict)));
+        self.track_object(&obj);
         let v = Value::Object(obj);
         self.stack.push(v);
     }
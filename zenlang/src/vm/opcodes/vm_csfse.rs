@@ -0,0 +1,28 @@
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM, string_size};
+use alloc::format;
+use alloc::string::*;
+
+impl VM {
+    pub fn op_csfse(&mut self, items: u64) {
+        if self.stack.len() < items as usize {
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("csfse failed: no more values on stack"),
+            );
+            return;
+        }
+
+        // values are formatted like stringify does
+        let values = self.stack.split_off(self.stack.len() - items as usize);
+        let mut string = String::new();
+        for value in values.iter() {
+            string.push_str(&format!("{}", value));
+        }
+        if !self.alloc(string_size(string.len())) {
+            return;
+        }
+
+        self.stack.push(Value::String(string));
+    }
+}
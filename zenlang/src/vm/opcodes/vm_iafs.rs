@@ -1,7 +1,10 @@
 use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM, array_size, string_size};
 use alloc::format;
+use alloc::rc::*;
 use alloc::string::*;
+use alloc::vec::*;
+use core::cell::*;
 
 impl VM {
     pub fn op_iafs(&mut self) {
@@ -10,28 +13,37 @@ impl VM {
         if let Some(value) = self.stack.pop() {
             index = value;
         } else {
-            self.error = format!("iafs failed: no more values on stack for index");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("iafs failed: no more values on stack for index"),
+            );
             return;
         }
         if let Some(value) = self.stack.pop() {
             array = value;
         } else {
-            self.error = format!("iafs failed: no more values on stack for array");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("iafs failed: no more values on stack for array"),
+            );
             return;
         }
         self.self_var = array.clone();
 
+        if let Value::Range(range) = index {
+            self.slice(array, &range);
+            return;
+        }
+
         match array {
             Value::Object(obj) => match &*obj.borrow() {
                 Object::Array(array) => {
-                    if let Value::Number(index) = index {
-                        let usize_index = index as usize;
-                        if usize_index >= array.len() {
-                            self.stack.push(Value::Null());
-                            return;
+                    if let Value::Number(_) | Value::Int(_) = index {
+                        // negative and fractional indexes are out of bounds too
+                        match index.as_index().and_then(|index| array.get(index)) {
+                            Some(value) => self.stack.push(value.clone()),
+                            None => self.stack.push(Value::Null()),
                         }
-
-                        self.stack.push(array[usize_index].clone());
                         return;
                     }
                 }
@@ -47,8 +59,8 @@ impl VM {
                 }
             },
             Value::String(string) => {
-                if let Value::Number(index) = index {
-                    if let Some(ch) = string.chars().nth(index as usize) {
+                if let Value::Number(_) | Value::Int(_) = index {
+                    if let Some(ch) = index.as_index().and_then(|index| string.chars().nth(index)) {
                         self.stack.push(Value::String(String::from(ch)));
                     } else {
                         self.stack.push(Value::Null());
@@ -57,9 +69,58 @@ impl VM {
                 }
             }
             _ => {
-                self.error = format!(
-                    "iafs failed: invalid operand types: {:?} {:?}",
-                    array, index
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!(
+                        "iafs failed: invalid operand types: {:?} {:?}",
+                        array, index
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Push the elements of an array or the characters of a string selected by a range
+    fn slice(&mut self, value: Value, range: &Range) {
+        match &value {
+            Value::Object(obj) => {
+                let slice: Vec<Value> = match &*obj.borrow() {
+                    Object::Array(array) => range
+                        .indexes(array.len())
+                        .map(|index| array[index].clone())
+                        .collect(),
+                    Object::Dictionary(_) => {
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            format!("iafs failed: a dictionary can't be sliced"),
+                        );
+                        return;
+                    }
+                };
+                if !self.alloc(array_size(slice.len())) {
+                    return;
+                }
+
+                let obj = Rc::new(RefCell::new(Object::Array(slice)));
+                self.track_object(&obj);
+                self.stack.push(Value::Object(obj));
+            }
+            Value::String(string) => {
+                let chars: Vec<char> = string.chars().collect();
+                let slice: String = range
+                    .indexes(chars.len())
+                    .map(|index| chars[index])
+                    .collect();
+                if !self.alloc(string_size(slice.len())) {
+                    return;
+                }
+
+                self.stack.push(Value::String(slice));
+            }
+            _ => {
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!("iafs failed: a {} can't be sliced", value.get_type()),
                 );
             }
         }
@@ -0,0 +1,160 @@
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM, array_size, string_size};
+use alloc::format;
+use alloc::rc::*;
+use alloc::string::*;
+use alloc::vec;
+use alloc::vec::*;
+use core::cell::*;
+
+/// Advance an iterator made by IterBegin, returns the next key and value
+///
+/// The iterator is an array of the iterated value, the index and
+/// the dictionary keys or the byte offset of the next string character.
+/// Ranges compute their numbers from the index, so they don't allocate
+fn iterator_next(iterator: &mut Vec<Value>) -> Option<(Value, Value)> {
+    let index = match iterator.get(1) {
+        Some(Value::Int(index)) => *index as usize,
+        _ => return None,
+    };
+
+    match (iterator[0].clone(), iterator.get(2).cloned()) {
+        (Value::Object(obj), None) => {
+            let value = match &*obj.borrow() {
+                Object::Array(array) => array.get(index)?.clone(),
+                Object::Dictionary(_) => return None,
+            };
+            iterator[1] = Value::Int((index + 1) as i64);
+            return Some((Value::Int(index as i64), value));
+        }
+        (Value::Range(range), None) => {
+            let number = range.get_value(index)?;
+            iterator[1] = Value::Int((index + 1) as i64);
+            return Some((Value::Int(index as i64), number));
+        }
+        (Value::Object(obj), Some(Value::Object(keys))) => {
+            let (Object::Dictionary(dict), Object::Array(keys)) = (&*obj.borrow(), &*keys.borrow())
+            else {
+                return None;
+            };
+
+            // keys removed by the loop body are skipped
+            for (index, key) in keys.iter().enumerate().skip(index) {
+                if let Value::String(name) = key {
+                    if let Some(value) = dict.get(name) {
+                        iterator[1] = Value::Int((index + 1) as i64);
+                        return Some((key.clone(), value.clone()));
+                    }
+                }
+            }
+            return None;
+        }
+        (Value::String(string), Some(Value::Int(offset))) => {
+            let offset = offset as usize;
+            let ch = string.get(offset..)?.chars().next()?;
+            iterator[1] = Value::Int((index + 1) as i64);
+            iterator[2] = Value::Int((offset + ch.len_utf8()) as i64);
+            return Some((Value::Int(index as i64), Value::String(String::from(ch))));
+        }
+        _ => return None,
+    }
+}
+
+impl VM {
+    pub fn op_iter_begin(&mut self) {
+        let value = match self.stack.pop() {
+            Some(value) => value,
+            None => {
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    format!("iterbegin failed: no value in stack"),
+                );
+                return;
+            }
+        };
+
+        let iterator = match &value {
+            Value::Object(obj) => match &*obj.borrow() {
+                Object::Array(_) => vec![value.clone(), Value::Int(0)],
+                Object::Dictionary(dict) => {
+                    let keys_size: usize = dict.keys().map(|key| string_size(key.len())).sum();
+                    if !self.alloc(array_size(dict.len()) + keys_size) {
+                        return;
+                    }
+
+                    let keys = dict.keys().map(|key| Value::String(key.clone())).collect();
+                    vec![
+                        value.clone(),
+                        Value::Int(0),
+                        Value::Object(Rc::new(RefCell::new(Object::Array(keys)))),
+                    ]
+                }
+            },
+            Value::String(_) => vec![value.clone(), Value::Int(0), Value::Int(0)],
+            Value::Range(_) => vec![value.clone(), Value::Int(0)],
+            _ => {
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!("for: cannot iterate over {}", value.get_type()),
+                );
+                return;
+            }
+        };
+
+        if !self.alloc(array_size(iterator.len())) {
+            return;
+        }
+        self.stack
+            .push(Value::Object(Rc::new(RefCell::new(Object::Array(
+                iterator,
+            )))));
+        self.check_stack_overflow();
+    }
+
+    pub fn op_iter_next(&mut self, addr: usize, push_key: bool) {
+        let iterator = match self.stack.pop() {
+            Some(Value::Object(obj)) => obj,
+            Some(_) => {
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!("iternext failed: value on stack is not an iterator"),
+                );
+                return;
+            }
+            None => {
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    format!("iternext failed: no value in stack"),
+                );
+                return;
+            }
+        };
+
+        let mut is_dictionary = false;
+        let next = match &mut *iterator.borrow_mut() {
+            Object::Array(iterator) => {
+                is_dictionary = matches!(iterator.get(2), Some(Value::Object(_)));
+                iterator_next(iterator)
+            }
+            Object::Dictionary(_) => None,
+        };
+
+        match next {
+            Some((key, value)) => {
+                if push_key {
+                    self.stack.push(value);
+                    self.stack.push(key);
+                } else if is_dictionary {
+                    // iterating a dictionary with a single variable goes over its keys
+                    self.stack.push(key);
+                } else {
+                    self.stack.push(value);
+                }
+                self.check_stack_overflow();
+            }
+            None => {
+                self.pc.inst = addr.wrapping_sub(1);
+            }
+        }
+    }
+}
@@ -1,16 +1,41 @@
 use core::cell::RefCell;
 
 use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use crate::{
+    scope::Scope,
     value::Value,
     vm::{ProgramCounter, VM},
 };
 
 impl VM {
-    pub fn op_lambda(&mut self, pc: usize, args: usize) {
-        let scope = self.scopes.last().unwrap().clone();
-        let scope = Rc::new(RefCell::new(scope));
+    pub fn op_lambda(&mut self, pc: usize, args: usize, captures: &Vec<String>) {
+        let mut env = Scope::new();
+        let mut created = Vec::new();
+        let frame = self.scopes.last_mut().unwrap();
+        for name in captures.iter() {
+            // globals are not captured, unless shadowed by a local variable
+            if frame.get(name).is_none()
+                && frame.get_cell(name).is_none()
+                && self.global_scope.get(name).is_some()
+            {
+                continue;
+            }
+
+            let (cell, is_new) = frame.capture(name);
+            if is_new {
+                created.push(cell.clone());
+            }
+            env.cells.push((name.clone(), cell));
+        }
+        for cell in created.iter() {
+            self.track_cell(cell);
+        }
+
+        let scope = Rc::new(RefCell::new(env));
+        self.track_scope(&scope);
         let lambda = Value::Lambda(ProgramCounter::with(self.pc.module, pc), scope, args);
         self.stack.push(lambda);
     }
@@ -9,6 +9,11 @@ impl VM {
         self.check_stack_overflow();
     }
 
+    pub fn op_load_int(&mut self, value: i64) {
+        self.stack.push(Value::Int(value));
+        self.check_stack_overflow();
+    }
+
     pub fn op_load_null(&mut self) {
         self.stack.push(Value::Null());
         self.check_stack_overflow();
@@ -0,0 +1,49 @@
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM};
+use alloc::format;
+use alloc::string::*;
+
+impl VM {
+    /// Pop the value a match pattern checks
+    fn pop_matched(&mut self, opcode_name: &str) -> Option<Value> {
+        let value = self.stack.pop();
+        if value.is_none() {
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("{} failed: no value in stack", opcode_name),
+            );
+        }
+        return value;
+    }
+
+    pub fn op_match_array(&mut self, len: u64, at_least: bool) {
+        let value = match self.pop_matched("matcharray") {
+            Some(value) => value,
+            None => return,
+        };
+
+        let mut matched = false;
+        if let Value::Object(obj) = &value {
+            if let Object::Array(array) = &*obj.borrow() {
+                let array_len = array.len() as u64;
+                matched = array_len == len || (at_least && array_len > len);
+            }
+        }
+        self.stack.push(Value::Boolean(matched));
+    }
+
+    pub fn op_match_dict(&mut self, keys: &[String]) {
+        let value = match self.pop_matched("matchdict") {
+            Some(value) => value,
+            None => return,
+        };
+
+        let mut matched = false;
+        if let Value::Object(obj) = &value {
+            if let Object::Dictionary(dict) = &*obj.borrow() {
+                matched = keys.iter().all(|key| dict.contains_key(key));
+            }
+        }
+        self.stack.push(Value::Boolean(matched));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM};
+use alloc::format;
+
+impl VM {
+    pub fn op_make_range(&mut self, inclusive: bool, has_step: bool) {
+        let count = if has_step { 3 } else { 2 };
+        if self.stack.len() < count {
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("mkrange failed: no more values on stack"),
+            );
+            return;
+        }
+
+        let step = match has_step {
+            true => self.stack.pop().unwrap(),
+            false => Value::Int(1),
+        };
+        let end = self.stack.pop().unwrap();
+        let start = self.stack.pop().unwrap();
+
+        match (start.as_number(), end.as_number(), step.as_number()) {
+            (Some(start_num), Some(end_num), Some(step_num)) => {
+                if step_num == 0.0 || step_num.is_nan() {
+                    self.set_error(
+                        RuntimeErrorKind::InvalidRange,
+                        format!("range: step must be a non-zero number, got {}", step),
+                    );
+                    return;
+                }
+
+                let mut range = Range::new(start_num, end_num, step_num, inclusive);
+                range.integer = matches!((&start, &step), (Value::Int(_), Value::Int(_)));
+                self.stack.push(Value::Range(range));
+                self.check_stack_overflow();
+            }
+            _ => {
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!(
+                        "range: expected numbers, got {}, {} and {}",
+                        start.get_type(),
+                        end.get_type(),
+                        step.get_type()
+                    ),
+                );
+            }
+        }
+    }
+}
@@ -12,6 +12,14 @@ impl VM {
 
         self.remove_scope();
 
+        // drop the exception handlers installed by the returning function
+        while let Some(handler) = self.handlers.last() {
+            if handler.call_stack_len < self.call_stack.len() {
+                break;
+            }
+            self.handlers.pop();
+        }
+
         if !self.call_stack.is_empty() {
             self.pc = self.call_stack.pop().unwrap();
         } else {
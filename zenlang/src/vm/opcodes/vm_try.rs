@@ -0,0 +1,84 @@
+use crate::value::*;
+use crate::vm::{ExceptionHandler, ProgramCounter, RuntimeError, RuntimeErrorKind, VM};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::rc::*;
+use alloc::string::*;
+use core::cell::*;
+
+impl VM {
+    pub fn op_try_begin(&mut self, addr: usize) {
+        self.handlers.push(ExceptionHandler {
+            pc: ProgramCounter::with(self.pc.module, addr),
+            call_stack_len: self.call_stack.len(),
+            scopes_len: self.scopes.len(),
+            args_len: self.args.len(),
+            stack_len: self.stack.len(),
+        });
+    }
+
+    pub fn op_try_end(&mut self) {
+        if self.handlers.pop().is_none() {
+            self.set_error(
+                RuntimeErrorKind::InvalidBytecode,
+                "tryend failed: no exception handler".into(),
+            );
+        }
+    }
+
+    pub fn op_throw(&mut self) {
+        if let Some(value) = self.stack.pop() {
+            let message = format!("uncaught exception: {}", value);
+            self.thrown = value;
+            self.set_error(RuntimeErrorKind::Thrown, message);
+        } else {
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                "throw failed: no value on stack".into(),
+            );
+        }
+    }
+
+    /// Convert a runtime error into a value that the catch block receives
+    fn error_to_value(&mut self, error: RuntimeError) -> Value {
+        if error.kind == RuntimeErrorKind::Thrown {
+            return core::mem::take(&mut self.thrown);
+        }
+
+        let mut dict = BTreeMap::<String, Value>::new();
+        dict.insert("_typename".into(), Value::String("RuntimeError".into()));
+        dict.insert("kind".into(), Value::String(format!("{:?}", error.kind)));
+        dict.insert("message".into(), Value::String(error.message));
+        let obj = Rc::new(RefCell::new(Object::Dictionary(dict)));
+        self.track_object(&obj);
+        return Value::Object(obj);
+    }
+
+    /// Unwind to the innermost exception handler and pass it the current error
+    ///
+    /// Returns false if the error can't be caught
+    pub(crate) fn catch_error(&mut self) -> bool {
+        match &self.error {
+            Some(error) if error.kind.is_catchable() => {}
+            _ => return false,
+        }
+
+        let handler = match self.handlers.pop() {
+            Some(handler) => handler,
+            None => return false,
+        };
+
+        let error = self.error.take().unwrap();
+        let value = self.error_to_value(error);
+
+        self.call_stack.truncate(handler.call_stack_len);
+        self.scopes.truncate(handler.scopes_len);
+        self.args.truncate(handler.args_len);
+        self.stack.truncate(handler.stack_len);
+        self.stack.push(value);
+
+        self.pc = handler.pc;
+        self.pc.inst = self.pc.inst.wrapping_sub(1);
+        return true;
+    }
+}
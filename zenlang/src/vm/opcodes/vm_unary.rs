@@ -0,0 +1,73 @@
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM};
+use alloc::format;
+
+impl VM {
+    /// Pop the operand of a unary opcode
+    fn pop_unary_operand(&mut self, opcode_name: &str) -> Option<Value> {
+        let value = self.stack.pop();
+        if value.is_none() {
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("{} failed: no value in stack", opcode_name),
+            );
+        }
+        return value;
+    }
+
+    pub fn op_not(&mut self) {
+        let value = match self.pop_unary_operand("not") {
+            Some(value) => value,
+            None => return,
+        };
+
+        // the same values are true as in branches
+        match value {
+            Value::Boolean(flag) => self.stack.push(Value::Boolean(!flag)),
+            Value::Number(num) => self.stack.push(Value::Boolean(num == 0.0)),
+            Value::Int(int) => self.stack.push(Value::Boolean(int == 0)),
+            _ => self.set_error(
+                RuntimeErrorKind::TypeMismatch,
+                format!("not failed: can't negate a {}", value.get_type()),
+            ),
+        }
+    }
+
+    pub fn op_neg(&mut self) {
+        let value = match self.pop_unary_operand("neg") {
+            Some(value) => value,
+            None => return,
+        };
+
+        match value {
+            Value::Number(num) => self.stack.push(Value::Number(-num)),
+            Value::Int(int) => match int.checked_neg() {
+                Some(int) => self.stack.push(Value::Int(int)),
+                None => self.set_error(
+                    RuntimeErrorKind::IntegerOverflow,
+                    "neg failed: integer overflow".into(),
+                ),
+            },
+            _ => self.set_error(
+                RuntimeErrorKind::TypeMismatch,
+                format!("neg failed: can't negate a {}", value.get_type()),
+            ),
+        }
+    }
+
+    pub fn op_bnot(&mut self) {
+        let value = match self.pop_unary_operand("bnot") {
+            Some(value) => value,
+            None => return,
+        };
+
+        match value {
+            Value::Number(num) => self.stack.push(Value::Int(!(num as i64))),
+            Value::Int(int) => self.stack.push(Value::Int(!int)),
+            _ => self.set_error(
+                RuntimeErrorKind::TypeMismatch,
+                format!("bnot failed: can't invert bits of a {}", value.get_type()),
+            ),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::value::*;
+use crate::vm::{RuntimeErrorKind, VM, array_size};
+use alloc::format;
+use alloc::rc::*;
+use alloc::string::*;
+use alloc::vec::*;
+use core::cell::*;
+
+impl VM {
+    /// Pop the value being destructured
+    fn pop_unpacked(&mut self, opcode_name: &str) -> Option<Value> {
+        let value = self.stack.pop();
+        if value.is_none() {
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("{} failed: no value in stack", opcode_name),
+            );
+        }
+        return value;
+    }
+
+    pub fn op_unpack_array(&mut self, count: u64, has_rest: bool) {
+        let value = match self.pop_unpacked("unpackarray") {
+            Some(value) => value,
+            None => return,
+        };
+
+        let array = match &value {
+            Value::Object(obj) => match &*obj.borrow() {
+                Object::Array(array) => Some(array.clone()),
+                Object::Dictionary(_) => None,
+            },
+            _ => None,
+        };
+        let array = match array {
+            Some(array) => array,
+            None => {
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!(
+                        "let: expected an array to destructure, got {}",
+                        value.get_type()
+                    ),
+                );
+                return;
+            }
+        };
+
+        // missing elements are null
+        for index in 0..count as usize {
+            self.stack
+                .push(array.get(index).cloned().unwrap_or(Value::Null()));
+        }
+        if has_rest {
+            let rest: Vec<Value> = array.into_iter().skip(count as usize).collect();
+            if !self.alloc(array_size(rest.len())) {
+                return;
+            }
+            let obj = Rc::new(RefCell::new(Object::Array(rest)));
+            self.track_object(&obj);
+            self.stack.push(Value::Object(obj));
+        }
+        self.check_stack_overflow();
+    }
+
+    pub fn op_unpack_dict(&mut self, keys: &[String]) {
+        let value = match self.pop_unpacked("unpackdict") {
+            Some(value) => value,
+            None => return,
+        };
+
+        let values: Option<Vec<Value>> = match &value {
+            Value::Object(obj) => match &*obj.borrow() {
+                // missing keys are null
+                Object::Dictionary(dict) => Some(
+                    keys.iter()
+                        .map(|key| dict.get(key).cloned().unwrap_or(Value::Null()))
+                        .collect(),
+                ),
+                Object::Array(_) => None,
+            },
+            _ => None,
+        };
+        let values = match values {
+            Some(values) => values,
+            None => {
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!(
+                        "let: expected a dictionary to destructure, got {}",
+                        value.get_type()
+                    ),
+                );
+                return;
+            }
+        };
+
+        self.stack.extend(values);
+        self.check_stack_overflow();
+    }
+}
@@ -1,10 +1,34 @@
 use crate::value::*;
 use crate::vm::ProgramCounter;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM};
 use alloc::format;
 use alloc::string::*;
 
 impl VM {
+    /// Get a reference to a function of a loaded module, only an exported one if `exported`
+    fn function_ref(&self, module_i: usize, name: &str, exported: bool) -> Option<Value> {
+        let func = self
+            .modules
+            .get(module_i)?
+            .functions
+            .iter()
+            .find(|func| func.name == name && (func.public || !exported))?;
+
+        let mut addr = ProgramCounter::new();
+        addr.inst = func.addr;
+        addr.module = module_i;
+        return Some(Value::FunctionRef(addr, func.arity));
+    }
+
+    /// Get a reference to a function a module exports with `pub`
+    pub(crate) fn exported_function_ref(&self, module_name: &str, name: &str) -> Option<Value> {
+        let module_i = self
+            .modules
+            .iter()
+            .position(|module| module.name == module_name)?;
+        return self.function_ref(module_i, name, true);
+    }
+
     pub fn op_load_var(&mut self, name: &String) {
         if let Some(scope) = self.scopes.last() {
             if let Some(value) = scope.get(name) {
@@ -12,6 +36,12 @@ impl VM {
                 self.check_stack_overflow();
                 return;
             }
+            if let Some(cell) = scope.get_cell(name) {
+                let value = cell.borrow().clone();
+                self.stack.push(value);
+                self.check_stack_overflow();
+                return;
+            }
         }
 
         if let Some(value) = self.global_scope.get(name) {
@@ -20,18 +50,36 @@ impl VM {
             return;
         }
 
-        for module_i in 0..self.modules.len() {
-            let module = &self.modules[module_i];
-            for func in module.functions.iter() {
-                if func.name == *name {
-                    let mut addr = ProgramCounter::new();
-                    addr.inst = func.addr;
-                    addr.module = module_i;
-                    self.stack.push(Value::FunctionRef(addr, func.args_count));
+        // `module::name` is a function exported by that module
+        if let Some((module_name, func_name)) = name.split_once("::") {
+            match self.exported_function_ref(module_name, func_name) {
+                Some(value) => {
+                    self.stack.push(value);
                     self.check_stack_overflow();
-                    return;
                 }
+                None => self.set_error(
+                    RuntimeErrorKind::NotExported,
+                    format!("loadv failed: {} doesn't export {}", module_name, func_name),
+                ),
             }
+            return;
+        }
+
+        // functions of the running module come first, other modules only give the ones they export
+        let current = self.pc.module;
+        let value = self.function_ref(current, name, false).or_else(|| {
+            (0..self.modules.len()).find_map(|module_i| self.function_ref(module_i, name, true))
+        });
+        if let Some(value) = value {
+            self.stack.push(value);
+            self.check_stack_overflow();
+            return;
+        }
+
+        if let Some(native) = self.get_native(name) {
+            self.stack.push(Value::Native(native));
+            self.check_stack_overflow();
+            return;
         }
         self.stack.push(Value::Null());
     }
@@ -45,18 +93,70 @@ impl VM {
             }
 
             if let Some(scope) = self.scopes.last_mut() {
+                if scope.get(name).is_none() {
+                    if let Some(cell) = scope.get_cell(name) {
+                        *cell.borrow_mut() = store_value;
+                        return;
+                    }
+                }
                 scope.create_if_doesnt_exist(name);
                 if let Some(value) = scope.get_mut(name) {
                     *value = store_value;
                     return;
                 }
             } else {
-                self.error = format!("storev failed: scopes is empty");
+                self.set_error(
+                    RuntimeErrorKind::InvalidBytecode,
+                    format!("storev failed: scopes is empty"),
+                );
                 return;
             }
         } else {
-            self.error = format!("storev failed: no value in stack");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("storev failed: no value in stack"),
+            );
             return;
         }
     }
+
+    pub fn op_load_local(&mut self, slot: u16) {
+        let value = match self.scopes.last() {
+            Some(scope) => scope
+                .slots
+                .get(slot as usize)
+                .cloned()
+                .unwrap_or(Value::Null()),
+            None => {
+                self.set_error(
+                    RuntimeErrorKind::InvalidBytecode,
+                    format!("loadl failed: scopes is empty"),
+                );
+                return;
+            }
+        };
+        self.stack.push(value);
+        self.check_stack_overflow();
+    }
+
+    pub fn op_store_local(&mut self, slot: u16) {
+        let store_value = match self.stack.pop() {
+            Some(value) => value,
+            None => {
+                self.set_error(
+                    RuntimeErrorKind::StackUnderflow,
+                    format!("storel failed: no value in stack"),
+                );
+                return;
+            }
+        };
+
+        match self.scopes.last_mut() {
+            Some(scope) => *scope.get_slot_mut(slot) = store_value,
+            None => self.set_error(
+                RuntimeErrorKind::InvalidBytecode,
+                format!("storel failed: scopes is empty"),
+            ),
+        }
+    }
 }
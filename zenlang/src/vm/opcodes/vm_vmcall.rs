@@ -1,5 +1,4 @@
-use crate::value::*;
-use crate::vm::VM;
+use crate::vm::{RuntimeErrorKind, VM};
 use alloc::format;
 
 impl VM {
@@ -28,14 +27,20 @@ impl VM {
 
         let index;
         if let Some(value) = self.stack.pop() {
-            if let Value::Number(value) = value {
+            if let Some(value) = value.as_number() {
                 index = value as i64 as u8;
             } else {
-                self.error = format!("dynvmcall failed: value on stack is not a number");
+                self.set_error(
+                    RuntimeErrorKind::TypeMismatch,
+                    format!("dynvmcall failed: value on stack is not a number"),
+                );
                 return;
             }
         } else {
-            self.error = format!("dynvmcall failed: no more values on stack");
+            self.set_error(
+                RuntimeErrorKind::StackUnderflow,
+                format!("dynvmcall failed: no more values on stack"),
+            );
             return;
         }
         self.vmcall(index);
@@ -0,0 +1,85 @@
+use crate::vm::ProgramCounter;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+/// Kind of a runtime error
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuntimeErrorKind {
+    /// An opcode needed more values than there are on the stack
+    StackUnderflow,
+    /// The call stack or the value stack got too large
+    StackOverflow,
+    /// A value of an unexpected type was used
+    TypeMismatch,
+    /// Division by 0
+    DivisionByZero,
+    /// An integer operation overflowed or shifted by an invalid amount
+    IntegerOverflow,
+    /// An array was indexed outside of its bounds
+    IndexOutOfBounds,
+    /// A range was made with a step that is zero or NaN
+    InvalidRange,
+    /// A value that is not a function or a lambda was called
+    NotCallable,
+    /// A function was called with a wrong number of arguments
+    ArgumentCount,
+    /// A vmcall with an unknown index was performed
+    InvalidVmcall,
+    /// A module could not be found
+    ModuleNotFound,
+    /// A function accessed as `module.name` doesn't exist or isn't exported with `pub`
+    NotExported,
+    /// The bytecode put the VM in an invalid state (pc overflow, args or scopes missing)
+    InvalidBytecode,
+    /// The memory limit of the VM was exceeded
+    OutOfMemory,
+    /// A value thrown with `throw` that was not caught
+    Thrown,
+    /// Any other error, for example raised by a platform
+    Other,
+}
+
+impl RuntimeErrorKind {
+    /// Can the error be caught by a try/catch
+    ///
+    /// Invalid bytecode leaves the VM in an undefined state, so it's not catchable
+    pub fn is_catchable(&self) -> bool {
+        return *self != RuntimeErrorKind::InvalidBytecode;
+    }
+}
+
+/// Runtime error
+///
+/// Contains the error kind and message, where it happened and the call stack at that moment
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub message: String,
+    /// Address of the faulting opcode
+    pub pc: ProgramCounter,
+    /// Return addresses at the moment of the error, outermost call first
+    pub call_stack: Vec<ProgramCounter>,
+}
+
+impl RuntimeError {
+    pub fn new(
+        kind: RuntimeErrorKind,
+        message: String,
+        pc: ProgramCounter,
+        call_stack: Vec<ProgramCounter>,
+    ) -> RuntimeError {
+        return RuntimeError {
+            kind: kind,
+            message: message,
+            pc: pc,
+            call_stack: call_stack,
+        };
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
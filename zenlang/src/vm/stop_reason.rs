@@ -1,5 +1,10 @@
+use crate::vm::RuntimeError;
+
+#[derive(Debug, Clone)]
 pub enum StopReason {
     Halt,
-    Error,
+    Error(RuntimeError),
     Breakpoint,
+    /// The fuel ran out before the next opcode, execution resumes after adding fuel
+    OutOfFuel,
 }
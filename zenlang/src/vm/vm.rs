@@ -1,24 +1,44 @@
 use crate::module::Module;
+use crate::opcode::Opcode;
 use crate::platform::Platform;
 use crate::scope::Scope;
 use crate::value::*;
 use crate::vm::ProgramCounter;
 use crate::vm::StopReason;
+use crate::vm::{GC_THRESHOLD, GcNode, GcStats, NativeFunction, RuntimeError, RuntimeErrorKind};
 use alloc::boxed::*;
 use alloc::collections::btree_set::BTreeSet;
 use alloc::format;
+use alloc::rc::Rc;
 use alloc::string::*;
 use alloc::vec::*;
 
 pub static MAX_STACK_SIZE: usize = 1000;
 
+/// ExceptionHandler
+///
+/// Installed by a try block, contains the catch address and the VM state to unwind to
+#[derive(Debug, Clone)]
+pub struct ExceptionHandler {
+    pub pc: ProgramCounter,
+    pub call_stack_len: usize,
+    pub scopes_len: usize,
+    pub args_len: usize,
+    pub stack_len: usize,
+}
+
+/// Fuel cost of every opcode, see [VM::set_fuel_cost]
+pub fn default_fuel_cost(_opcode: &Opcode) -> u64 {
+    return 1;
+}
+
 pub struct VM {
     pub modules: Vec<Module>,
     pub pc: ProgramCounter,
     pub stack: Vec<Value>,
     pub call_stack: Vec<ProgramCounter>,
     pub scopes: Vec<Scope>,
-    pub error: String,
+    pub error: Option<RuntimeError>,
     pub ret: Value,
     pub platform: Option<Box<dyn Platform>>,
     pub global_scope: Scope,
@@ -26,17 +46,33 @@ pub struct VM {
     pub self_var: Value,
     pub args: Vec<Vec<Value>>,
     pub breakpoints: BTreeSet<ProgramCounter>,
+    pub handlers: Vec<ExceptionHandler>,
+    pub natives: Vec<Rc<NativeFunction>>,
+    /// Remaining fuel, None if unlimited
+    pub fuel: Option<u64>,
+    pub fuel_cost: fn(&Opcode) -> u64,
+    /// Heap budget in bytes, None if unlimited
+    pub memory_limit: Option<usize>,
+    /// Estimated heap size in bytes, accounted on allocation
+    pub memory_used: usize,
+    /// Estimated heap size found by the last walk of the heap
+    pub memory_measured: usize,
+    /// Number of tracked allocations that triggers a collection, 0 disables automatic collection
+    pub gc_threshold: usize,
+    pub gc_stats: GcStats,
+    pub(crate) gc_tracked: Vec<GcNode>,
+    pub(crate) thrown: Value,
 }
 
 impl VM {
     pub fn new() -> VM {
-        return VM {
+        let mut vm = VM {
             modules: Vec::new(),
             pc: ProgramCounter::new(),
             stack: Vec::new(),
             call_stack: Vec::new(),
             scopes: Vec::new(),
-            error: String::new(),
+            error: None,
             ret: Value::Null(),
             platform: None,
             global_scope: Scope::new(),
@@ -44,18 +80,73 @@ impl VM {
             self_var: Value::Null(),
             args: Vec::new(),
             breakpoints: BTreeSet::new(),
+            handlers: Vec::new(),
+            natives: Vec::new(),
+            fuel: None,
+            fuel_cost: default_fuel_cost,
+            memory_limit: None,
+            memory_used: 0,
+            memory_measured: 0,
+            gc_threshold: GC_THRESHOLD,
+            gc_stats: GcStats::default(),
+            gc_tracked: Vec::new(),
+            thrown: Value::Null(),
         };
+        crate::stdlib::register_natives(&mut vm);
+        return vm;
     }
 
     pub fn load_module(&mut self, module: &Module) -> Result<(), String> {
+        return self.load_module_with(module, &mut Vec::new());
+    }
+
+    /// Load a module after its dependencies, it's added only if it links
+    ///
+    /// `loading` are the modules waiting for their dependencies, so cyclic dependencies end
+    fn load_module_with(
+        &mut self,
+        module: &Module,
+        loading: &mut Vec<String>,
+    ) -> Result<(), String> {
         // check if already loaded
-        for m in self.modules.iter_mut() {
-            if m.name == module.name {
-                return Ok(());
+        if loading.contains(&module.name) || self.modules.iter().any(|m| m.name == module.name) {
+            return Ok(());
+        }
+
+        loading.push(module.name.clone());
+        for dependency in module.dependencies.iter() {
+            let name = dependency.to_string();
+
+            // check if the dependency is already loaded
+            if loading.contains(&name) || self.modules.iter().any(|m| m.name == name) {
+                continue;
+            }
+
+            // load the dependency
+            if let Some(platform) = &self.platform {
+                if let Some(dependency_module) = platform.get_module(name) {
+                    if let Err(e) = self.load_module_with(&dependency_module, loading) {
+                        loading.pop();
+                        return Err(e);
+                    }
+                } else {
+                    loading.pop();
+                    return Err(format!(
+                        "unresolved dependency {} (of module {}): not found",
+                        dependency, module.name
+                    ));
+                }
+            } else {
+                loading.pop();
+                return Err(format!(
+                    "unresolved dependency {} (of module {}): self.platform is None",
+                    dependency, module.name
+                ));
             }
         }
+        loading.pop();
 
-        self.modules.push(module.clone());
+        self.link(module, loading)?;
 
         for var in module.globals.iter() {
             if self.global_scope.get(var).is_some() {
@@ -64,9 +155,13 @@ impl VM {
                     var, module.name
                 ));
             }
+        }
+        for var in module.globals.iter() {
             self.global_scope.create_if_doesnt_exist(var);
         }
 
+        self.modules.push(module.clone());
+
         for func in module.functions.iter() {
             if func.ctor {
                 self.pc.inst = func.addr;
@@ -75,52 +170,119 @@ impl VM {
                 self.add_scope();
 
                 while !self.halted {
-                    if self.step().is_some() {
-                        break;
+                    match self.step() {
+                        Some(StopReason::Halt) | Some(StopReason::Error(_)) => break,
+                        Some(StopReason::OutOfFuel) => {
+                            self.scopes.clear();
+                            self.halted = true;
+                            return Err(format!(
+                                "in constructor of module {}: out of fuel",
+                                module.name
+                            ));
+                        }
+                        Some(StopReason::Breakpoint) | None => {}
                     }
                 }
 
                 self.scopes.clear();
 
-                if !self.error.is_empty() {
+                if let Some(error) = &self.error {
                     self.halted = true;
                     return Err(format!(
                         "in constructor of module {} at {}: {}",
-                        module.name, self.pc, self.error
+                        module.name, error.pc, error
                     ));
                 }
                 self.halted = false;
             }
         }
 
-        for dependency in module.dependencies.iter() {
-            let name = dependency.to_string();
+        return Ok(());
+    }
 
-            // check if the dependency is already loaded
-            for module in self.modules.iter_mut() {
-                if module.name == name {
-                    return Ok(());
+    /// Check the functions of other modules that a module uses
+    ///
+    /// `module.name` and the names imported with `use` have to be exported, and an unqualified
+    /// name that isn't the module's own function or variable or a native has to be exported by
+    /// exactly one module. Modules in `loading` aren't loaded yet, so they're not checked
+    fn link(&self, module: &Module, loading: &[String]) -> Result<(), String> {
+        // loadv finds the module's functions before natives, so the native would never be called
+        if let Some(func) = module
+            .functions
+            .iter()
+            .find(|func| self.get_native(&func.name).is_some())
+        {
+            return Err(format!(
+                "{} (in module {}) shadows a native function",
+                func.name, module.name
+            ));
+        }
+
+        // names the module defines and the names it uses, collected once
+        let mut own: BTreeSet<&String> = module.functions.iter().map(|func| &func.name).collect();
+        own.extend(module.globals.iter());
+        // `use` targets are checked even if they're never called
+        let mut used: BTreeSet<&String> = module.imports.iter().collect();
+        for opcode in module.opcodes.iter() {
+            match opcode {
+                Opcode::StoreVar(name) | Opcode::StoreArg(name) => {
+                    own.insert(name);
+                }
+                Opcode::LoadVar(name) => {
+                    used.insert(name);
                 }
+                _ => {}
             }
+        }
 
-            // load the dependency
-            if let Some(platform) = &self.platform {
-                if let Some(module) = platform.get_module(name) {
-                    if let Err(e) = self.load_module(&module) {
-                        return Err(e);
-                    }
-                } else {
+        for name in used {
+            if let Some((module_name, func_name)) = name.split_once("::") {
+                if !loading.iter().any(|m| m == module_name)
+                    && self.exported_function_ref(module_name, func_name).is_none()
+                {
                     return Err(format!(
-                        "unresolved dependency {} (of module {}): not found",
-                        dependency, module.name
+                        "unresolved {} (in module {}): {} doesn't export {}",
+                        name, module.name, module_name, func_name
                     ));
                 }
-            } else {
+                continue;
+            }
+
+            if own.contains(name)
+                || self.global_scope.get(name).is_some()
+                || self.get_native(name).is_some()
+            {
+                continue;
+            }
+
+            let mut defining = self
+                .modules
+                .iter()
+                .filter(|other| other.functions.iter().any(|func| func.name == *name));
+            let exporting: Vec<&String> = defining
+                .clone()
+                .filter(|other| {
+                    other
+                        .functions
+                        .iter()
+                        .any(|func| func.name == *name && func.public)
+                })
+                .map(|other| &other.name)
+                .collect();
+            if exporting.len() > 1 {
                 return Err(format!(
-                    "unresolved dependency {} (of module {}): self.platform is None",
-                    dependency, module.name
+                    "ambiguous {} (in module {}): exported by both {} and {}, use {}.{}",
+                    name, module.name, exporting[0], exporting[1], exporting[0], name
                 ));
             }
+            if exporting.is_empty() {
+                if let Some(other) = defining.next() {
+                    return Err(format!(
+                        "unresolved {} (in module {}): {} doesn't export {}",
+                        name, module.name, other.name, name
+                    ));
+                }
+            }
         }
         return Ok(());
     }
@@ -140,12 +302,51 @@ impl VM {
         return Err("cannot find entry function");
     }
 
+    /// Set the runtime error, capturing the current pc and call stack
+    ///
+    /// Only the first error is kept, the VM stops at the end of the current step
+    pub fn set_error(&mut self, kind: RuntimeErrorKind, message: String) {
+        if self.error.is_none() {
+            self.error = Some(self.runtime_error(kind, message));
+        }
+    }
+
+    /// Limit the execution to `fuel` units, by default every opcode costs 1 unit
+    ///
+    /// When the fuel runs out, step returns [StopReason::OutOfFuel]
+    pub fn set_fuel(&mut self, fuel: u64) {
+        self.fuel = Some(fuel);
+    }
+
+    /// Add fuel to the remaining fuel, does nothing if the fuel is unlimited
+    pub fn add_fuel(&mut self, fuel: u64) {
+        if let Some(remaining) = self.fuel {
+            self.fuel = Some(remaining.saturating_add(fuel));
+        }
+    }
+
+    /// Remove the fuel limit
+    pub fn remove_fuel_limit(&mut self) {
+        self.fuel = None;
+    }
+
+    /// Set the function that returns the fuel cost of an opcode
+    pub fn set_fuel_cost(&mut self, fuel_cost: fn(&Opcode) -> u64) {
+        self.fuel_cost = fuel_cost;
+    }
+
     pub(crate) fn check_stack_overflow(&mut self) {
         if self.call_stack.len() >= MAX_STACK_SIZE {
-            self.error = "call stack overflow".into();
+            self.set_error(
+                RuntimeErrorKind::StackOverflow,
+                "call stack overflow".into(),
+            );
         }
         if self.stack.len() >= MAX_STACK_SIZE {
-            self.error = "call stack overflow".into();
+            self.set_error(
+                RuntimeErrorKind::StackOverflow,
+                "call stack overflow".into(),
+            );
         }
     }
 
@@ -157,7 +358,7 @@ impl VM {
         self.scopes.pop();
     }
 
-    pub fn get_function_name_from_pc(&mut self, pc: &ProgramCounter) -> Option<String> {
+    pub fn get_function_name_from_pc(&self, pc: &ProgramCounter) -> Option<String> {
         if pc.module >= self.modules.len() {
             return None;
         }
@@ -184,35 +385,58 @@ impl VM {
             return Some(StopReason::Halt);
         }
 
-        if !self.error.is_empty() {
-            return Some(StopReason::Error);
+        if let Some(error) = &self.error {
+            return Some(StopReason::Error(error.clone()));
         }
 
+        self.maybe_collect();
+
         if self.pc.module >= self.modules.len() {
-            self.error = format!(
-                "module pc overflow: {}/{}",
-                self.pc.module,
-                self.modules.len()
+            self.set_error(
+                RuntimeErrorKind::InvalidBytecode,
+                format!(
+                    "module pc overflow: {}/{}",
+                    self.pc.module,
+                    self.modules.len()
+                ),
             );
-            return Some(StopReason::Error);
+            return Some(StopReason::Error(self.error.clone().unwrap()));
         }
 
         let cycle_module = self.pc.module;
         let opcodes = core::mem::take(&mut self.modules[cycle_module].opcodes);
         if self.pc.inst >= opcodes.len() {
-            self.error = format!(
-                "inst pc overflow: {}/{} {:?}",
-                self.pc.inst,
-                opcodes.len(),
-                opcodes
+            self.set_error(
+                RuntimeErrorKind::InvalidBytecode,
+                format!(
+                    "inst pc overflow: {}/{} {:?}",
+                    self.pc.inst,
+                    opcodes.len(),
+                    opcodes
+                ),
             );
-            return Some(StopReason::Error);
+            self.modules[cycle_module].opcodes = opcodes;
+            return Some(StopReason::Error(self.error.clone().unwrap()));
         }
 
         let opcode = &opcodes[self.pc.inst as usize];
 
+        if let Some(fuel) = self.fuel {
+            let cost = (self.fuel_cost)(opcode);
+            if fuel < cost {
+                self.modules[cycle_module].opcodes = opcodes;
+                return Some(StopReason::OutOfFuel);
+            }
+            self.fuel = Some(fuel - cost);
+        }
+
         self.execute_opcode(opcode);
         self.modules[cycle_module].opcodes = opcodes;
+
+        if self.error.is_some() && !self.catch_error() {
+            return Some(StopReason::Error(self.error.clone().unwrap()));
+        }
+
         self.pc.inst = self.pc.inst.wrapping_add(1);
 
         if self.breakpoints.contains(&self.pc) {
@@ -3,15 +3,105 @@ use crate::value::*;
 use crate::vm::*;
 use alloc::format;
 use alloc::string::String;
+use libm::{floor, fmod, pow};
+
+/// Floored modulo, the result has the sign of the divisor, so `a == (a ~/ b) * b + a % b`
+pub(crate) fn modulo(left: f64, right: f64) -> f64 {
+    let remainder = fmod(left, right);
+    if remainder != 0.0 && (remainder < 0.0) != (right < 0.0) {
+        return remainder + right;
+    }
+    return remainder;
+}
+
+/// Floor division
+pub(crate) fn floor_div(left: f64, right: f64) -> f64 {
+    return floor(left / right);
+}
+
+/// Floored integer modulo, None on division by 0
+pub(crate) fn int_modulo(left: i64, right: i64) -> Option<i64> {
+    if right == 0 {
+        return None;
+    }
+    let remainder = left.wrapping_rem(right);
+    if remainder != 0 && (remainder < 0) != (right < 0) {
+        return Some(remainder + right);
+    }
+    return Some(remainder);
+}
+
+/// Floored integer division, None on division by 0 or overflow
+pub(crate) fn int_floor_div(left: i64, right: i64) -> Option<i64> {
+    let quotient = left.checked_div(right)?;
+    if left % right != 0 && (left < 0) != (right < 0) {
+        return Some(quotient - 1);
+    }
+    return Some(quotient);
+}
+
+/// Integer power, a negative exponent gives a number
+pub(crate) fn int_pow(left: i64, right: i64) -> Option<Value> {
+    if right < 0 {
+        return Some(Value::Number(pow(left as f64, right as f64)));
+    }
+    let result = u32::try_from(right)
+        .ok()
+        .and_then(|exponent| left.checked_pow(exponent))?;
+    return Some(Value::Int(result));
+}
+
+/// Arithmetic operations promote integers to numbers when mixed with numbers
+fn is_arithmetic(op: AstBinopOp) -> bool {
+    return matches!(
+        op,
+        AstBinopOp::PLUS
+            | AstBinopOp::MINUS
+            | AstBinopOp::MUL
+            | AstBinopOp::DIV
+            | AstBinopOp::MOD
+            | AstBinopOp::INTDIV
+            | AstBinopOp::POW
+    );
+}
+
+/// Bitwise operands are integers, numbers are truncated and booleans are 0 or 1
+fn bit_operand(value: &Value) -> Option<i64> {
+    return match value {
+        Value::Int(int) => Some(*int),
+        Value::Number(num) => Some(*num as i64),
+        Value::Boolean(boolean) => Some(*boolean as i64),
+        _ => None,
+    };
+}
 
 impl VM {
     pub(crate) fn compute_values(&mut self, left: &Value, right: &Value, op: AstBinopOp) -> Value {
+        // integers are promoted to numbers when mixed with numbers
+        match (left, right) {
+            (Value::Int(left_int), Value::Int(right_int)) => {
+                if let Some(value) = self.compute_ints(*left_int, *right_int, op) {
+                    return value;
+                }
+            }
+            (Value::Int(left_int), Value::Number(_)) if is_arithmetic(op) => {
+                return self.compute_values(&Value::Number(*left_int as f64), right, op);
+            }
+            (Value::Number(_), Value::Int(right_int)) if is_arithmetic(op) => {
+                return self.compute_values(left, &Value::Number(*right_int as f64), op);
+            }
+            _ => {}
+        }
+
         match op {
             AstBinopOp::PLUS => match (left, right) {
                 (Value::Number(left_num), Value::Number(right_num)) => {
                     return Value::Number(left_num + right_num);
                 }
                 (Value::String(left_str), Value::String(right_str)) => {
+                    if !self.alloc(string_size(left_str.len() + right_str.len())) {
+                        return Value::Null();
+                    }
                     return Value::String(format!("{}{}", left_str, right_str));
                 }
                 _ => {}
@@ -27,7 +117,16 @@ impl VM {
                 (Value::Number(left_num), Value::Number(right_num)) => {
                     return Value::Number(left_num * right_num);
                 }
-                (Value::String(left_str), Value::Number(right_num)) => {
+                (Value::String(left_str), Value::Number(_) | Value::Int(_)) => {
+                    let right_num = &right.as_number().unwrap();
+                    let count = if *right_num > 0.0 {
+                        *right_num as usize
+                    } else {
+                        0
+                    };
+                    if !self.alloc(string_size(left_str.len().saturating_mul(count))) {
+                        return Value::Null();
+                    }
                     let mut new = String::new();
                     for _ in 0..*right_num as i64 {
                         new.push_str(&left_str);
@@ -40,55 +139,43 @@ impl VM {
                 if let Value::Number(left_num) = left {
                     if let Value::Number(right_num) = right {
                         if *right_num == 0.0 {
-                            self.error = "division by 0".into();
+                            self.set_error(
+                                RuntimeErrorKind::DivisionByZero,
+                                "division by 0".into(),
+                            );
                             return Value::Null();
                         }
                         return Value::Number(left_num / right_num);
                     }
                 }
             }
-            AstBinopOp::BITSHR => match (left, right) {
-                (Value::Number(left_num), Value::Number(right_num)) => {
-                    return Value::Number(((*left_num as i64) >> (*right_num as i64)) as f64);
-                }
-                _ => {}
-            },
-            AstBinopOp::BITSHL => match (left, right) {
-                (Value::Number(left_num), Value::Number(right_num)) => {
-                    return Value::Number(((*left_num as i64) << (*right_num as i64)) as f64);
+            AstBinopOp::MOD | AstBinopOp::INTDIV => {
+                if let (Value::Number(left_num), Value::Number(right_num)) = (left, right) {
+                    if *right_num == 0.0 {
+                        self.set_error(RuntimeErrorKind::DivisionByZero, "division by 0".into());
+                        return Value::Null();
+                    }
+                    if matches!(op, AstBinopOp::MOD) {
+                        return Value::Number(modulo(*left_num, *right_num));
+                    }
+                    return Value::Number(floor_div(*left_num, *right_num));
                 }
-                _ => {}
-            },
-            AstBinopOp::BITAND => match (left, right) {
+            }
+            AstBinopOp::POW => match (left, right) {
                 (Value::Number(left_num), Value::Number(right_num)) => {
-                    return Value::Number(((*left_num as i64) & (*right_num as i64)) as f64);
-                }
-                (Value::Boolean(left), Value::Boolean(right)) => {
-                    return Value::Number((*left && *right) as i64 as f64);
-                }
-                (Value::Number(left_num), Value::Boolean(right_bool)) => {
-                    return Value::Number(((*left_num as i64) & (*right_bool as i64)) as f64);
-                }
-                (Value::Boolean(left_bool), Value::Number(right_num)) => {
-                    return Value::Number(((*left_bool as i64) & (*right_num as i64)) as f64);
+                    return Value::Number(pow(*left_num, *right_num));
                 }
                 _ => {}
             },
-            AstBinopOp::BITOR => match (left, right) {
-                (Value::Number(left_num), Value::Number(right_num)) => {
-                    return Value::Number(((*left_num as i64) | (*right_num as i64)) as f64);
-                }
-                (Value::Boolean(left), Value::Boolean(right)) => {
-                    return Value::Number((*left || *right) as i64 as f64);
-                }
-                (Value::Number(left_num), Value::Boolean(right_bool)) => {
-                    return Value::Number(((*left_num as i64) | (*right_bool as i64)) as f64);
+            AstBinopOp::BITSHR
+            | AstBinopOp::BITSHL
+            | AstBinopOp::BITAND
+            | AstBinopOp::BITOR
+            | AstBinopOp::BITXOR => {
+                if let (Some(left_int), Some(right_int)) = (bit_operand(left), bit_operand(right)) {
+                    return self.compute_bits(left_int, right_int, op);
                 }
-                (Value::Boolean(left_bool), Value::Number(right_num)) => {
-                    return Value::Number(((*left_bool as i64) | (*right_num as i64)) as f64);
-                }
-                _ => {}
-            },
+            }
             AstBinopOp::EQ => {
                 return Value::Boolean(left.equal(&right, self));
             }
@@ -109,14 +196,66 @@ impl VM {
             }
         }
 
-        self.error = format!(
-            "unmatched left and right value types: {}, {}",
-            left.get_type(),
-            right.get_type()
+        self.set_error(
+            RuntimeErrorKind::TypeMismatch,
+            format!(
+                "unmatched left and right value types: {}, {}",
+                left.get_type(),
+                right.get_type()
+            ),
         );
         return Value::Null();
     }
 
+    /// Compute an operation on two integers, None if the operation isn't an integer operation
+    fn compute_ints(&mut self, left: i64, right: i64, op: AstBinopOp) -> Option<Value> {
+        let result = match op {
+            AstBinopOp::PLUS => left.checked_add(right).map(Value::Int),
+            AstBinopOp::MINUS => left.checked_sub(right).map(Value::Int),
+            AstBinopOp::MUL => left.checked_mul(right).map(Value::Int),
+            AstBinopOp::DIV | AstBinopOp::MOD | AstBinopOp::INTDIV if right == 0 => {
+                self.set_error(RuntimeErrorKind::DivisionByZero, "division by 0".into());
+                return Some(Value::Null());
+            }
+            AstBinopOp::DIV => Some(Value::Number(left as f64 / right as f64)),
+            AstBinopOp::MOD => int_modulo(left, right).map(Value::Int),
+            AstBinopOp::INTDIV => int_floor_div(left, right).map(Value::Int),
+            AstBinopOp::POW => int_pow(left, right),
+            _ => return None,
+        };
+
+        if result.is_none() {
+            self.set_error(RuntimeErrorKind::IntegerOverflow, "integer overflow".into());
+        }
+        return Some(result.unwrap_or(Value::Null()));
+    }
+
+    fn compute_bits(&mut self, left: i64, right: i64, op: AstBinopOp) -> Value {
+        let result = match op {
+            AstBinopOp::BITSHR | AstBinopOp::BITSHL => {
+                let amount = match u32::try_from(right) {
+                    Ok(amount) if amount < i64::BITS => amount,
+                    _ => {
+                        self.set_error(
+                            RuntimeErrorKind::IntegerOverflow,
+                            format!("shift amount {} out of range", right),
+                        );
+                        return Value::Null();
+                    }
+                };
+                if matches!(op, AstBinopOp::BITSHR) {
+                    left >> amount
+                } else {
+                    left << amount
+                }
+            }
+            AstBinopOp::BITAND => left & right,
+            AstBinopOp::BITOR => left | right,
+            _ => left ^ right,
+        };
+        return Value::Int(result);
+    }
+
     pub(crate) fn compute_stack_values(&mut self, op: AstBinopOp) -> Value {
         let mut left = Value::Null();
         let mut right = Value::Null();
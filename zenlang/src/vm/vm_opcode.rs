@@ -17,6 +17,9 @@ impl VM {
             Opcode::LoadConstant(value) => {
                 self.op_load_constant(*value);
             }
+            Opcode::LoadInt(value) => {
+                self.op_load_int(*value);
+            }
             Opcode::LoadNull() => {
                 self.op_load_null();
             }
@@ -44,6 +47,21 @@ impl VM {
             Opcode::Cdfse(names) => {
                 self.op_cdfse(names);
             }
+            Opcode::Csfse(items) => {
+                self.op_csfse(*items);
+            }
+            Opcode::MatchArray(len, at_least) => {
+                self.op_match_array(*len, *at_least);
+            }
+            Opcode::MatchDict(keys) => {
+                self.op_match_dict(keys);
+            }
+            Opcode::UnpackArray(count, has_rest) => {
+                self.op_unpack_array(*count, *has_rest);
+            }
+            Opcode::UnpackDict(keys) => {
+                self.op_unpack_dict(keys);
+            }
             Opcode::Aiafs() => {
                 self.op_aiafs();
             }
@@ -56,9 +74,30 @@ impl VM {
             Opcode::StoreArg(name) => {
                 self.op_storearg(name);
             }
+            Opcode::MakeRange(inclusive, has_step) => {
+                self.op_make_range(*inclusive, *has_step);
+            }
+            Opcode::IterBegin() => {
+                self.op_iter_begin();
+            }
+            Opcode::IterNext(addr, push_key) => {
+                self.op_iter_next(*addr, *push_key);
+            }
+            Opcode::LoadLocal(slot) => {
+                self.op_load_local(*slot);
+            }
+            Opcode::StoreLocal(slot) => {
+                self.op_store_local(*slot);
+            }
+            Opcode::StoreArgLocal(slot) => {
+                self.op_storearg_local(*slot);
+            }
             Opcode::Pop() => {
                 if self.stack.is_empty() {
-                    self.error = format!("pop failed: no value in stack");
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        format!("pop failed: no value in stack"),
+                    );
                 } else {
                     self.stack.pop();
                 }
@@ -84,6 +123,15 @@ impl VM {
             Opcode::Div() => {
                 self.op_div();
             }
+            Opcode::Mod() => {
+                self.op_mod();
+            }
+            Opcode::IntDiv() => {
+                self.op_intdiv();
+            }
+            Opcode::Pow() => {
+                self.op_pow();
+            }
             Opcode::Eq() => {
                 self.op_eq();
             }
@@ -114,10 +162,36 @@ impl VM {
             Opcode::Bor() => {
                 self.op_bor();
             }
+            Opcode::Bxor() => {
+                self.op_bxor();
+            }
+            Opcode::Bnot() => {
+                self.op_bnot();
+            }
+            Opcode::Not() => {
+                self.op_not();
+            }
+            Opcode::Neg() => {
+                self.op_neg();
+            }
+            Opcode::Dup() => {
+                if let Some(value) = self.stack.last() {
+                    self.stack.push(value.clone());
+                    self.check_stack_overflow();
+                } else {
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        format!("dup failed: no value in stack"),
+                    );
+                }
+            }
             Opcode::Ret() => {
                 self.op_ret();
             }
-            Opcode::Lambda(pc, args) => self.op_lambda(*pc, *args),
+            Opcode::Lambda(pc, args, captures) => self.op_lambda(*pc, *args, captures),
+            Opcode::TryBegin(addr) => self.op_try_begin(*addr),
+            Opcode::TryEnd() => self.op_try_end(),
+            Opcode::Throw() => self.op_throw(),
         }
     }
 }
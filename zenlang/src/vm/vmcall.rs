@@ -24,7 +24,10 @@ impl VM {
                         platform.print(format!("{}", value));
                         return;
                     }
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                 }
             }
             2 => {
@@ -34,7 +37,10 @@ impl VM {
                         platform.println(format!("{}", value));
                         return;
                     }
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                 }
             }
             3 => {
@@ -50,19 +56,26 @@ impl VM {
                 if let Some(platform) = &self.platform {
                     if let Some(value) = self.stack.pop() {
                         if let Value::String(name) = value {
-                            //platform.println(format!("{}", value));
-                            self.error = format!("module not found: {}", name);
-                            if let Some(module) = platform.get_module(name) {
-                                self.error.clear();
-
+                            if let Some(module) = platform.get_module(name.clone()) {
                                 let _ = self.load_module(&module);
+                            } else {
+                                self.set_error(
+                                    RuntimeErrorKind::ModuleNotFound,
+                                    format!("module not found: {}", name),
+                                );
                             }
                             return;
                         } else {
-                            self.error = "vmcall: expected a string".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected a string".into(),
+                            );
                         }
                     }
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                 }
             }
             5 => {
@@ -70,15 +83,24 @@ impl VM {
                 if let Some(value) = self.stack.pop() {
                     if let Value::Object(obj) = value {
                         if let Object::Array(array) = &*obj.borrow() {
-                            self.stack.push(Value::Number(array.len() as f64));
+                            self.stack.push(Value::Int(array.len() as i64));
                         } else {
-                            self.error = "vmcall: expected an array".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected an array".into(),
+                            );
                         }
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                 }
             }
             6 => {
@@ -89,7 +111,10 @@ impl VM {
                 if let Some(value) = self.stack.pop() {
                     element = value;
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -98,20 +123,33 @@ impl VM {
                         if let Object::Array(value) = &*obj.borrow() {
                             array = value.clone();
                         } else {
-                            self.error = "vmcall: expected an array".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected an array".into(),
+                            );
                             return;
                         }
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
+                if !self.alloc(array_size(array.len() + 1)) {
+                    return;
+                }
                 array.push(element);
                 let ptr = Rc::new(RefCell::new(Object::Array(array)));
+                self.track_object(&ptr);
                 self.stack.push(Value::Object(ptr));
             }
             7 => {
@@ -123,20 +161,33 @@ impl VM {
                         if let Object::Array(value) = &*obj.borrow() {
                             array = value.clone();
                         } else {
-                            self.error = "vmcall: expected an array".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected an array".into(),
+                            );
                             return;
                         }
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
+                if !self.alloc(array_size(array.len())) {
+                    return;
+                }
                 array.pop();
                 let ptr = Rc::new(RefCell::new(Object::Array(array)));
+                self.track_object(&ptr);
                 self.stack.push(Value::Object(ptr));
             }
             8 => {
@@ -145,14 +196,20 @@ impl VM {
                 let at;
 
                 if let Some(value) = self.stack.pop() {
-                    if let Value::Number(value) = value {
-                        at = value as usize;
+                    if let Some(value) = value.as_index() {
+                        at = value;
                     } else {
-                        self.error = "vmcall: expected a number".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a non-negative integer".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -161,15 +218,24 @@ impl VM {
                         if let Object::Array(value) = &*obj.borrow() {
                             array = value.clone();
                         } else {
-                            self.error = "vmcall: expected an array".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected an array".into(),
+                            );
                             return;
                         }
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -178,8 +244,12 @@ impl VM {
                     return;
                 }
 
+                if !self.alloc(array_size(array.len())) {
+                    return;
+                }
                 array.remove(at);
                 let ptr = Rc::new(RefCell::new(Object::Array(array)));
+                self.track_object(&ptr);
                 self.stack.push(Value::Object(ptr));
             }
             9 => {
@@ -191,19 +261,28 @@ impl VM {
                 if let Some(value) = self.stack.pop() {
                     element = value;
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
                 if let Some(value) = self.stack.pop() {
-                    if let Value::Number(value) = value {
-                        at = value as usize;
+                    if let Some(value) = value.as_index() {
+                        at = value;
                     } else {
-                        self.error = "vmcall: expected a number".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a non-negative integer".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -212,15 +291,24 @@ impl VM {
                         if let Object::Array(value) = &*obj.borrow() {
                             array = value.clone();
                         } else {
-                            self.error = "vmcall: expected an array".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected an array".into(),
+                            );
                             return;
                         }
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -228,8 +316,12 @@ impl VM {
                     self.stack.push(Value::Null());
                     return;
                 }
+                if !self.alloc(array_size(array.len() + 1)) {
+                    return;
+                }
                 array.insert(at, element);
                 let ptr = Rc::new(RefCell::new(Object::Array(array)));
+                self.track_object(&ptr);
                 self.stack.push(Value::Object(ptr));
             }
             10 => {
@@ -241,11 +333,17 @@ impl VM {
                     if let Value::String(value) = value {
                         delimiter = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -253,20 +351,32 @@ impl VM {
                     if let Value::String(value) = value {
                         string = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
+                if !self
+                    .alloc(string_size(string.len()) + array_size(string.split(&delimiter).count()))
+                {
+                    return;
+                }
                 let mut array: Vec<Value> = Vec::new();
                 for part in string.split(&delimiter) {
                     array.push(Value::String(String::from(part)))
                 }
 
                 let ptr = Rc::new(RefCell::new(Object::Array(array)));
+                self.track_object(&ptr);
                 self.stack.push(Value::Object(ptr));
             }
             11 => {
@@ -277,22 +387,32 @@ impl VM {
                     if let Value::String(value) = value {
                         name = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
                 if let Some(platform) = &self.platform {
                     if let Some(bytes) = platform.read_file_bytes(name) {
+                        if !self.alloc(array_size(bytes.len())) {
+                            return;
+                        }
                         let mut array: Vec<Value> = Vec::new();
                         for byte in bytes {
-                            array.push(Value::Number(byte as f64));
+                            array.push(Value::Int(byte as i64));
                         }
 
                         let ptr = Rc::new(RefCell::new(Object::Array(array)));
+                        self.track_object(&ptr);
                         self.stack.push(Value::Object(ptr));
                     } else {
                         self.stack.push(Value::Null());
@@ -307,16 +427,25 @@ impl VM {
                     if let Value::String(value) = value {
                         name = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
                 if let Some(platform) = &self.platform {
                     if let Some(bytes) = platform.read_file_bytes(name) {
+                        if !self.alloc(string_size(bytes.len() * 2)) {
+                            return;
+                        }
                         let mut string = String::new();
                         for byte in bytes {
                             string.push(byte as char);
@@ -337,24 +466,35 @@ impl VM {
                     if let Value::Object(obj) = value {
                         if let Object::Array(array) = &*obj.borrow() {
                             for value in array.iter() {
-                                if let Value::Number(byte) = value {
-                                    bytes.push(*byte as u8);
+                                if let Some(byte) = value.as_number() {
+                                    bytes.push(byte as u8);
                                 } else {
-                                    self.error =
-                                        "vmcall: expected non number in a byte array".into();
+                                    self.set_error(
+                                        RuntimeErrorKind::TypeMismatch,
+                                        "vmcall: expected non number in a byte array".into(),
+                                    );
                                     return;
                                 }
                             }
                         } else {
-                            self.error = "vmcall: expected a byte array".into();
+                            self.set_error(
+                                RuntimeErrorKind::TypeMismatch,
+                                "vmcall: expected a byte array".into(),
+                            );
                             return;
                         }
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -362,11 +502,17 @@ impl VM {
                     if let Value::String(value) = value {
                         name = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -385,11 +531,17 @@ impl VM {
                             bytes.push(ch as u8);
                         }
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -397,11 +549,17 @@ impl VM {
                     if let Value::String(value) = value {
                         name = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -417,11 +575,17 @@ impl VM {
                     if let Value::String(value) = value {
                         ch = value;
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -429,7 +593,7 @@ impl VM {
                     self.stack.push(Value::Null());
                 } else {
                     self.stack
-                        .push(Value::Number(ch.chars().nth(0).unwrap() as i64 as f64));
+                        .push(Value::Int(ch.chars().nth(0).unwrap() as i64));
                 }
             }
             16 => {
@@ -437,14 +601,20 @@ impl VM {
                 let ch;
 
                 if let Some(value) = self.stack.pop() {
-                    if let Value::Number(value) = value {
+                    if let Some(value) = value.as_number() {
                         ch = value as i64 as u8;
                     } else {
-                        self.error = "vmcall: expected a number".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a number".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
 
@@ -454,9 +624,16 @@ impl VM {
             17 => {
                 // stringify
                 if let Some(value) = self.stack.pop() {
-                    self.stack.push(Value::String(format!("{}", value)));
+                    let string = format!("{}", value);
+                    if !self.alloc(string_size(string.len())) {
+                        return;
+                    }
+                    self.stack.push(Value::String(string));
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
             }
@@ -464,20 +641,30 @@ impl VM {
                 // number
                 if let Some(value) = self.stack.pop() {
                     if let Value::String(num_str) = value {
-                        match num_str.parse::<f64>() {
-                            Err(e) => {
+                        // integers are parsed exactly, other numbers as f64
+                        match (num_str.parse::<i64>(), num_str.parse::<f64>()) {
+                            (Ok(int), _) => {
+                                self.stack.push(interop_ok(Value::Int(int)));
+                            }
+                            (Err(_), Err(e)) => {
                                 self.stack.push(interop_err(Value::String(e.to_string())));
                             }
-                            Ok(num) => {
+                            (Err(_), Ok(num)) => {
                                 self.stack.push(interop_ok(Value::Number(num)));
                             }
                         }
                     } else {
-                        self.error = "vmcall: expected a string".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected a string".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
             }
@@ -485,15 +672,25 @@ impl VM {
                 // clone
                 if let Some(value) = self.stack.pop() {
                     if let Value::Object(obj) = value {
+                        if !self.alloc(value_size(&Value::Object(obj.clone()))) {
+                            return;
+                        }
                         let obj = &*obj.borrow();
                         let new = Rc::new(RefCell::new(obj.clone()));
+                        self.track_object(&new);
                         self.stack.push(Value::Object(new));
                     } else {
-                        self.error = "vmcall: expected an object".into();
+                        self.set_error(
+                            RuntimeErrorKind::TypeMismatch,
+                            "vmcall: expected an object".into(),
+                        );
                         return;
                     }
                 } else {
-                    self.error = "vmcall: no value on stack".into();
+                    self.set_error(
+                        RuntimeErrorKind::StackUnderflow,
+                        "vmcall: no value on stack".into(),
+                    );
                     return;
                 }
             }
@@ -501,7 +698,10 @@ impl VM {
                 if let Some(mut platform) = self.platform.take() {
                     let result = platform.as_mut().vmcall(self, index);
                     if !result {
-                        self.error = format!("vmcall: invalid vmcall index {}", index);
+                        self.set_error(
+                            RuntimeErrorKind::InvalidVmcall,
+                            format!("vmcall: invalid vmcall index {}", index),
+                        );
                     }
                     self.platform = Some(platform);
                 }
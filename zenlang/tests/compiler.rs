@@ -1,4 +1,5 @@
 use zenlang::compiler::*;
+use zenlang::module::Arity;
 use zenlang::opcode::*;
 use zenlang::parser::*;
 use zenlang::tokenizer::*;
@@ -17,7 +18,7 @@ fn compiler_test_func_1() {
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -33,10 +34,10 @@ fn compiler_test_func_2() {
     assert_eq!(module.functions.len(), 2);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "add");
-    assert_eq!(module.functions[0].args_count, 2);
+    assert_eq!(module.functions[0].arity, Arity::exact(2));
     assert_eq!(module.functions[1].addr, 4);
     assert_eq!(module.functions[1].name, "main");
-    assert_eq!(module.functions[1].args_count, 0);
+    assert_eq!(module.functions[1].arity, Arity::exact(0));
 }
 
 #[test]
@@ -55,7 +56,7 @@ fn compiler_test_return() {
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -70,12 +71,12 @@ fn compiler_test_simple_return() {
     let module = compiler.get_module();
     println!("{:?}", module);
     assert_eq!(module.opcodes.len(), 2);
-    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(69.0)));
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(69)));
     assert!(matches!(module.opcodes[1], Opcode::Ret()));
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -89,14 +90,14 @@ fn compiler_test_return_add() {
 
     let module = compiler.get_module();
     assert_eq!(module.opcodes.len(), 4);
-    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(2.0)));
-    assert!(matches!(module.opcodes[1], Opcode::LoadConstant(3.0)));
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(2)));
+    assert!(matches!(module.opcodes[1], Opcode::LoadInt(3)));
     assert!(matches!(module.opcodes[2], Opcode::Add()));
     assert!(matches!(module.opcodes[3], Opcode::Ret()));
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -111,16 +112,16 @@ fn compiler_test_return_add_mul() {
     let module = compiler.get_module();
     println!("{:?}", module.opcodes);
     assert_eq!(module.opcodes.len(), 6);
-    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(2.0)));
-    assert!(matches!(module.opcodes[1], Opcode::LoadConstant(2.0)));
-    assert!(matches!(module.opcodes[2], Opcode::LoadConstant(2.0)));
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(2)));
+    assert!(matches!(module.opcodes[1], Opcode::LoadInt(2)));
+    assert!(matches!(module.opcodes[2], Opcode::LoadInt(2)));
     assert!(matches!(module.opcodes[3], Opcode::Mul()));
     assert!(matches!(module.opcodes[4], Opcode::Add()));
     assert!(matches!(module.opcodes[5], Opcode::Ret()));
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -134,20 +135,16 @@ fn compiler_test_let() {
 
     let module = compiler.get_module();
     assert_eq!(module.opcodes.len(), 8);
-    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(1.0)));
-    assert!(matches!(module.opcodes[1], Opcode::LoadConstant(2.0)));
-    assert!(matches!(module.opcodes[2], Opcode::LoadConstant(3.0)));
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(1)));
+    assert!(matches!(module.opcodes[1], Opcode::LoadInt(2)));
+    assert!(matches!(module.opcodes[2], Opcode::LoadInt(3)));
     assert!(matches!(module.opcodes[3], Opcode::Mul()));
     assert!(matches!(module.opcodes[4], Opcode::Add()));
-    if let Opcode::StoreVar(s) = &module.opcodes[5] {
-        assert_eq!(s.to_string(), "x");
-    } else {
-        assert!(false);
-    }
+    assert!(matches!(module.opcodes[5], Opcode::StoreLocal(0)));
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -162,26 +159,18 @@ fn compiler_test_varref() {
     let module = compiler.get_module();
     assert_eq!(module.opcodes.len(), 8);
     println!("{:?}", module.opcodes);
-    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(1.0)));
-    assert!(matches!(module.opcodes[1], Opcode::LoadConstant(2.0)));
-    assert!(matches!(module.opcodes[2], Opcode::LoadConstant(3.0)));
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(1)));
+    assert!(matches!(module.opcodes[1], Opcode::LoadInt(2)));
+    assert!(matches!(module.opcodes[2], Opcode::LoadInt(3)));
     assert!(matches!(module.opcodes[3], Opcode::Mul()));
     assert!(matches!(module.opcodes[4], Opcode::Add()));
-    if let Opcode::StoreVar(s) = &module.opcodes[5] {
-        assert_eq!(s.to_string(), "x");
-    } else {
-        assert!(false);
-    }
-    if let Opcode::LoadVar(s) = &module.opcodes[6] {
-        assert_eq!(s.to_string(), "x");
-    } else {
-        assert!(false);
-    }
+    assert!(matches!(module.opcodes[5], Opcode::StoreLocal(0)));
+    assert!(matches!(module.opcodes[6], Opcode::LoadLocal(0)));
     assert!(matches!(module.opcodes[7], Opcode::Ret()));
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -203,11 +192,7 @@ fn compiler_test_func_call() {
     }
     assert!(matches!(module.opcodes[2], Opcode::Call()));
     assert!(matches!(module.opcodes[3], Opcode::PushRet()));
-    if let Opcode::StoreVar(s) = &module.opcodes[4] {
-        assert_eq!(s.to_string(), "x");
-    } else {
-        assert!(false);
-    }
+    assert!(matches!(module.opcodes[4], Opcode::StoreLocal(0)));
 
     assert!(matches!(module.opcodes[5], Opcode::BeginArgs()));
     if let Opcode::LoadVar(s) = &module.opcodes[6] {
@@ -220,7 +205,7 @@ fn compiler_test_func_call() {
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -245,7 +230,7 @@ fn compiler_test_load_string() {
     assert_eq!(module.functions.len(), 1);
     assert_eq!(module.functions[0].addr, 0);
     assert_eq!(module.functions[0].name, "main");
-    assert_eq!(module.functions[0].args_count, 0);
+    assert_eq!(module.functions[0].arity, Arity::exact(0));
 }
 
 #[test]
@@ -274,3 +259,78 @@ fn compiler_test_implicit_null() {
         "function main implicitly returns null"
     );
 }
+
+#[test]
+fn compiler_test_debug_info() {
+    let mut tokenizer = Tokenizer::new("fn main {\n    let x = 1;\n\n    return x;\n}".into());
+    tokenizer.filename = "main.zen".into();
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let module = compiler.get_module();
+    assert_eq!(module.opcodes.len(), 4);
+    assert_eq!(module.get_source_filename(), Some("main.zen"));
+
+    let lines: Vec<usize> = (0..module.opcodes.len())
+        .map(|addr| module.get_source_location(addr).unwrap().line)
+        .collect();
+    assert_eq!(lines, vec![2, 2, 4, 4]);
+    assert_eq!(module.get_source_location(2).unwrap().column, 5);
+    assert!(module.get_source_location(4).is_none());
+
+    // debug info survives serialization
+    let mut loaded = zenlang::module::Module::new();
+    assert!(loaded.load(module.compile().unwrap()).is_ok());
+    assert_eq!(loaded.get_source_location(3).unwrap().line, 4);
+
+    module.strip_debug_info();
+    assert!(module.get_source_location(0).is_none());
+    let mut loaded = zenlang::module::Module::new();
+    assert!(loaded.load(module.compile().unwrap()).is_ok());
+    assert!(loaded.debug_info.is_none());
+}
+
+#[test]
+fn compiler_test_debug_info_nested() {
+    let mut tokenizer =
+        Tokenizer::new("fn main {\n    if true {\n        return 1;\n    }\n}".into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let module = compiler.get_module();
+    let lines: Vec<usize> = (0..module.opcodes.len())
+        .map(|addr| module.get_source_location(addr).unwrap().line)
+        .collect();
+    // LoadBool, BranchTrue, Branch (if), LoadInt, Ret (return), Branch (if), implicit LoadNull, Ret (fn)
+    assert_eq!(lines, vec![2, 2, 2, 3, 3, 2, 1, 1]);
+}
+
+#[test]
+fn compiler_test_local_slots() {
+    let mut tokenizer =
+        Tokenizer::new("fn add a b {let c = a + b; let f = fn { return c; }; return f();}".into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+    assert!(matches!(module.opcodes[0], Opcode::StoreArgLocal(1)));
+    assert!(matches!(module.opcodes[1], Opcode::StoreArgLocal(0)));
+    assert!(matches!(module.opcodes[2], Opcode::LoadLocal(0)));
+    assert!(matches!(module.opcodes[3], Opcode::LoadLocal(1)));
+    // c is captured by the lambda, so it stays a named variable
+    if let Opcode::StoreVar(s) = &module.opcodes[5] {
+        assert_eq!(s.to_string(), "c");
+    } else {
+        assert!(false);
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use zenlang::interop::*;
+use zenlang::value::*;
+
+#[derive(ToValue, FromValue, Debug, Clone, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+#[derive(ToValue, FromValue, Debug, Clone, PartialEq)]
+struct Shape {
+    name: String,
+    points: Vec<Point>,
+    closed: bool,
+    color: Option<String>,
+}
+
+fn roundtrip<T: ToValue + FromValue + Clone + PartialEq + std::fmt::Debug>(value: T) {
+    let converted = value.clone().to_value();
+    println!("{}", converted);
+    assert_eq!(T::from_value(&converted), Ok(value));
+}
+
+#[test]
+fn interop_primitives() {
+    roundtrip(1.5f64);
+    roundtrip(42i32);
+    roundtrip(7usize);
+    roundtrip(true);
+    roundtrip(String::from("hello"));
+    roundtrip(());
+    roundtrip(Some(1u8));
+    roundtrip(None::<u8>);
+
+    assert_eq!(u8::from_value(&Value::Number(256.0)).is_err(), true);
+    assert_eq!(i32::from_value(&Value::Number(1.5)).is_err(), true);
+    assert_eq!(
+        String::from_value(&Value::Number(1.0)),
+        Err("expected string, found number".into())
+    );
+}
+
+#[test]
+fn interop_collections() {
+    roundtrip(vec![1.0, 2.0, 3.0]);
+    roundtrip(vec![vec![String::from("a")], vec![]]);
+
+    let mut map = BTreeMap::new();
+    map.insert(String::from("a"), 1i64);
+    map.insert(String::from("b"), 2i64);
+    roundtrip(map);
+
+    assert_eq!(
+        Vec::<f64>::from_value(&vec![Value::Number(1.0), Value::Null()].to_value()),
+        Err("index 1: expected number, found null".into())
+    );
+}
+
+#[test]
+fn interop_result() {
+    let ok: Result<f64, String> = Ok(2.0);
+    assert!(
+        ok.clone()
+            .to_value()
+            .equal(&interop_ok(Value::Number(2.0)), &zenlang::vm::VM::new())
+    );
+    roundtrip(ok);
+
+    let err: Result<f64, String> = Err("failed".into());
+    assert!(err.clone().to_value().equal(
+        &interop_err(Value::String("failed".into())),
+        &zenlang::vm::VM::new()
+    ));
+    roundtrip(err);
+}
+
+#[test]
+fn interop_derive() {
+    let shape = Shape {
+        name: "triangle".into(),
+        points: vec![
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 0.0, y: 1.0 },
+        ],
+        closed: true,
+        color: None,
+    };
+
+    let value = shape.to_value();
+    assert_eq!(get_field::<String>(&value, "_typename"), Ok("Shape".into()));
+    assert_eq!(get_field::<bool>(&value, "closed"), Ok(true));
+    assert_eq!(
+        Shape::from_value(&value),
+        Ok(Shape {
+            name: "triangle".into(),
+            points: vec![
+                Point { x: 0.0, y: 0.0 },
+                Point { x: 1.0, y: 0.0 },
+                Point { x: 0.0, y: 1.0 },
+            ],
+            closed: true,
+            color: None,
+        })
+    );
+
+    assert_eq!(
+        Point::from_value(&make_dictionary([("x", Value::Number(1.0))])),
+        Err("missing field y".into())
+    );
+}
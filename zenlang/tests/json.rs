@@ -0,0 +1,108 @@
+use zenlang::interop::*;
+use zenlang::json;
+use zenlang::module::Arity;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn parse_error(text: &str) -> Result<(), String> {
+    return json::parse(text).map(|_| ());
+}
+
+fn roundtrip(text: &str, indent: usize) {
+    let value = json::parse(text).unwrap();
+    assert_eq!(json::stringify(&value, indent), Ok(text.into()));
+}
+
+#[test]
+fn json_parse() {
+    let value = json::parse(r#" {"a": [1, -2.5e2, true, false, null], "b": {"c": "d"}} "#).unwrap();
+    let a: Vec<Value> = get_field(&value, "a").unwrap();
+    assert_eq!(a.len(), 5);
+    assert!(matches!(a[0], Value::Int(1)));
+    assert!(a[1].equal(&Value::Number(-250.0), &VM::new()));
+
+    let b: std::collections::BTreeMap<String, String> = get_field(&value, "b").unwrap();
+    assert_eq!(b.get("c"), Some(&"d".to_string()));
+}
+
+#[test]
+fn json_integers() {
+    // integers above 2^53 are kept exactly
+    roundtrip("[9007199254740993,-1,1.5]", 0);
+    assert!(matches!(
+        json::parse("9223372036854775808"),
+        Ok(Value::Number(_))
+    ));
+}
+
+#[test]
+fn json_parse_strings() {
+    let value = json::parse(r#""a\"b\\c\n\u00e9\ud83d\ude00 é""#).unwrap();
+    assert_eq!(String::from_value(&value), Ok("a\"b\\c\né😀 é".into()));
+}
+
+#[test]
+fn json_parse_errors() {
+    assert_eq!(
+        parse_error(""),
+        Err("unexpected end of input at 1:1".into())
+    );
+    assert_eq!(
+        parse_error("[1,]"),
+        Err("unexpected character at 1:4".into())
+    );
+    assert_eq!(
+        parse_error("{\n  \"a\" 1}"),
+        Err("expected : at 2:7".into())
+    );
+    assert_eq!(
+        parse_error("01"),
+        Err("unexpected trailing characters at 1:2".into())
+    );
+    assert_eq!(
+        parse_error("\"abc"),
+        Err("unterminated string at 1:5".into())
+    );
+    assert_eq!(parse_error("\"\\x\""), Err("invalid escape at 1:2".into()));
+    assert_eq!(parse_error("tru"), Err("expected true at 1:1".into()));
+    assert_eq!(
+        parse_error(&"[".repeat(1000)),
+        Err("maximum nesting depth exceeded at 1:257".into())
+    );
+}
+
+#[test]
+fn json_stringify() {
+    roundtrip(
+        r#"{"a":[1,0.5,true,null],"b":{},"c":[],"d":"\"\n\u0001"}"#,
+        0,
+    );
+    roundtrip(
+        r#"{
+  "a": [
+    1,
+    {
+      "b": "c"
+    }
+  ],
+  "d": []
+}"#,
+        2,
+    );
+
+    assert_eq!(
+        json::stringify(&Value::Number(f64::NAN), 0),
+        Ok("null".into())
+    );
+    assert_eq!(
+        json::stringify(
+            &Value::FunctionRef(ProgramCounter::new(), Arity::exact(0)),
+            0
+        ),
+        Err("json: cannot convert a function to JSON".into())
+    );
+    assert_eq!(
+        json::stringify(&Value::Null(), 11),
+        Err("indent 11 is larger than 10".into())
+    );
+}
@@ -0,0 +1,185 @@
+use zenlang::compiler::*;
+use zenlang::module::Module;
+use zenlang::opcode::Opcode;
+use zenlang::optimizer::OptimizationLevel;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn compile(code: &str, level: OptimizationLevel) -> Module {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    compiler.optimization_level = level;
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let module = compiler.get_module().clone();
+    println!("{:?}", module.opcodes);
+    return module;
+}
+
+fn run(module: &Module) -> Value {
+    let mut vm = VM::new();
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(module);
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+    assert_eq!(vm.error, None);
+    assert!(vm.stack.is_empty());
+    return vm.ret.clone();
+}
+
+/// Run the code with and without optimizations and check both return the same value
+fn expect_same_result(code: &str, value: Value) {
+    let plain = compile(code, OptimizationLevel::None);
+    let optimized = compile(code, OptimizationLevel::Full);
+    assert!(optimized.opcodes.len() <= plain.opcodes.len());
+
+    let vm = VM::new();
+    assert!(run(&plain).equal(&value, &vm));
+    assert!(run(&optimized).equal(&value, &vm));
+}
+
+#[test]
+fn optimizer_constant_folding() {
+    let module = compile("fn main {return 1 + 2 * 3;}", OptimizationLevel::Basic);
+    assert_eq!(module.opcodes.len(), 2);
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(7)));
+    assert!(matches!(module.opcodes[1], Opcode::Ret()));
+}
+
+#[test]
+fn optimizer_no_folding_of_division_by_zero() {
+    let module = compile("fn main {return 1 / 0;}", OptimizationLevel::Full);
+    assert!(matches!(module.opcodes[2], Opcode::Div()));
+}
+
+#[test]
+fn optimizer_level_none() {
+    let module = compile("fn main {return 1 + 2;}", OptimizationLevel::None);
+    assert_eq!(module.opcodes.len(), 4);
+}
+
+#[test]
+fn optimizer_dead_code_and_function_addresses() {
+    let code = "fn first {return 1; return 2;} fn main {return first() + 2;}";
+    let plain = compile(code, OptimizationLevel::None);
+    let module = compile(code, OptimizationLevel::Full);
+    assert!(module.opcodes.len() < plain.opcodes.len());
+    assert_eq!(module.functions[0].addr, 0);
+    assert_eq!(module.functions[1].addr, 2);
+    assert!(matches!(module.opcodes[1], Opcode::Ret()));
+
+    let debug_info = module.debug_info.as_ref().unwrap();
+    assert_eq!(debug_info.locations.len(), module.opcodes.len());
+    assert!(run(&module).equal(&Value::Number(3.0), &VM::new()));
+}
+
+#[test]
+fn optimizer_branch_threading() {
+    let mut module = Module::new();
+    module.opcodes = vec![
+        Opcode::LoadBool(true),
+        Opcode::BranchTrue(3),
+        Opcode::Ret(),
+        Opcode::Branch(4),
+        Opcode::LoadNull(),
+        Opcode::Ret(),
+    ];
+    module.optimize(OptimizationLevel::Basic);
+    assert!(matches!(module.opcodes[1], Opcode::BranchTrue(4)));
+}
+
+#[test]
+fn optimizer_push_pop() {
+    let mut module = Module::new();
+    module.opcodes = vec![
+        Opcode::LoadConstant(1.0),
+        Opcode::Pop(),
+        Opcode::LoadNull(),
+        Opcode::Ret(),
+    ];
+    module.optimize(OptimizationLevel::Basic);
+    assert_eq!(module.opcodes.len(), 2);
+    assert!(matches!(module.opcodes[0], Opcode::LoadNull()));
+}
+
+#[test]
+fn optimizer_same_result_loops() {
+    expect_same_result(
+        "fn main {let i = 0; let sum = 0; while i < 10 { let i = i + 1; if i == 3 { continue; } if i == 8 { break; } let sum = sum + i * 2; } return sum;}",
+        Value::Number(50.0),
+    );
+}
+
+#[test]
+fn optimizer_same_result_try_catch() {
+    expect_same_result(
+        "fn fail {throw 2 + 3; return 0;} fn main {let x = 0; try { fail(); } catch e { let x = e; } return x;}",
+        Value::Number(5.0),
+    );
+}
+
+#[test]
+fn optimizer_same_result_lambda() {
+    expect_same_result(
+        "fn main {let n = 2 * 5; let f = fn a { return a + n; }; return f(1 + 1);}",
+        Value::Number(12.0),
+    );
+}
+
+#[test]
+fn optimizer_same_result_for() {
+    expect_same_result(
+        "fn main {let n = 0; for i, x in [1, 2, 3, 4] { if i == 0 { continue; } let n = n + x * (1 + 1); } return n;}",
+        Value::Number(18.0),
+    );
+}
+
+#[test]
+fn optimizer_negation_folding() {
+    let module = compile("fn main {return -2 * 3;}", OptimizationLevel::Basic);
+    assert_eq!(module.opcodes.len(), 2);
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(-6)));
+}
+
+#[test]
+fn optimizer_same_result_logical() {
+    expect_same_result(
+        "fn main {let x = 4; if x > 1 && !(x == 3) || false { return -x; } return 0;}",
+        Value::Number(-4.0),
+    );
+}
+
+#[test]
+fn optimizer_arithmetic_folding() {
+    let module = compile(
+        "fn main {return 2 ** 3 + 7 % 4 + 9 ~/ 2;}",
+        OptimizationLevel::Basic,
+    );
+    assert_eq!(module.opcodes.len(), 2);
+    assert!(matches!(module.opcodes[0], Opcode::LoadInt(15)));
+
+    let module = compile("fn main {return 1 % 0;}", OptimizationLevel::Basic);
+    assert!(matches!(module.opcodes[2], Opcode::Mod()));
+}
+
+#[test]
+fn optimizer_integer_folding() {
+    let module = compile("fn main {return 1.5 * 2.0;}", OptimizationLevel::Basic);
+    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(3.0)));
+
+    let module = compile("fn main {return 7 / 2;}", OptimizationLevel::Basic);
+    assert!(matches!(module.opcodes[0], Opcode::LoadConstant(3.5)));
+
+    // overflows are runtime errors
+    let module = compile(
+        "fn main {return 9223372036854775807 + 1;}",
+        OptimizationLevel::Basic,
+    );
+    assert!(matches!(module.opcodes[2], Opcode::Add()));
+}
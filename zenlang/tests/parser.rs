@@ -0,0 +1,43 @@
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+
+#[test]
+fn parser_test_error_location() {
+    let mut tokenizer = Tokenizer::new("fn main {\n    let x = 1\n    return x;\n}".into());
+    tokenizer.filename = "test.zen".into();
+    let mut parser = Parser::new(&mut tokenizer);
+
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => {
+            assert!(matches!(
+                e.kind,
+                error::ErrorKind::StatementSemicolon(Token::Return)
+            ));
+            assert_eq!(e.span.line, 3);
+            assert_eq!(e.span.column, 5);
+            assert_eq!(
+                e.to_string(),
+                "Expected `;` where a statement terminates, but got Return\n --> test.zen:3:5\n  |\n3 |     return x;\n  |     ^^^^^^"
+            );
+        }
+    }
+}
+
+#[test]
+fn parser_test_error_location_through_compiler() {
+    let mut tokenizer = Tokenizer::new("fn main {\n    return (1 + 2;\n}".into());
+    tokenizer.filename = "paren.zen".into();
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+
+    match compiler.compile() {
+        Ok(_) => assert!(false),
+        Err(e) => {
+            assert!(e.starts_with("Expected `)` to end an expression, but got Semicolon"));
+            assert!(e.contains("--> paren.zen:2:18"));
+            assert!(e.ends_with("2 |     return (1 + 2;\n  |                  ^"));
+        }
+    }
+}
@@ -6,7 +6,7 @@ fn tokenizer_test_number() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Number(1.23)));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -195,7 +195,7 @@ fn tokenizer_test_eq_2() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::OperatorCmp('=', '=')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -211,7 +211,7 @@ fn tokenizer_test_lt() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::OperatorCmp('<', '<')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -227,7 +227,7 @@ fn tokenizer_test_gt() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::OperatorCmp('>', '>')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -243,7 +243,7 @@ fn tokenizer_test_le() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::OperatorCmp('<', '=')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -259,7 +259,7 @@ fn tokenizer_test_ge() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::OperatorCmp('>', '=')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -275,7 +275,7 @@ fn tokenizer_test_neq() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::OperatorCmp('!', '=')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -291,7 +291,7 @@ fn tokenizer_test_not() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Operator('!')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -305,9 +305,9 @@ fn tokenizer_test_bitand() {
     tokenizer.next();
     tokenizer.next();
     let token = tokenizer.next();
-    assert!(matches!(token, Token::BitOperator('&', '&')));
+    assert!(matches!(token, Token::Operator('&')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -321,9 +321,9 @@ fn tokenizer_test_bitor() {
     tokenizer.next();
     tokenizer.next();
     let token = tokenizer.next();
-    assert!(matches!(token, Token::BitOperator('|', '|')));
+    assert!(matches!(token, Token::Operator('|')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -339,7 +339,7 @@ fn tokenizer_test_bitshr() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::BitOperator('>', '>')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -355,7 +355,7 @@ fn tokenizer_test_bitshl() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::BitOperator('<', '<')));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -371,7 +371,7 @@ fn tokenizer_test_while() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::While));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -387,7 +387,7 @@ fn tokenizer_test_break() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Break));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -407,7 +407,7 @@ fn tokenizer_test_escape_str() {
         assert!(false);
     }
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -423,7 +423,7 @@ fn tokenizer_test_dot() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Dot));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -439,7 +439,7 @@ fn tokenizer_test_hashtag() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Hashtag));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -455,7 +455,7 @@ fn tokenizer_test_vmcall() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Vmcall));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -471,7 +471,7 @@ fn tokenizer_test_mod() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Mod));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 #[test]
@@ -487,7 +487,7 @@ fn tokenizer_test_dynmod() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Dynmod));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
 }
 
 // let x.1.0 = 69;
@@ -503,18 +503,172 @@ fn tokenizer_test_let_dotted() {
     let token = tokenizer.next();
     assert!(matches!(token, Token::Dot));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(1.0)));
+    assert!(matches!(token, Token::Int(1)));
     let token = tokenizer.next();
     assert!(matches!(token, Token::Dot));
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(0.0)));
+    assert!(matches!(token, Token::Int(0)));
 
     let token = tokenizer.next();
     assert!(matches!(token, Token::Assign));
 
     let token = tokenizer.next();
-    assert!(matches!(token, Token::Number(69.0)));
+    assert!(matches!(token, Token::Int(69)));
 
     let token = tokenizer.next();
     assert!(matches!(token, Token::Semicolon));
 }
+
+#[test]
+fn tokenizer_test_spans() {
+    let mut tokenizer = Tokenizer::new("fn main {\n    return \"hi\";\n}".into());
+    let (token, span) = tokenizer.next_spanned();
+    assert!(matches!(token, Token::Fn));
+    assert_eq!(
+        (span.offset, span.len, span.line, span.column),
+        (0, 2, 1, 1)
+    );
+
+    let (token, span) = tokenizer.next_spanned();
+    assert!(matches!(token, Token::Identifier(_)));
+    assert_eq!(
+        (span.offset, span.len, span.line, span.column),
+        (3, 4, 1, 4)
+    );
+
+    tokenizer.next();
+
+    let (token, span) = tokenizer.next_spanned();
+    assert!(matches!(token, Token::Return));
+    assert_eq!(
+        (span.offset, span.len, span.line, span.column),
+        (14, 6, 2, 5)
+    );
+
+    let (token, span) = tokenizer.next_spanned();
+    assert!(matches!(token, Token::String(_)));
+    assert_eq!(
+        (span.offset, span.len, span.line, span.column),
+        (21, 4, 2, 12)
+    );
+
+    // stepping back restores the span of the previous token
+    tokenizer.back();
+    assert_eq!(tokenizer.span().column, 5);
+
+    let token = tokenizer.next();
+    assert!(matches!(token, Token::String(_)));
+    assert_eq!(tokenizer.span().column, 12);
+}
+
+#[test]
+fn tokenizer_test_spans_unicode_and_back() {
+    let mut tokenizer = Tokenizer::new("let s = \"żółw\"; s".into());
+    for _ in 0..4 {
+        tokenizer.next();
+    }
+    let span = tokenizer.span();
+    assert_eq!((span.offset, span.len, span.column), (8, 9, 9));
+
+    // stepping back twice moves the span back to `=`
+    tokenizer.next();
+    tokenizer.back();
+    tokenizer.back();
+    assert_eq!(tokenizer.span().column, 7);
+    tokenizer.next();
+    tokenizer.next();
+    let (token, span) = tokenizer.next_spanned();
+    assert!(matches!(token, Token::Identifier(_)));
+    assert_eq!((span.offset, span.len, span.column), (19, 1, 17));
+}
+
+#[test]
+fn tokenizer_test_spans_large_input() {
+    let code = "let a = 1;\n".repeat(20000);
+    let mut tokenizer = Tokenizer::new(code);
+    let mut span = tokenizer.span();
+    while !matches!(tokenizer.next(), Token::EOF) {
+        span = tokenizer.span();
+    }
+    assert_eq!((span.line, span.column), (20000, 10));
+}
+
+#[test]
+fn tokenizer_test_range() {
+    let mut tokenizer = Tokenizer::new("0..5 1.5..=x a.b".into());
+    assert!(matches!(tokenizer.next(), Token::Int(0)));
+    assert!(matches!(tokenizer.next(), Token::Range(false)));
+    assert!(matches!(tokenizer.next(), Token::Int(5)));
+    assert!(matches!(tokenizer.next(), Token::Number(1.5)));
+    assert!(matches!(tokenizer.next(), Token::Range(true)));
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+    assert!(matches!(tokenizer.next(), Token::Dot));
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+}
+
+#[test]
+fn tokenizer_test_logical() {
+    let mut tokenizer = Tokenizer::new("a && b || !c ^ ~d".into());
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+    assert!(matches!(tokenizer.next(), Token::LogicalOperator('&', '&')));
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+    assert!(matches!(tokenizer.next(), Token::LogicalOperator('|', '|')));
+    assert!(matches!(tokenizer.next(), Token::Operator('!')));
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+    assert!(matches!(tokenizer.next(), Token::Operator('^')));
+    assert!(matches!(tokenizer.next(), Token::Operator('~')));
+    assert!(matches!(tokenizer.next(), Token::Identifier(_)));
+}
+
+#[test]
+fn tokenizer_test_int() {
+    let mut tokenizer = Tokenizer::new("42 1.0 0xff 0xffffffffffffffff 9223372036854775808".into());
+    assert!(matches!(tokenizer.next(), Token::Int(42)));
+    assert!(matches!(tokenizer.next(), Token::Number(1.0)));
+    assert!(matches!(tokenizer.next(), Token::Int(255)));
+    assert!(matches!(tokenizer.next(), Token::Int(-1)));
+    // too large for an integer
+    assert!(matches!(tokenizer.next(), Token::Number(num) if num > 9.2e18));
+}
+
+#[test]
+fn tokenizer_test_format_string() {
+    let mut tokenizer = Tokenizer::new(r#"f"a {b + "}"} \{c\}" "d\"e""#.into());
+    match tokenizer.next() {
+        Token::FormatString(parts) => {
+            assert_eq!(parts.len(), 3);
+            assert!(matches!(&parts[0], FormatPart::Literal(text) if text == "a "));
+            assert!(matches!(&parts[1], FormatPart::Expression(code) if code == r#"b + "}""#));
+            assert!(matches!(&parts[2], FormatPart::Literal(text) if text == " {c}"));
+        }
+        token => panic!("unexpected token {:?}", token),
+    }
+    assert!(matches!(tokenizer.next(), Token::String(s) if s == "d\"e"));
+}
+
+#[test]
+fn tokenizer_test_plain_string_braces() {
+    let mut tokenizer = Tokenizer::new(r#""open { brace" f"{x""#.into());
+    assert!(matches!(tokenizer.next(), Token::String(s) if s == "open { brace"));
+    assert!(matches!(tokenizer.next(), Token::UnterminatedFormatString));
+}
+
+#[test]
+fn tokenizer_test_comment_after_operand() {
+    let mut tokenizer = Tokenizer::new("let x = 5 // comment\n; [1, 2 // two\n] 7 ~/ 2".into());
+    assert!(matches!(tokenizer.next(), Token::Let));
+    assert!(matches!(tokenizer.next(), Token::Identifier(s) if s == "x"));
+    assert!(matches!(tokenizer.next(), Token::Assign));
+    assert!(matches!(tokenizer.next(), Token::Int(5)));
+    assert!(matches!(tokenizer.next(), Token::Semicolon));
+    assert!(matches!(tokenizer.next(), Token::Lbracket));
+    assert!(matches!(tokenizer.next(), Token::Int(1)));
+    assert!(matches!(tokenizer.next(), Token::Comma));
+    assert!(matches!(tokenizer.next(), Token::Int(2)));
+    assert!(matches!(tokenizer.next(), Token::Rbracket));
+    assert!(matches!(tokenizer.next(), Token::Int(7)));
+    assert!(matches!(tokenizer.next(), Token::DoubleOperator('~', '/')));
+    assert!(matches!(tokenizer.next(), Token::Int(2)));
+    assert!(matches!(tokenizer.next(), Token::EOF));
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use zenlang::compiler::*;
+use zenlang::module::Arity;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Mode {
+    Fast,
+    Limit(u32),
+    Range { min: i32, max: i32 },
+    Pair(String, bool),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Config {
+    name: String,
+    retries: u8,
+    ratio: f64,
+    tags: Vec<String>,
+    limits: BTreeMap<String, i64>,
+    parent: Option<Box<Config>>,
+    modes: Vec<Mode>,
+}
+
+fn config() -> Config {
+    let mut limits = BTreeMap::new();
+    limits.insert("cpu".into(), 4);
+    limits.insert("memory".into(), -1);
+
+    return Config {
+        name: "test".into(),
+        retries: 3,
+        ratio: 0.5,
+        tags: vec!["a".into(), "b".into()],
+        limits: limits,
+        parent: Some(Box::new(Config {
+            name: "parent".into(),
+            retries: 0,
+            ratio: 1.0,
+            tags: vec![],
+            limits: BTreeMap::new(),
+            parent: None,
+            modes: vec![],
+        })),
+        modes: vec![
+            Mode::Fast,
+            Mode::Limit(10),
+            Mode::Range { min: -1, max: 1 },
+            Mode::Pair("x".into(), true),
+        ],
+    };
+}
+
+#[test]
+fn value_serde_roundtrip() {
+    let value = to_value(&config()).unwrap();
+    println!("{}", value);
+    assert_eq!(from_value::<Config>(&value), Ok(config()));
+
+    let value: Value = from_value(&value).unwrap();
+    assert_eq!(from_value::<Config>(&value), Ok(config()));
+}
+
+#[test]
+fn value_serde_layout() {
+    let value = to_value(&config()).unwrap();
+    let vm = VM::new();
+
+    let modes = to_value(&config().modes).unwrap();
+    let mut expected = BTreeMap::new();
+    expected.insert("Limit".to_string(), Value::Number(10.0));
+    assert!(
+        to_value(&Mode::Limit(10))
+            .unwrap()
+            .equal(&to_value(&expected).unwrap(), &vm)
+    );
+    assert!(
+        to_value(&Mode::Fast)
+            .unwrap()
+            .equal(&Value::String("Fast".into()), &vm)
+    );
+    assert_eq!(
+        format!("{}", modes),
+        "[\"Fast\", {Limit = 10}, {Range = {max = 1, min = -1}}, {Pair = [\"x\", true]}]"
+    );
+    assert_eq!(
+        format!("{}", to_value(&config().limits).unwrap()),
+        "{cpu = 4, memory = -1}"
+    );
+    assert!(matches!(value, Value::Object(_)));
+}
+
+#[test]
+fn value_serde_errors() {
+    assert_eq!(
+        from_value::<u8>(&Value::Number(1.5)),
+        Err(ValueError::new("1.5 is not a valid u8".into()))
+    );
+    assert!(from_value::<Config>(&Value::Null()).is_err());
+    assert!(from_value::<String>(&Value::Number(1.0)).is_err());
+    assert!(to_value(&Value::FunctionRef(ProgramCounter::new(), Arity::exact(0))).is_err());
+}
+
+#[test]
+fn value_serde_script() {
+    let code = r#"
+fn main {
+    let config = get_config();
+    let config["name"] = config.name + "!";
+    let config["retries"] = config.retries + 1;
+    let config["tags"] = [config.tags[1]];
+    return config;
+}
+    "#;
+
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    vm.register_native("get_config", 0, |vm, _| {
+        to_value(&config()).map_err(|e| vm.runtime_error(RuntimeErrorKind::Other, e.to_string()))
+    });
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+    assert_eq!(vm.error, None);
+
+    let mut expected = config();
+    expected.name = "test!".into();
+    expected.retries = 4;
+    expected.tags = vec!["b".into()];
+    assert_eq!(from_value::<Config>(&vm.ret), Ok(expected));
+}
+
+#[test]
+fn value_serde_cyclic() {
+    let dict = Rc::new(RefCell::new(Object::Dictionary(BTreeMap::new())));
+    if let Object::Dictionary(map) = &mut *dict.borrow_mut() {
+        map.insert("self".into(), Value::Object(dict.clone()));
+    }
+
+    let error = to_value(&Value::Object(dict.clone())).unwrap_err();
+    assert_eq!(error.to_string(), "maximum nesting depth exceeded");
+
+    // break the cycle so the dictionary is freed
+    *dict.borrow_mut() = Object::Dictionary(BTreeMap::new());
+}
@@ -20,12 +20,12 @@ fn vm_test_ret_num() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Number(1.23)));
 }
 
@@ -45,12 +45,12 @@ fn vm_test_ret_string() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     if let Value::String(s) = vm.ret {
         assert_eq!(s, "Hello");
     } else {
@@ -74,12 +74,12 @@ fn vm_test_ret_true() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -99,12 +99,12 @@ fn vm_test_ret_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(false)));
 }
 
@@ -124,12 +124,12 @@ fn vm_test_ret_null() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Null()));
 }
 
@@ -150,12 +150,12 @@ fn vm_test_cmp_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(false)));
 }
 
@@ -176,11 +176,11 @@ fn vm_test_cmp() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
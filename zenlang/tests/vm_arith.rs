@@ -0,0 +1,107 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+fn expect_error(code: &str, kind: RuntimeErrorKind, message: &str) {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    let error = vm.error.unwrap();
+    assert_eq!(error.kind, kind);
+    assert_eq!(error.message, message);
+}
+
+#[test]
+fn vm_test_modulo() {
+    expect_to_return("fn main {return 7 % 3;}".into(), Value::Number(1.0));
+    expect_to_return("fn main {return -7 % 3;}".into(), Value::Number(2.0));
+    expect_to_return("fn main {return 7 % -3;}".into(), Value::Number(-2.0));
+    expect_to_return("fn main {return 5.5 % 2;}".into(), Value::Number(1.5));
+    expect_to_return(
+        "fn main {let n = 0; for i in 0..10 { if i % 2 == 0 { let n = n + 1; } } return n;}".into(),
+        Value::Number(5.0),
+    );
+}
+
+#[test]
+fn vm_test_floor_division() {
+    expect_to_return("fn main {return 7 ~/ 2;}".into(), Value::Number(3.0));
+    expect_to_return("fn main {return -7 ~/ 2;}".into(), Value::Number(-4.0));
+    expect_to_return(
+        "fn main {let a = 17; let b = 5; return (a ~/ b) * b + a % b;}".into(),
+        Value::Number(17.0),
+    );
+}
+
+#[test]
+fn vm_test_floor_division_and_comments() {
+    expect_to_return(
+        "// comment\nfn main {\n// comment\nlet x = 9 ~/ 2; // comment\nreturn x;}".into(),
+        Value::Number(4.0),
+    );
+}
+
+#[test]
+fn vm_test_pow() {
+    expect_to_return("fn main {return 2 ** 10;}".into(), Value::Number(1024.0));
+    expect_to_return("fn main {return 2 ** 3 ** 2;}".into(), Value::Number(512.0));
+    expect_to_return("fn main {return -2 ** 2;}".into(), Value::Number(-4.0));
+    expect_to_return("fn main {return 2 ** -1;}".into(), Value::Number(0.5));
+    expect_to_return("fn main {return 3 * 2 ** 2;}".into(), Value::Number(12.0));
+}
+
+#[test]
+fn vm_test_division_by_zero() {
+    expect_error(
+        "fn main {return 1 % 0;}",
+        RuntimeErrorKind::DivisionByZero,
+        "division by 0",
+    );
+    expect_error(
+        "fn main {return 1 ~/ 0;}",
+        RuntimeErrorKind::DivisionByZero,
+        "division by 0",
+    );
+}
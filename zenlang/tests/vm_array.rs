@@ -30,14 +30,14 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(69.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(69)));
 }
 
 #[test]
@@ -66,14 +66,14 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(69.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(69)));
 }
 
 #[test]
@@ -102,14 +102,14 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -137,12 +137,12 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
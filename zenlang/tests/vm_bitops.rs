@@ -21,13 +21,13 @@ fn vm_test_bitor() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(7.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(7)));
 }
 
 #[test]
@@ -47,13 +47,13 @@ fn vm_test_bitand() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(3.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(3)));
 }
 
 #[test]
@@ -73,13 +73,13 @@ fn vm_test_bitshr() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(30.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(30)));
 }
 
 #[test]
@@ -99,13 +99,13 @@ fn vm_test_bitshl() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(492.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(492)));
 }
 
 #[test]
@@ -137,13 +137,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(10.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(10)));
 }
 
 #[test]
@@ -175,11 +175,11 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(0.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(0)));
 }
@@ -21,12 +21,12 @@ fn vm_test_eq() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -47,12 +47,12 @@ fn vm_test_neq() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -73,12 +73,12 @@ fn vm_test_gt() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -99,12 +99,12 @@ fn vm_test_gt_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(false)));
 }
 
@@ -125,12 +125,12 @@ fn vm_test_lt() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -151,12 +151,12 @@ fn vm_test_lt_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(false)));
 }
 
@@ -177,12 +177,12 @@ fn vm_test_ge() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -203,12 +203,12 @@ fn vm_test_ge_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(false)));
 }
 
@@ -229,12 +229,12 @@ fn vm_test_le() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(true)));
 }
 
@@ -255,11 +255,11 @@ fn vm_test_le_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Boolean(false)));
 }
@@ -0,0 +1,171 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+fn expect_error(code: &str, kind: RuntimeErrorKind, message: &str) {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    let error = vm.error.unwrap();
+    assert_eq!(error.kind, kind);
+    assert_eq!(error.message, message);
+}
+
+#[test]
+fn vm_test_destructure_array() {
+    expect_to_return(
+        r#"fn main {let [a, b] = [1, 2]; return a * 10 + b;}"#.into(),
+        Value::Int(12),
+    );
+}
+
+#[test]
+fn vm_test_destructure_array_rest() {
+    expect_to_return(
+        r#"fn main {let [first, ...rest] = [1, 2, 3]; return f"{first} {rest}";}"#.into(),
+        Value::String("1 [2, 3]".into()),
+    );
+    expect_to_return(
+        r#"fn main {let [a, b, ...rest] = [1]; return f"{a} {b} {rest}";}"#.into(),
+        Value::String("1 null []".into()),
+    );
+}
+
+#[test]
+fn vm_test_destructure_dict() {
+    expect_to_return(
+        r#"fn main {let point = {"x" = 3, "y" = 4}; let {"x" = x, "y" = y} = point; return x * y;}"#
+            .into(),
+        Value::Int(12),
+    );
+}
+
+#[test]
+fn vm_test_destructure_nested() {
+    expect_to_return(
+        r#"fn main {let [{"name" = name}, [x, y]] = [{"name" = "a"}, [1, 2]]; return f"{name}{x}{y}";}"#
+            .into(),
+        Value::String("a12".into()),
+    );
+}
+
+#[test]
+fn vm_test_destructure_missing_is_null() {
+    expect_to_return(
+        r#"fn main {let [a, b] = [1]; let {"z" = z} = {"x" = 1}; return b == null && z == null;}"#
+            .into(),
+        Value::Boolean(true),
+    );
+}
+
+#[test]
+fn vm_test_destructure_wrong_type() {
+    expect_error(
+        r#"fn main {let [a, b] = 5;}"#,
+        RuntimeErrorKind::TypeMismatch,
+        "let: expected an array to destructure, got int",
+    );
+    expect_error(
+        r#"fn main {let {"x" = x} = "text";}"#,
+        RuntimeErrorKind::TypeMismatch,
+        "let: expected a dictionary to destructure, got string",
+    );
+}
+
+#[test]
+fn vm_test_destructure_parameters() {
+    let code = r#"
+fn length [x, y] {
+    return x * x + y * y;
+}
+fn area {"w" = w, "h" = h} {
+    return w * h;
+}
+fn main {
+    let scale = fn [a, b] factor { return [a * factor, b * factor]; };
+    let [sx, sy] = scale([1, 2], 3);
+    return [length([3, 4]), area({"w" = 2, "h" = 5}), sx, sy];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![Value::Int(25), Value::Int(10), Value::Int(3), Value::Int(6)].to_value(),
+    );
+}
+
+#[test]
+fn vm_test_destructure_if_let() {
+    let code = r#"
+fn pick x y {
+    if let [a, b] = x {
+        return a + b;
+    } elif let {"sum" = sum} = y {
+        return sum;
+    }
+    return -1;
+}
+fn main {
+    return [pick([1, 2], null), pick(null, {"sum" = 9}), pick(null, null)];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![Value::Int(3), Value::Int(9), Value::Int(-1)].to_value(),
+    );
+}
+
+#[test]
+fn vm_test_destructure_parse_errors() {
+    let mut tokenizer = Tokenizer::new(r#"fn main {let [a b] = [1, 2];}"#.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => assert!(matches!(
+            e.kind,
+            error::ErrorKind::DestructureRbracket(Token::Identifier(_))
+        )),
+    }
+}
@@ -28,13 +28,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -62,13 +62,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -95,13 +95,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -129,13 +129,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 // * Dotted
@@ -164,13 +164,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -198,13 +198,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -231,13 +231,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
 
 #[test]
@@ -265,11 +265,11 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(111.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(111)));
 }
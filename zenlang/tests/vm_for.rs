@@ -0,0 +1,126 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.stack.is_empty());
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+#[test]
+fn vm_test_for_array() {
+    expect_to_return(
+        "fn main {let sum = 0; for x in [1, 2, 3, 4] { let sum = sum + x; } return sum;}".into(),
+        Value::Number(10.0),
+    );
+}
+
+#[test]
+fn vm_test_for_array_index() {
+    expect_to_return(
+        "fn main {let sum = 0; for i, x in [5, 6, 7] { let sum = sum + i * x; } return sum;}"
+            .into(),
+        Value::Number(20.0),
+    );
+}
+
+#[test]
+fn vm_test_for_dictionary() {
+    expect_to_return(
+        "fn main {let s = \"\"; for k, v in {\"a\" = 1, \"b\" = 2} { let s = s + k + stringify(v); } return s;}"
+            .into(),
+        Value::String("a1b2".into()),
+    );
+}
+
+#[test]
+fn vm_test_for_dictionary_keys() {
+    expect_to_return(
+        "fn main {let s = \"\"; for k in {\"x\" = 1, \"y\" = 2} { let s = s + k; } return s;}"
+            .into(),
+        Value::String("xy".into()),
+    );
+}
+
+#[test]
+fn vm_test_for_string() {
+    expect_to_return(
+        "fn main {let s = \"\"; for c in \"hello\" { let s = c + s; } return s;}".into(),
+        Value::String("olleh".into()),
+    );
+}
+
+#[test]
+fn vm_test_for_break_continue() {
+    expect_to_return(
+        "fn main {let sum = 0; for x in [1, 2, 3, 4, 5, 6] { if x == 2 { continue; } if x == 5 { break; } let sum = sum + x; } return sum;}"
+            .into(),
+        Value::Number(8.0),
+    );
+}
+
+#[test]
+fn vm_test_for_nested() {
+    expect_to_return(
+        "fn main {let n = 0; for a in [1, 2, 3] { for b in [1, 2] { let n = n + a * b; } } return n;}"
+            .into(),
+        Value::Number(18.0),
+    );
+}
+
+#[test]
+fn vm_test_for_return() {
+    expect_to_return(
+        "fn find arr x { for i, v in arr { if v == x { return i; } } return null; } fn main {return find([4, 5, 6], 6);}"
+            .into(),
+        Value::Number(2.0),
+    );
+}
+
+#[test]
+fn vm_test_for_not_iterable() {
+    let mut tokenizer = Tokenizer::new("fn main {for x in 5 { } return null;}".into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    let error = vm.error.unwrap();
+    assert_eq!(error.kind, RuntimeErrorKind::TypeMismatch);
+    assert_eq!(error.message, "for: cannot iterate over int");
+}
@@ -0,0 +1,113 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+fn expect_parse_error(code: &str) -> error::ErrorKind {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => panic!("expected a parse error"),
+        Err(e) => return e.kind,
+    }
+}
+
+#[test]
+fn vm_test_format_string() {
+    expect_to_return(
+        r#"fn main {let count = 3; let ratio = 0.5; return f"total: {count} items ({ratio * 100}%)";}"#
+            .into(),
+        Value::String("total: 3 items (50%)".into()),
+    );
+}
+
+#[test]
+fn vm_test_format_string_values() {
+    expect_to_return(
+        r#"fn main {let d = {"a" = "x"}; return f"{d["a"]}{[1, 2]} {null} {1 > 2}";}"#.into(),
+        Value::String("x[1, 2] null false".into()),
+    );
+    expect_to_return(
+        r#"fn main {return f"{ {"k" = 1}.k + 1 }";}"#.into(),
+        Value::String("2".into()),
+    );
+}
+
+#[test]
+fn vm_test_format_string_escapes() {
+    expect_to_return(
+        r#"fn main {let x = 1; return f"\{x\} = {x}\n";}"#.into(),
+        Value::String("{x} = 1\n".into()),
+    );
+    expect_to_return(
+        r#"fn main {return f"say \"{1 + 1}\"";}"#.into(),
+        Value::String("say \"2\"".into()),
+    );
+}
+
+#[test]
+fn vm_test_format_string_side_effects() {
+    expect_to_return(
+        r#"fn main {let d = {"n" = 0}; let f = fn { let d["n"] = d["n"] + 1; return d["n"]; }; return f"{f()}{f()}" + f"{f()}";}"#
+            .into(),
+        Value::String("123".into()),
+    );
+}
+
+#[test]
+fn vm_test_format_string_errors() {
+    assert!(matches!(
+        expect_parse_error(r#"fn main {return f"{1 2}";}"#),
+        error::ErrorKind::FormatStringEnd(Token::Int(2))
+    ));
+    assert!(matches!(
+        expect_parse_error(r#"fn main {return f"{}";}"#),
+        error::ErrorKind::ExprUnexpectedPrimary(Token::EOF)
+    ));
+}
+
+#[test]
+fn vm_test_plain_strings_keep_braces() {
+    expect_to_return(
+        r#"fn main {let s = "open { brace"; return s + " and } {x}";}"#.into(),
+        Value::String("open { brace and } {x}".into()),
+    );
+}
+
+#[test]
+fn vm_test_format_string_unterminated() {
+    assert!(matches!(
+        expect_parse_error(r#"fn main {return f"a { b";}"#),
+        error::ErrorKind::FormatStringUnterminated()
+    ));
+}
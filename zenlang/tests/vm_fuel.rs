@@ -0,0 +1,149 @@
+use zenlang::compiler::*;
+use zenlang::opcode::Opcode;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn load(code: String) -> VM {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    let _ = vm.load_module(module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    return vm;
+}
+
+#[test]
+fn vm_fuel_infinite_loop() {
+    let mut vm = load(
+        r#"
+fn main {
+    while true {
+    }
+    return null;
+}
+        "#
+        .into(),
+    );
+
+    vm.set_fuel(1000);
+    assert!(matches!(vm.run_until_halt(), StopReason::OutOfFuel));
+    assert_eq!(vm.fuel, Some(0));
+    assert_eq!(vm.error, None);
+
+    // still out of fuel without topping up
+    assert!(matches!(vm.step(), Some(StopReason::OutOfFuel)));
+}
+
+#[test]
+fn vm_fuel_resume() {
+    let mut vm = load(
+        r#"
+fn main {
+    let i = 0;
+    while i < 100 {
+        let i = i + 1;
+    }
+    return i;
+}
+        "#
+        .into(),
+    );
+
+    vm.set_fuel(10);
+    let mut refuels = 0;
+    loop {
+        match vm.run_until_halt() {
+            StopReason::OutOfFuel => {
+                refuels += 1;
+                vm.add_fuel(10);
+            }
+            StopReason::Halt => break,
+            reason => panic!("unexpected stop reason: {:?}", reason),
+        }
+    }
+
+    assert!(refuels > 10);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Number(100.0), &vm));
+}
+
+#[test]
+fn vm_fuel_unlimited() {
+    let mut vm = load(
+        r#"
+fn main {
+    return 42;
+}
+        "#
+        .into(),
+    );
+
+    vm.set_fuel(0);
+    assert!(matches!(vm.run_until_halt(), StopReason::OutOfFuel));
+    vm.remove_fuel_limit();
+    assert!(matches!(vm.run_until_halt(), StopReason::Halt));
+    assert!(vm.ret.equal(&Value::Number(42.0), &vm));
+}
+
+#[test]
+fn vm_fuel_cost() {
+    let code = r#"
+fn f {
+    return 1;
+}
+fn main {
+    return f() + f();
+}
+    "#;
+
+    let mut vm = load(code.into());
+    vm.set_fuel(1000);
+    vm.run_until_halt();
+    let default_used = 1000 - vm.fuel.unwrap();
+
+    let mut vm = load(code.into());
+    vm.set_fuel_cost(|opcode| match opcode {
+        Opcode::Call() => 100,
+        _ => 1,
+    });
+    vm.set_fuel(1000);
+    assert!(matches!(vm.run_until_halt(), StopReason::Halt));
+    assert_eq!(1000 - vm.fuel.unwrap(), default_used + 2 * 99);
+}
+
+#[test]
+fn vm_fuel_constructor() {
+    let mut tokenizer = Tokenizer::new(
+        r#"
+fn #[ctor] init {
+    while true {
+    }
+}
+        "#
+        .into(),
+    );
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let module = compiler.get_module();
+    module.name = "main".into();
+    let mut vm = VM::new();
+    vm.set_fuel(1000);
+
+    assert_eq!(
+        vm.load_module(module),
+        Err("in constructor of module main: out of fuel".into())
+    );
+}
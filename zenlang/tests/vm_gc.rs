@@ -0,0 +1,148 @@
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn load(code: &str) -> VM {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    return vm;
+}
+
+#[test]
+fn vm_gc_self_reference() {
+    let mut vm = load(
+        r#"
+fn make {
+    let d = {"a" = 1};
+    let d["self"] = d;
+    return null;
+}
+fn main {
+    make();
+    return null;
+}
+        "#,
+    );
+    vm.gc_threshold = 0;
+
+    assert!(matches!(vm.run_until_halt(), StopReason::Halt));
+    assert_eq!(vm.error, None);
+    assert_eq!(vm.collect(), 1);
+    assert_eq!(vm.collect(), 0);
+
+    let stats = vm.gc_stats();
+    assert_eq!(stats.collections, 2);
+    assert_eq!(stats.collected, 1);
+    assert_eq!(stats.live, 0);
+}
+
+#[test]
+fn vm_gc_lambda_cycle() {
+    let mut vm = load(
+        r#"
+fn make {
+    let d = {"a" = 1};
+    let f = fn {
+        let d["calls"] = 1;
+        return 1;
+    };
+    let d["f"] = f;
+    return f();
+}
+fn main {
+    return make();
+}
+        "#,
+    );
+    vm.gc_threshold = 0;
+
+    assert!(matches!(vm.run_until_halt(), StopReason::Halt));
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Number(1.0), &vm));
+
+    // the dictionary, the lambda scope and the cell of the captured variable
+    assert_eq!(vm.collect(), 3);
+}
+
+#[test]
+fn vm_gc_reachable_cycle() {
+    let mut vm = load(
+        r#"
+fn main {
+    let d = {"a" = 1};
+    let d["self"] = d;
+    let unused = [[1], [2]];
+    return d;
+}
+        "#,
+    );
+    vm.gc_threshold = 0;
+
+    assert!(matches!(vm.run_until_halt(), StopReason::Halt));
+    assert_eq!(vm.collect(), 0);
+
+    let a: f64 = get_field(&vm.ret, "a").unwrap();
+    assert_eq!(a, 1.0);
+    let inner: Value = get_field(&vm.ret, "self").unwrap();
+    assert!(matches!(inner, Value::Object(_)));
+}
+
+#[test]
+fn vm_gc_host_reference() {
+    let mut vm = VM::new();
+    let array = vec![Value::Null()].to_value();
+    if let Value::Object(obj) = &array {
+        if let Object::Array(elements) = &mut *obj.borrow_mut() {
+            elements[0] = array.clone();
+        }
+    }
+    vm.track_value(&array);
+
+    // still referenced by the host
+    assert_eq!(vm.collect(), 0);
+    drop(array);
+    assert_eq!(vm.collect(), 1);
+}
+
+#[test]
+fn vm_gc_automatic() {
+    let mut vm = load(
+        r#"
+fn main {
+    let i = 0;
+    while i < 1000 {
+        let d = {"i" = i};
+        let d["self"] = d;
+        let i = i + 1;
+    }
+    return i;
+}
+        "#,
+    );
+    vm.gc_threshold = 100;
+
+    assert!(matches!(vm.run_until_halt(), StopReason::Halt));
+    assert_eq!(vm.error, None);
+
+    let stats = vm.gc_stats();
+    println!("{:?}", stats);
+    assert!(stats.collections >= 9);
+    assert!(stats.collected >= 900);
+    assert!(stats.tracked <= 200);
+}
@@ -22,13 +22,13 @@ fn vm_test_ret_if_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(0.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(0)));
 }
 
 #[test]
@@ -49,13 +49,13 @@ fn vm_test_ret_if_true() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(1.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(1)));
 }
 
 #[test]
@@ -77,14 +77,14 @@ fn vm_test_ret_if_else() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     println!("{}", vm.ret);
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -118,13 +118,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(5.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(5)));
 }
 
 #[test]
@@ -146,13 +146,13 @@ fn vm_test_ret_if_chained_false() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(0.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(0)));
 }
 
 #[test]
@@ -174,13 +174,13 @@ fn vm_test_ret_if_chained_true() {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(1.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(1)));
 }
 
 #[test]
@@ -217,13 +217,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(5.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(5)));
 }
 
 #[test]
@@ -260,13 +260,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -307,11 +307,11 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(3.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(3)));
 }
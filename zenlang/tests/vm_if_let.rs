@@ -32,13 +32,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(0.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(0)));
 }
 
 #[test]
@@ -69,13 +69,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(1.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(1)));
 }
 
 #[test]
@@ -110,13 +110,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -151,11 +151,11 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(1.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(1)));
 }
@@ -0,0 +1,129 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_error(code: &str, kind: RuntimeErrorKind, message: &str) {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    let error = vm.error.unwrap();
+    assert_eq!(error.kind, kind);
+    assert_eq!(error.message, message);
+}
+
+fn expect_type(code: &str, value: Value) {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert_eq!(vm.ret.get_type(), value.get_type());
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+#[test]
+fn vm_test_int_arithmetic() {
+    expect_type("fn main {return 2 + 3 * 4;}", Value::Int(14));
+    expect_type("fn main {return 7 ~/ 2;}", Value::Int(3));
+    expect_type("fn main {return -7 ~/ 2;}", Value::Int(-4));
+    expect_type("fn main {return -7 % 3;}", Value::Int(2));
+    expect_type("fn main {return 2 ** 62;}", Value::Int(1 << 62));
+    expect_type("fn main {return -(5);}", Value::Int(-5));
+}
+
+#[test]
+fn vm_test_int_promotion() {
+    expect_type("fn main {return 1 + 0.5;}", Value::Number(1.5));
+    expect_type("fn main {return 0.5 * 4;}", Value::Number(2.0));
+    expect_type("fn main {return 7 / 2;}", Value::Number(3.5));
+    expect_type("fn main {return 2 ** -1;}", Value::Number(0.5));
+    expect_type("fn main {return 1 == 1.0;}", Value::Boolean(true));
+    expect_type("fn main {return 2 < 2.5;}", Value::Boolean(true));
+}
+
+#[test]
+fn vm_test_int_overflow() {
+    expect_error(
+        "fn main {return 9223372036854775807 + 1;}",
+        RuntimeErrorKind::IntegerOverflow,
+        "integer overflow",
+    );
+    expect_error(
+        "fn main {return 2 ** 63;}",
+        RuntimeErrorKind::IntegerOverflow,
+        "integer overflow",
+    );
+    expect_error(
+        "fn main {let x = 0x8000000000000000; return -x;}",
+        RuntimeErrorKind::IntegerOverflow,
+        "neg failed: integer overflow",
+    );
+    expect_error(
+        "fn main {return 1 << 64;}",
+        RuntimeErrorKind::IntegerOverflow,
+        "shift amount 64 out of range",
+    );
+}
+
+#[test]
+fn vm_test_int_bits() {
+    // above 2^53 every bit is kept
+    expect_type(
+        "fn main {return 0xffffffffffffffff & 0x8000000000000001;}",
+        Value::Int(i64::MIN + 1),
+    );
+    expect_type("fn main {return 1 << 63 >> 63;}", Value::Int(-1));
+    expect_type("fn main {return ~0;}", Value::Int(-1));
+    expect_type("fn main {return 12 ^ 10;}", Value::Int(6));
+}
+
+#[test]
+fn vm_test_int_index() {
+    expect_type("fn main {let a = [1, 2, 3]; return a[2];}", Value::Int(3));
+    expect_type("fn main {let a = [1, 2, 3]; return a[-1];}", Value::Null());
+    expect_type("fn main {let a = [1, 2, 3]; return a[1.5];}", Value::Null());
+    expect_type("fn main {let a = [1, 2, 3]; return a[1.0];}", Value::Int(2));
+    expect_error(
+        "fn main {let a = [1]; let a[-1] = 2; return a;}",
+        RuntimeErrorKind::IndexOutOfBounds,
+        "aiafs failed: invalid index -1",
+    );
+}
+
+#[test]
+fn vm_test_int_loops() {
+    expect_type(
+        "fn main {let n = 0; for i in 0..4 { let n = n + i; } return n;}",
+        Value::Int(6),
+    );
+    expect_type(
+        "fn main {let n = 0; for i in 0..2 step 0.5 { let n = n + i; } return n;}",
+        Value::Number(3.0),
+    );
+}
@@ -1,4 +1,6 @@
 extern crate alloc;
+use alloc::rc::Rc;
+use core::cell::RefCell;
 
 use zenlang::compiler::*;
 use zenlang::module::Module;
@@ -31,7 +33,7 @@ fn expect_to_return(code: String, value: Value) {
     vm.run_until_halt();
 
     println!("vm.ret: {:?}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(vm.ret.equal(&value, &vm));
 }
 
@@ -130,3 +132,100 @@ fn main {
         Value::Number(42.0),
     );
 }
+
+#[test]
+fn vm_lambda_test_counter() {
+    expect_to_return(
+        r#"
+fn make_counter {
+    let count = 0;
+    return fn {
+        let count = count + 1;
+        return count;
+    };
+}
+fn main {
+    let a = make_counter();
+    let b = make_counter();
+    a();
+    a();
+    b();
+    return [a(), b()];
+}
+        "#
+        .into(),
+        Value::Object(Rc::new(RefCell::new(Object::Array(vec![
+            Value::Number(3.0),
+            Value::Number(2.0),
+        ])))),
+    );
+}
+
+#[test]
+fn vm_lambda_test_shared() {
+    expect_to_return(
+        r#"
+fn main {
+    let total = 0;
+    let add = fn n {
+        let total = total + n;
+        return null;
+    };
+    let get = fn {
+        return total;
+    };
+    add(40);
+    let total = total + 1;
+    add(1);
+    return [total, get()];
+}
+        "#
+        .into(),
+        Value::Object(Rc::new(RefCell::new(Object::Array(vec![
+            Value::Number(42.0),
+            Value::Number(42.0),
+        ])))),
+    );
+}
+
+#[test]
+fn vm_lambda_test_nested() {
+    expect_to_return(
+        r#"
+fn main {
+    let x = 1;
+    let outer = fn {
+        let inner = fn {
+            let x = x * 10;
+            return null;
+        };
+        inner();
+        inner();
+        return null;
+    };
+    outer();
+    return x;
+}
+        "#
+        .into(),
+        Value::Number(100.0),
+    );
+}
+
+#[test]
+fn vm_lambda_test_locals() {
+    expect_to_return(
+        r#"
+fn main {
+    let f = fn a {
+        let y = a * 2;
+        return y;
+    };
+    let y = 1;
+    return f(20) + y + f(0);
+}
+        "#
+        .into(),
+        Value::Number(41.0),
+    );
+}
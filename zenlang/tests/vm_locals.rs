@@ -0,0 +1,87 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+#[test]
+fn vm_test_locals_loop() {
+    expect_to_return(
+        "fn main {let i = 0; let sum = 0; while i < 100 { let sum = sum + i; let i = i + 1; } return sum;}"
+            .into(),
+        Value::Number(4950.0),
+    );
+}
+
+#[test]
+fn vm_test_locals_recursion() {
+    expect_to_return(
+        "fn fib n { if n < 2 { return n; } let a = fib(n - 1); let b = fib(n - 2); return a + b; } fn main {return fib(15);}"
+            .into(),
+        Value::Number(610.0),
+    );
+}
+
+#[test]
+fn vm_test_locals_arguments() {
+    expect_to_return(
+        "fn sub a b { return a - b; } fn main {return sub(10, 3);}".into(),
+        Value::Number(7.0),
+    );
+}
+
+#[test]
+fn vm_test_locals_assigned_later_in_loop() {
+    expect_to_return(
+        "fn main {let i = 0; let last = 0; while i < 3 { if i > 0 { let last = x; } let x = i * 10; let i = i + 1; } return last;}"
+            .into(),
+        Value::Number(10.0),
+    );
+}
+
+#[test]
+fn vm_test_locals_shared_with_lambda() {
+    expect_to_return(
+        "fn main {let x = 1; let f = fn { return x; }; let x = 5; return f();}".into(),
+        Value::Number(5.0),
+    );
+}
+
+#[test]
+fn vm_test_locals_read_before_assigned() {
+    expect_to_return(
+        "fn helper { return 1; } fn main { let a = helper(); let helper = 5; return [a, helper]; }"
+            .into(),
+        vec![Value::Int(1), Value::Int(5)].to_value(),
+    );
+}
@@ -0,0 +1,121 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+#[test]
+fn vm_test_logical_and() {
+    expect_to_return(
+        "fn main {return true && false;}".into(),
+        Value::Boolean(false),
+    );
+    expect_to_return(
+        "fn main {return 1 < 2 && 3 > 2;}".into(),
+        Value::Boolean(true),
+    );
+    expect_to_return("fn main {return true && 5;}".into(), Value::Number(5.0));
+}
+
+#[test]
+fn vm_test_logical_or() {
+    expect_to_return(
+        "fn main {return false || true;}".into(),
+        Value::Boolean(true),
+    );
+    expect_to_return("fn main {return 0 || 7;}".into(), Value::Number(7.0));
+    expect_to_return("fn main {return 3 || 7;}".into(), Value::Number(3.0));
+}
+
+#[test]
+fn vm_test_logical_short_circuit() {
+    expect_to_return(
+        "fn main {let d = {\"n\" = 0}; let f = fn { let d[\"n\"] = d[\"n\"] + 1; return true; }; let a = false && f(); let b = true || f(); let c = true && f(); return d[\"n\"];}"
+            .into(),
+        Value::Number(1.0),
+    );
+}
+
+#[test]
+fn vm_test_logical_precedence() {
+    expect_to_return(
+        "fn main {let x = 5; return x == 1 || x == 5 && x > 2;}".into(),
+        Value::Boolean(true),
+    );
+    expect_to_return(
+        "fn main {let x = 5; if x > 1 && x < 10 { return 1; } return 0;}".into(),
+        Value::Number(1.0),
+    );
+}
+
+#[test]
+fn vm_test_unary() {
+    expect_to_return("fn main {return !true;}".into(), Value::Boolean(false));
+    expect_to_return("fn main {return !0;}".into(), Value::Boolean(true));
+    expect_to_return(
+        "fn main {let x = 3; return -x * 2;}".into(),
+        Value::Number(-6.0),
+    );
+    expect_to_return("fn main {return 1 - -1;}".into(), Value::Number(2.0));
+    expect_to_return("fn main {return ~5;}".into(), Value::Number(-6.0));
+    expect_to_return(
+        "fn main {return !(1 > 2) && !false;}".into(),
+        Value::Boolean(true),
+    );
+}
+
+#[test]
+fn vm_test_bitwise() {
+    expect_to_return("fn main {return 6 & 3;}".into(), Value::Number(2.0));
+    expect_to_return("fn main {return 6 | 3;}".into(), Value::Number(7.0));
+    expect_to_return("fn main {return 6 ^ 3;}".into(), Value::Number(5.0));
+    expect_to_return("fn main {return 1 | 6 ^ 3 & 1;}".into(), Value::Number(7.0));
+}
+
+#[test]
+fn vm_test_unary_type_mismatch() {
+    let mut tokenizer = Tokenizer::new("fn main {return -\"a\";}".into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    assert_eq!(vm.error.unwrap().kind, RuntimeErrorKind::TypeMismatch);
+}
@@ -0,0 +1,212 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+fn compile_warnings(code: &str) -> Vec<String> {
+    let mut tokenizer = Tokenizer::new(code.into());
+    tokenizer.filename = "main.zen".into();
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    return compiler.warnings.clone();
+}
+
+#[test]
+fn vm_test_match_literals() {
+    let code = r#"
+fn describe x {
+    return match x {
+        0 => "zero",
+        -1 => "minus one",
+        1.5 => "one and a half",
+        "a" => "letter a",
+        true => "true",
+        null => "null",
+        _ => "other",
+    };
+}
+fn main {
+    return [describe(0), describe(-1), describe(1.5), describe("a"), describe(true), describe(null), describe(7)];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![
+            "zero",
+            "minus one",
+            "one and a half",
+            "letter a",
+            "true",
+            "null",
+            "other",
+        ]
+        .to_value(),
+    );
+}
+
+#[test]
+fn vm_test_match_bindings_and_guards() {
+    expect_to_return(
+        r#"fn main {let n = 7; return match n { x if x > 10 => "big", x if x > 5 => f"medium {x}", _ => "small" };}"#
+            .into(),
+        Value::String("medium 7".into()),
+    );
+}
+
+#[test]
+fn vm_test_match_arrays() {
+    let code = r#"
+fn shape x {
+    return match x {
+        [] => "empty",
+        [a] => f"one {a}",
+        [a, [b, c]] => f"nested {a} {b} {c}",
+        [a, b] => f"two {a} {b}",
+        [first, ..] => f"many from {first}",
+        _ => "not an array",
+    };
+}
+fn main {
+    return [shape([]), shape([1]), shape([1, [2, 3]]), shape([1, 2]), shape([4, 5, 6]), shape("x")];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![
+            "empty",
+            "one 1",
+            "nested 1 2 3",
+            "two 1 2",
+            "many from 4",
+            "not an array",
+        ]
+        .to_value(),
+    );
+}
+
+#[test]
+fn vm_test_match_dictionaries() {
+    let code = r#"
+fn handle result {
+    return match result {
+        Result {"_err" = null, "_ok" = value} => f"ok {value}",
+        Result {"_err" = error} => f"error {error}",
+        {"x" = x, "y" = 0} => f"on the x axis at {x}",
+        {"x" = x} => f"x is {x}",
+        _ => "unknown",
+    };
+}
+fn main {
+    return [handle(ok(1)), handle(err("bad")), handle({"x" = 2, "y" = 0}), handle({"x" = 3}), handle({"_typename" = "Other", "_err" = 1})];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![
+            "ok 1",
+            "error bad",
+            "on the x axis at 2",
+            "x is 3",
+            "unknown",
+        ]
+        .to_value(),
+    );
+}
+
+#[test]
+fn vm_test_match_blocks() {
+    let code = r#"
+fn main {
+    let log = "";
+    for x in [1, 2, 3] {
+        match x {
+            1 => { let log = log + "one "; }
+            2 => { continue; }
+            _ => { let log = log + "many"; }
+        }
+    }
+    let nothing = match 5 { 5 => { let log = log + "!"; } };
+    return [log, nothing];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![Value::String("one many!".into()), Value::Null()].to_value(),
+    );
+}
+
+#[test]
+fn vm_test_match_nested() {
+    expect_to_return(
+        r#"fn main {return match [1, 2] { [a, b] => match b { 2 => a + b, _ => 0 }, _ => -1 };}"#
+            .into(),
+        Value::Int(3),
+    );
+}
+
+#[test]
+fn vm_test_match_non_exhaustive() {
+    expect_to_return(
+        r#"fn main {return match 3 { 1 => "one", x if x > 5 => "big" };}"#.into(),
+        Value::Null(),
+    );
+
+    let warnings = compile_warnings(
+        r#"fn main {
+    return match 3 { 1 => "one", x if x > 5 => "big" };
+}"#,
+    );
+    assert_eq!(
+        warnings,
+        vec!["main.zen:2:12: match is not exhaustive, it gives null when no arm matches"]
+    );
+    let warnings = compile_warnings(r#"fn main {return match 3 { 1 => "one", x => "other" };}"#);
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn vm_test_match_parse_errors() {
+    let mut tokenizer = Tokenizer::new(r#"fn main {return match 1 { 1 "one" };}"#.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => assert!(matches!(
+            e.kind,
+            error::ErrorKind::MatchArrow(Token::String(_))
+        )),
+    }
+}
@@ -0,0 +1,191 @@
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn run(code: String, limit: usize) -> (VM, StopReason) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    vm.set_memory_limit(limit);
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    let reason = vm.run_until_halt();
+    return (vm, reason);
+}
+
+fn expect_out_of_memory(code: &str, limit: usize) -> RuntimeError {
+    let (_, reason) = run(code.into(), limit);
+    let error = match reason {
+        StopReason::Error(error) => error,
+        reason => panic!("expected a runtime error, got {:?}", reason),
+    };
+    println!("error: {:?}", error);
+    assert_eq!(error.kind, RuntimeErrorKind::OutOfMemory);
+    return error;
+}
+
+#[test]
+fn vm_memory_string_repeat() {
+    let error = expect_out_of_memory(
+        r#"
+fn main {
+    return "a" * 1000000000;
+}
+        "#,
+        1024 * 1024,
+    );
+    assert!(error.message.starts_with("memory limit exceeded"));
+}
+
+#[test]
+fn vm_memory_string_concat() {
+    expect_out_of_memory(
+        r#"
+fn main {
+    let s = "0123456789";
+    while true {
+        let s = s + s;
+    }
+    return s;
+}
+        "#,
+        64 * 1024,
+    );
+}
+
+#[test]
+fn vm_memory_array_push() {
+    expect_out_of_memory(
+        r#"
+fn main {
+    let array = [];
+    while true {
+        let array = array_push(array, [1, 2, 3]);
+    }
+    return array;
+}
+        "#,
+        64 * 1024,
+    );
+}
+
+#[test]
+fn vm_memory_dictionary() {
+    expect_out_of_memory(
+        r#"
+fn main {
+    let dict = {};
+    let key = "k";
+    while true {
+        let key = key + "k";
+        let dict[key] = null;
+    }
+    return dict;
+}
+        "#,
+        64 * 1024,
+    );
+}
+
+#[test]
+fn vm_memory_freed_values() {
+    let (vm, reason) = run(
+        r#"
+fn main {
+    let i = 0;
+    while i < 1000 {
+        let s = "x" * 1000;
+        let a = [s, s, s];
+        let i = i + 1;
+    }
+    return i;
+}
+        "#
+        .into(),
+        64 * 1024,
+    );
+
+    assert!(matches!(reason, StopReason::Halt));
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Number(1000.0), &vm));
+    assert!(vm.memory_used <= 64 * 1024);
+}
+
+#[test]
+fn vm_memory_catch() {
+    let (vm, _) = run(
+        r#"
+fn main {
+    try {
+        let s = "a" * 1000000000;
+    } catch e {
+        return e.kind;
+    }
+    return null;
+}
+        "#
+        .into(),
+        1024 * 1024,
+    );
+
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::String("OutOfMemory".into()), &vm));
+}
+
+#[test]
+fn vm_memory_heap_size() {
+    let mut vm = VM::new();
+    let array = vec![Value::String("abcd".into())].to_value();
+    vm.stack.push(array.clone());
+    vm.stack.push(array.clone());
+
+    // shared objects are counted once
+    let size = VM::deep_size(&array);
+    assert_eq!(size, array_size(1) + string_size(4));
+    assert_eq!(vm.heap_size(), size);
+
+    // cycles terminate
+    if let Value::Object(obj) = &array {
+        if let Object::Array(elements) = &mut *obj.borrow_mut() {
+            elements.push(array.clone());
+        }
+    }
+    assert_eq!(vm.heap_size(), array_size(2) + string_size(4));
+}
+
+#[test]
+fn vm_memory_near_limit() {
+    // temporary strings keep the estimate going over the limit while the live heap stays under it
+    let (vm, reason) = run(
+        r#"
+fn main {
+    let keep = "a" * 50000;
+    let i = 0;
+    while i < 20000 {
+        let temp = "b" * 100;
+        let i = i + 1;
+    }
+    return i;
+}
+        "#
+        .into(),
+        64 * 1024,
+    );
+    assert!(matches!(reason, StopReason::Halt), "{:?}", reason);
+    assert!(vm.memory_measured <= 64 * 1024);
+}
@@ -0,0 +1,226 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::module::Module;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn compile_module(name: &str, code: &str) -> Result<Module, String> {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    compiler.compile()?;
+
+    let module = compiler.get_module();
+    module.name = name.into();
+    return Ok(module.clone());
+}
+
+const NET: &str = r#"
+fn init {
+    return "net";
+}
+pub fn setup {
+    return init();
+}
+pub fn connect host {
+    return f"connected to {host}";
+}
+fn secret {
+    return 42;
+}
+"#;
+
+fn expect_to_return(code: &str, value: Value) {
+    let net = compile_module("net", NET).unwrap();
+    let main = compile_module("main", code).unwrap();
+
+    let mut vm = VM::new();
+    assert_eq!(vm.load_module(&net), Ok(()));
+    assert_eq!(vm.load_module(&main), Ok(()));
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+#[test]
+fn vm_test_modules_qualified_call() {
+    expect_to_return(
+        r#"mod net; fn main {return net.connect("example.org");}"#,
+        Value::String("connected to example.org".into()),
+    );
+}
+
+#[test]
+fn vm_test_modules_names_dont_collide() {
+    expect_to_return(
+        r#"mod net; fn init {return "main";} fn main {return [init(), net.setup()];}"#,
+        vec![Value::String("main".into()), Value::String("net".into())].to_value(),
+    );
+}
+
+#[test]
+fn vm_test_modules_use() {
+    expect_to_return(
+        r#"mod net; use net::{connect, setup}; fn main {return f"{connect("a")} {setup()}";}"#,
+        Value::String("connected to a net".into()),
+    );
+    expect_to_return(
+        r#"mod net; use net::connect; fn main {let f = fn {return connect("b");}; return f();}"#,
+        Value::String("connected to b".into()),
+    );
+}
+
+#[test]
+fn vm_test_modules_use_shadowed_by_variable() {
+    expect_to_return(
+        r#"mod net; use net::{connect}; fn main {let connect = 5; return connect;}"#,
+        Value::Int(5),
+    );
+}
+
+#[test]
+fn vm_test_modules_not_exported() {
+    let net = compile_module("net", NET).unwrap();
+    let main = compile_module("main", r#"mod net; fn main {return net.secret();}"#).unwrap();
+
+    let mut vm = VM::new();
+    assert_eq!(vm.load_module(&net), Ok(()));
+    assert_eq!(
+        vm.load_module(&main),
+        Err("unresolved net::secret (in module main): net doesn't export secret".into())
+    );
+
+    let main = compile_module("main2", r#"mod net; use net::{secret}; fn main {}"#).unwrap();
+    assert_eq!(
+        vm.load_module(&main),
+        Err("unresolved net::secret (in module main2): net doesn't export secret".into())
+    );
+    let main = compile_module(
+        "main3",
+        r#"mod net; use net::{secret}; fn main {secret();}"#,
+    )
+    .unwrap();
+    assert!(vm.load_module(&main).is_err());
+}
+
+#[test]
+fn vm_test_modules_use_undeclared_module() {
+    assert_eq!(
+        compile_module("main", r#"use net::{connect}; fn main {}"#).err(),
+        Some("use of undeclared module net, declare it with `mod net;` first".into())
+    );
+}
+
+#[test]
+fn vm_test_modules_pub_recorded() {
+    let net = compile_module("net", NET).unwrap();
+    let public: Vec<(String, bool)> = net
+        .functions
+        .iter()
+        .map(|func| (func.name.clone(), func.public))
+        .collect();
+    assert_eq!(
+        public,
+        vec![
+            ("init".into(), false),
+            ("setup".into(), true),
+            ("connect".into(), true),
+            ("secret".into(), false),
+        ]
+    );
+}
+
+#[test]
+fn vm_test_modules_parse_errors() {
+    let mut tokenizer = Tokenizer::new(r#"pub let x;"#.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => assert!(matches!(
+            e.kind,
+            error::ErrorKind::PubExpectedFn(Token::Let)
+        )),
+    }
+
+    let mut tokenizer = Tokenizer::new(r#"mod net; use net::{connect setup};"#.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => assert!(matches!(
+            e.kind,
+            error::ErrorKind::UseName(Token::Identifier(_))
+        )),
+    }
+}
+
+#[test]
+fn vm_test_modules_unqualified_private_rejected() {
+    let net = compile_module("net", NET).unwrap();
+    let main = compile_module("main", r#"fn main {return secret();}"#).unwrap();
+
+    let mut vm = VM::new();
+    assert_eq!(vm.load_module(&net), Ok(()));
+    assert_eq!(
+        vm.load_module(&main),
+        Err("unresolved secret (in module main): net doesn't export secret".into())
+    );
+
+    let stdlib = zenlang::stdlib::compile_stdlib_module();
+    let main = compile_module(
+        "main",
+        r#"mod stdlib; fn main {println(_vmcall_ret_unsafe_2(5, 17));}"#,
+    )
+    .unwrap();
+    let mut vm = VM::new();
+    assert_eq!(vm.load_module(&stdlib), Ok(()));
+    assert_eq!(
+        vm.load_module(&main),
+        Err("unresolved _vmcall_ret_unsafe_2 (in module main): stdlib doesn't export _vmcall_ret_unsafe_2".into())
+    );
+}
+
+#[test]
+fn vm_test_modules_ambiguous_name() {
+    let a = compile_module("a", r#"pub fn init {return "a";}"#).unwrap();
+    let b = compile_module("b", r#"pub fn init {return "b";}"#).unwrap();
+
+    let mut vm = VM::new();
+    assert_eq!(vm.load_module(&a), Ok(()));
+    assert_eq!(vm.load_module(&b), Ok(()));
+    let main = compile_module("main", r#"mod a; mod b; fn main {return init();}"#).unwrap();
+    assert_eq!(
+        vm.load_module(&main),
+        Err("ambiguous init (in module main): exported by both a and b, use a.init".into())
+    );
+
+    let main = compile_module("main", r#"mod a; mod b; fn main {return b.init();}"#).unwrap();
+    assert_eq!(vm.load_module(&main), Ok(()));
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::String("b".into()), &vm));
+}
+
+#[test]
+fn vm_test_modules_failed_link_not_loaded() {
+    let net = compile_module("net", NET).unwrap();
+    let main = compile_module("main", r#"mod net; fn main {return net.secret();}"#).unwrap();
+
+    let mut vm = VM::new();
+    assert_eq!(vm.load_module(&net), Ok(()));
+    assert!(vm.load_module(&main).is_err());
+    assert!(vm.modules.iter().all(|module| module.name != "main"));
+    // a retry is checked again instead of passing as already loaded
+    assert!(vm.load_module(&main).is_err());
+}
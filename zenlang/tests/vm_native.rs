@@ -0,0 +1,251 @@
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn run(code: String, register: fn(&mut VM)) -> (VM, StopReason) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    register(&mut vm);
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    let reason = vm.run_until_halt();
+    return (vm, reason);
+}
+
+fn compile_module(name: &str, code: &str) -> zenlang::module::Module {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let module = compiler.get_module();
+    module.name = name.into();
+    return module.clone();
+}
+
+fn register_add(vm: &mut VM) {
+    vm.register_native("add", 2, |vm, args| match (&args[0], &args[1]) {
+        (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+        _ => Err(vm.runtime_error(
+            RuntimeErrorKind::TypeMismatch,
+            "add: expected integers".into(),
+        )),
+    });
+}
+
+#[test]
+fn vm_native_call() {
+    let (vm, reason) = run(
+        r#"
+fn main {
+    return add(1, add(2, 3));
+}
+        "#
+        .into(),
+        register_add,
+    );
+
+    assert!(matches!(reason, StopReason::Halt));
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Number(6.0), &vm));
+}
+
+#[test]
+fn vm_native_first_class() {
+    let (vm, reason) = run(
+        r#"
+fn apply f a b {
+    return f(a, b);
+}
+fn main {
+    let f = add;
+    return apply(f, 4, 5);
+}
+        "#
+        .into(),
+        register_add,
+    );
+
+    assert!(matches!(reason, StopReason::Halt));
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Number(9.0), &vm));
+}
+
+#[test]
+fn vm_native_error() {
+    let (vm, reason) = run(
+        r#"
+fn main {
+    return add(1, "2");
+}
+        "#
+        .into(),
+        register_add,
+    );
+
+    let error = match reason {
+        StopReason::Error(error) => error,
+        _ => panic!("expected a runtime error"),
+    };
+    assert_eq!(error.kind, RuntimeErrorKind::TypeMismatch);
+    assert_eq!(error.message, "add: expected integers");
+    assert_eq!(vm.get_function_name_from_pc(&error.pc), Some("main".into()));
+}
+
+#[test]
+fn vm_native_catch_error() {
+    let (vm, _) = run(
+        r#"
+fn main {
+    try {
+        add(1, null);
+    } catch e {
+        return e.kind;
+    }
+    return "not caught";
+}
+        "#
+        .into(),
+        register_add,
+    );
+
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::String("TypeMismatch".into()), &vm));
+}
+
+#[test]
+fn vm_native_argument_count() {
+    let (_, reason) = run(
+        r#"
+fn main {
+    return add(1);
+}
+        "#
+        .into(),
+        register_add,
+    );
+
+    let error = match reason {
+        StopReason::Error(error) => error,
+        _ => panic!("expected a runtime error"),
+    };
+    assert_eq!(error.kind, RuntimeErrorKind::ArgumentCount);
+}
+
+#[test]
+fn vm_native_state() {
+    let (vm, _) = run(
+        r#"
+fn main {
+    counter();
+    counter();
+    return counter();
+}
+        "#
+        .into(),
+        |vm| {
+            let count = std::rc::Rc::new(std::cell::Cell::new(0.0));
+            vm.register_native("counter", 0, move |_, _| {
+                count.set(count.get() + 1.0);
+                Ok(Value::Number(count.get()))
+            });
+        },
+    );
+
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Number(3.0), &vm));
+}
+
+#[test]
+fn vm_native_register_fn() {
+    let (vm, reason) = run(
+        r#"
+fn main {
+    return join(repeat("ab", 3), "-");
+}
+        "#
+        .into(),
+        |vm| {
+            vm.register_fn("repeat", |(s, count): (String, usize)| vec![s; count]);
+            vm.register_fn("join", |(parts, sep): (Vec<String>, String)| {
+                parts.join(&sep)
+            });
+        },
+    );
+
+    assert!(matches!(reason, StopReason::Halt));
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::String("ab-ab-ab".into()), &vm));
+}
+
+#[test]
+fn vm_native_register_fn_type_mismatch() {
+    let (_, reason) = run(
+        r#"
+fn main {
+    return repeat("ab", "3");
+}
+        "#
+        .into(),
+        |vm| {
+            vm.register_fn("repeat", |(s, count): (String, usize)| vec![s; count]);
+        },
+    );
+
+    let error = match reason {
+        StopReason::Error(error) => error,
+        _ => panic!("expected a runtime error"),
+    };
+    assert_eq!(error.kind, RuntimeErrorKind::TypeMismatch);
+    assert_eq!(
+        error.message,
+        "repeat: argument 1: expected number, found string"
+    );
+}
+
+#[test]
+fn vm_native_shadowed_by_module_function() {
+    let mut vm = VM::new();
+    register_add(&mut vm);
+    let module = compile_module(
+        "main",
+        "fn add a b { return a - b; } fn main { return add(1, 2); }",
+    );
+    assert_eq!(
+        vm.load_module(&module),
+        Err("add (in module main) shadows a native function".into())
+    );
+}
+
+#[test]
+fn vm_native_links_over_private_function() {
+    // `other` is loaded before the native is registered, its private add doesn't hide it
+    let mut vm = VM::new();
+    let other = compile_module("other", "fn add a b { return a - b; }");
+    assert_eq!(vm.load_module(&other), Ok(()));
+    register_add(&mut vm);
+
+    let main = compile_module("main", "fn main { return add(1, 2); }");
+    assert_eq!(vm.load_module(&main), Ok(()));
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&Value::Int(3), &vm));
+}
@@ -28,13 +28,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
 
     if let Value::String(str) = vm.ret {
         assert_eq!(str, "abc");
@@ -67,13 +67,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
 
     if let Value::String(str) = vm.ret {
         assert_eq!(str, "aaa");
@@ -0,0 +1,163 @@
+extern crate alloc;
+
+use zenlang::compiler::*;
+use zenlang::interop::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+fn expect_error(code: &str, kind: RuntimeErrorKind, message: &str) {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    let error = vm.error.unwrap();
+    assert_eq!(error.kind, kind);
+    assert_eq!(error.message, message);
+}
+
+#[test]
+fn vm_test_params_defaults() {
+    let code = r#"
+fn greet name greeting = "hi" punct = "!" {
+    return f"{greeting} {name}{punct}";
+}
+fn main {
+    return [greet("bob"), greet("bob", "hello"), greet("bob", "hey", "?")];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![
+            Value::String("hi bob!".into()),
+            Value::String("hello bob!".into()),
+            Value::String("hey bob?".into()),
+        ]
+        .to_value(),
+    );
+}
+
+#[test]
+fn vm_test_params_default_uses_earlier_param() {
+    expect_to_return(
+        r#"fn area w h = w {return w * h;} fn main {return area(3) + area(2, 5);}"#.into(),
+        Value::Int(19),
+    );
+}
+
+#[test]
+fn vm_test_params_null_gets_default() {
+    expect_to_return(
+        r#"fn f a = 1 {return a;} fn main {return f(null);}"#.into(),
+        Value::Int(1),
+    );
+}
+
+#[test]
+fn vm_test_params_rest() {
+    let code = r#"
+fn log level ...messages {
+    return f"{level}: {messages}";
+}
+fn main {
+    return [log("info"), log("warn", 1, 2, 3)];
+}
+"#;
+    expect_to_return(
+        code.into(),
+        vec![
+            Value::String("info: []".into()),
+            Value::String("warn: [1, 2, 3]".into()),
+        ]
+        .to_value(),
+    );
+}
+
+#[test]
+fn vm_test_params_defaults_and_rest() {
+    expect_to_return(
+        r#"fn f a b = 10 ...rest {return [a, b, rest];} fn main {let [a, b, rest] = f(1); let [c, d, more] = f(1, 2, 3); return f"{a} {b} {rest} {c} {d} {more}";}"#
+            .into(),
+        Value::String("1 10 [] 1 2 [3]".into()),
+    );
+}
+
+#[test]
+fn vm_test_params_argument_count() {
+    expect_error(
+        r#"fn f a b = 1 {return a;} fn main {return f();}"#,
+        RuntimeErrorKind::ArgumentCount,
+        "call: expected 1 to 2 arguments, but provided 0 (trying to call a function at (0;0))",
+    );
+    expect_error(
+        r#"fn f a b = 1 {return a;} fn main {return f(1, 2, 3);}"#,
+        RuntimeErrorKind::ArgumentCount,
+        "call: expected 1 to 2 arguments, but provided 3 (trying to call a function at (0;0))",
+    );
+    expect_error(
+        r#"fn f a ...rest {return a;} fn main {return f();}"#,
+        RuntimeErrorKind::ArgumentCount,
+        "call: expected at least 1 arguments, but provided 0 (trying to call a function at (0;0))",
+    );
+}
+
+#[test]
+fn vm_test_params_parse_errors() {
+    let mut tokenizer = Tokenizer::new(r#"fn f a = 1 b {}"#.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => assert!(matches!(
+            e.kind,
+            error::ErrorKind::FunctionDefaultMissing(_)
+        )),
+    }
+
+    let mut tokenizer = Tokenizer::new(r#"fn f ...rest a {}"#.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    match parser.parse() {
+        Ok(_) => assert!(false),
+        Err(e) => assert!(matches!(
+            e.kind,
+            error::ErrorKind::FunctionRestLast(Token::Identifier(_))
+        )),
+    }
+}
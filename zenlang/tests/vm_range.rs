@@ -0,0 +1,178 @@
+extern crate alloc;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn expect_to_return(code: String, value: Value) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    println!("{:?}", compiler.warnings);
+
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(&module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    vm.run_until_halt();
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.stack.is_empty());
+    assert!(vm.ret.equal(&value, &vm));
+}
+
+fn expect_error(code: &str, kind: RuntimeErrorKind) {
+    let mut tokenizer = Tokenizer::new(code.into());
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+
+    let mut vm = VM::new();
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+    vm.run_until_halt();
+
+    assert_eq!(vm.error.unwrap().kind, kind);
+}
+
+#[test]
+fn vm_test_range_for() {
+    expect_to_return(
+        "fn main {let sum = 0; for i in 0..5 { let sum = sum + i; } return sum;}".into(),
+        Value::Number(10.0),
+    );
+}
+
+#[test]
+fn vm_test_range_for_inclusive() {
+    expect_to_return(
+        "fn main {let sum = 0; for i in 1..=5 { let sum = sum + i; } return sum;}".into(),
+        Value::Number(15.0),
+    );
+}
+
+#[test]
+fn vm_test_range_for_step() {
+    expect_to_return(
+        "fn main {let sum = 0; for i in 0..10 step 3 { let sum = sum + i; } return sum;}".into(),
+        Value::Number(18.0),
+    );
+    expect_to_return(
+        "fn main {let s = \"\"; for i in 3..=1 step 0 - 1 { let s = s + stringify(i); } return s;}"
+            .into(),
+        Value::String("321".into()),
+    );
+}
+
+#[test]
+fn vm_test_range_for_index() {
+    expect_to_return(
+        "fn main {let sum = 0; for i, x in 10..13 { let sum = sum + i * x; } return sum;}".into(),
+        Value::Number(35.0),
+    );
+}
+
+#[test]
+fn vm_test_range_empty() {
+    expect_to_return(
+        "fn main {let n = 0; for i in 5..5 { let n = n + 1; } for i in 5..0 { let n = n + 1; } return n;}"
+            .into(),
+        Value::Number(0.0),
+    );
+}
+
+#[test]
+fn vm_test_range_value() {
+    expect_to_return(
+        "fn main {let step = 2; let r = 0..=step * 3 step step; return stringify(r);}".into(),
+        Value::String("0..=6 step 2".into()),
+    );
+}
+
+#[test]
+fn vm_test_range_slice_array() {
+    expect_to_return(
+        "fn main {let a = [1, 2, 3, 4, 5]; return a[1..3];}".into(),
+        Value::Object(Rc::new(RefCell::new(Object::Array(vec![
+            Value::Number(2.0),
+            Value::Number(3.0),
+        ])))),
+    );
+    expect_to_return(
+        "fn main {let a = [1, 2, 3, 4, 5]; return a[3..100];}".into(),
+        Value::Object(Rc::new(RefCell::new(Object::Array(vec![
+            Value::Number(4.0),
+            Value::Number(5.0),
+        ])))),
+    );
+}
+
+#[test]
+fn vm_test_range_slice_string() {
+    expect_to_return(
+        "fn main {return \"hello world\"[6..=10];}".into(),
+        Value::String("world".into()),
+    );
+    expect_to_return(
+        "fn main {return \"hello\"[4..=0 step 0 - 1];}".into(),
+        Value::String("olleh".into()),
+    );
+}
+
+#[test]
+fn vm_test_range_slice_clamped() {
+    // slices keep the part of the range inside the bounds, indexing past the end gives null
+    expect_to_return(
+        "fn main {return [1, 2, 3, 4, 5][2..10];}".into(),
+        Value::Object(Rc::new(RefCell::new(Object::Array(vec![
+            Value::Int(3),
+            Value::Int(4),
+            Value::Int(5),
+        ])))),
+    );
+    expect_to_return(
+        "fn main {return \"hello\"[0 - 3..2];}".into(),
+        Value::String("he".into()),
+    );
+    expect_to_return(
+        "fn main {return [1, 2, 3][10..20];}".into(),
+        Value::Object(Rc::new(RefCell::new(Object::Array(vec![])))),
+    );
+    expect_to_return("fn main {return [1, 2, 3][10];}".into(), Value::Null());
+}
+
+#[test]
+fn vm_test_range_errors() {
+    expect_error(
+        "fn main {return 0..10 step 0;}",
+        RuntimeErrorKind::InvalidRange,
+    );
+    expect_error("fn main {return 0..\"a\";}", RuntimeErrorKind::TypeMismatch);
+}
+
+#[test]
+fn vm_test_range_array_count() {
+    expect_to_return(
+        "fn main {return array_count([1, 2, 1, 3, 1], 1);}".into(),
+        Value::Number(3.0),
+    );
+}
@@ -34,14 +34,14 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(1.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(1)));
 }
 
 #[test]
@@ -74,14 +74,14 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(1.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(1)));
 }
 
 #[test]
@@ -114,12 +114,12 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(69.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(69)));
 }
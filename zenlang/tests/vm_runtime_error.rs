@@ -0,0 +1,183 @@
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::vm::*;
+
+fn expect_error(code: String, kind: RuntimeErrorKind) -> (VM, RuntimeError) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    let error = match vm.run_until_halt() {
+        StopReason::Error(error) => error,
+        _ => panic!("expected a runtime error"),
+    };
+
+    println!("error: {:?}", error);
+    assert_eq!(error.kind, kind);
+    assert_eq!(vm.error, Some(error.clone()));
+
+    // the vm stays stopped at the error
+    assert!(matches!(vm.step(), Some(StopReason::Error(_))));
+
+    return (vm, error);
+}
+
+#[test]
+fn vm_runtime_error_division_by_zero() {
+    let (vm, error) = expect_error(
+        r#"
+fn div a b {
+    return a / b;
+}
+fn main {
+    return div(1, 0);
+}
+        "#
+        .into(),
+        RuntimeErrorKind::DivisionByZero,
+    );
+
+    assert_eq!(error.message, "division by 0");
+    assert_eq!(error.to_string(), "division by 0");
+    assert_eq!(vm.get_function_name_from_pc(&error.pc), Some("div".into()));
+    assert_eq!(error.call_stack.len(), 1);
+    assert_eq!(
+        vm.get_function_name_from_pc(&error.call_stack[0]),
+        Some("main".into())
+    );
+}
+
+#[test]
+fn vm_runtime_error_index_out_of_bounds() {
+    let (vm, error) = expect_error(
+        r#"
+fn main {
+    let arr = [1, 2];
+    let arr[5] = 3;
+    return arr;
+}
+        "#
+        .into(),
+        RuntimeErrorKind::IndexOutOfBounds,
+    );
+
+    assert_eq!(error.message, "aiafs failed: index outside bounds");
+    assert!(error.call_stack.is_empty());
+    assert_eq!(
+        vm.modules[error.pc.module]
+            .get_source_location(error.pc.inst)
+            .unwrap()
+            .line,
+        4
+    );
+}
+
+#[test]
+fn vm_runtime_error_not_callable() {
+    expect_error(
+        r#"
+fn main {
+    let x = 1;
+    return x();
+}
+        "#
+        .into(),
+        RuntimeErrorKind::NotCallable,
+    );
+}
+
+#[test]
+fn vm_runtime_error_argument_count() {
+    let (vm, error) = expect_error(
+        r#"
+fn add a b {
+    return a + b;
+}
+fn main {
+    return add(1);
+}
+        "#
+        .into(),
+        RuntimeErrorKind::ArgumentCount,
+    );
+
+    // the error points at the call, not into the callee
+    assert!(matches!(
+        vm.modules[error.pc.module].get_opcode(error.pc.inst as u32),
+        zenlang::opcode::Opcode::Call()
+    ));
+}
+
+#[test]
+fn vm_runtime_error_type_mismatch() {
+    expect_error(
+        r#"
+fn main {
+    return 1 + "a";
+}
+        "#
+        .into(),
+        RuntimeErrorKind::TypeMismatch,
+    );
+}
+
+#[test]
+fn vm_runtime_error_backtrace() {
+    let (vm, error) = expect_error(
+        r#"
+fn inner x {
+    return x / 0;
+}
+fn outer {
+    let y = 1;
+    return inner(y);
+}
+fn main {
+    return outer();
+}
+        "#
+        .into(),
+        RuntimeErrorKind::DivisionByZero,
+    );
+
+    let frames = vm.error_backtrace(&error);
+    let names: Vec<&str> = frames
+        .iter()
+        .map(|frame| frame.function_name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, vec!["inner", "outer", "main"]);
+
+    let lines: Vec<usize> = frames
+        .iter()
+        .map(|frame| frame.location.unwrap().line)
+        .collect();
+    assert_eq!(lines, vec![3, 7, 10]);
+
+    assert_eq!(frames[0].pc, error.pc);
+    assert_eq!(frames[0].module_name, Some(String::new()));
+    assert_eq!(
+        frames[0].offset,
+        error.pc.inst - vm.modules[1].functions[0].addr
+    );
+    assert_eq!(
+        frames[0].to_string(),
+        format!("::inner+{} at {}", frames[0].offset, error.pc)
+    );
+
+    // the vm state still points at the error
+    assert_eq!(vm.backtrace(), frames);
+}
@@ -27,7 +27,7 @@ fn expect_to_return(code: String, value: Value) {
     vm.run_until_halt();
 
     println!("vm.ret: {:?}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(vm.ret.equal(&value, &vm));
 }
 
@@ -30,7 +30,7 @@ fn expect_to_return(code: String, value: Value) {
     vm.run_until_halt();
 
     println!("vm.ret: {:?}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(vm.ret.equal(&value, &vm));
 }
 
@@ -55,7 +55,7 @@ fn expect_to_return_obj(code: String, object: Object) {
     vm.run_until_halt();
 
     println!("vm.ret: {}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(
         vm.ret
             .equal(&Value::Object(Rc::new(RefCell::new(object))), &vm)
@@ -392,3 +392,79 @@ fn main {
         Value::Boolean(true),
     );
 }
+
+#[test]
+fn vm_test_stdlib_json() {
+    expect_to_return(
+        r#"
+fn main {
+    let text = json_stringify({"a" = [1, true, null], "b" = "str"}, 0);
+    let result = json_parse(text);
+    return [text, result._ok.a[0], result._ok.b];
+}
+    "#
+        .into(),
+        vec![
+            Value::String(r#"{"a":[1,true,null],"b":"str"}"#.into()),
+            Value::Number(1.0),
+            Value::String("str".into()),
+        ]
+        .to_value(),
+    );
+}
+
+#[test]
+fn vm_test_stdlib_json_invalid() {
+    expect_to_return(
+        r#"
+fn main {
+    return json_parse("[1, 2");
+}
+    "#
+        .into(),
+        interop_err(Value::String("expected , or ] at 1:6".into())),
+    );
+}
+
+#[test]
+fn vm_test_stdlib_json_indent_limit() {
+    expect_to_return(
+        r#"
+fn main {
+    return json_stringify([1], 10);
+}
+    "#
+        .into(),
+        Value::String(format!("[\n{}1\n]", " ".repeat(10))),
+    );
+
+    let mut tokenizer = Tokenizer::new(
+        r#"
+fn main {
+    return json_stringify([[1]], 9223372036854775807);
+}
+    "#
+        .into(),
+    );
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(compiler.get_module());
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    let error = match vm.run_until_halt() {
+        StopReason::Error(error) => error,
+        _ => panic!("expected a runtime error"),
+    };
+    assert_eq!(error.kind, RuntimeErrorKind::TypeMismatch);
+    assert_eq!(
+        error.message,
+        "json_stringify: indent has to be a number from 0 to 10, found 9223372036854775807"
+    );
+}
@@ -29,13 +29,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     if let Value::String(s) = vm.ret {
         assert_eq!(s, "A");
     } else {
@@ -68,13 +68,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
     println!("{}", vm.ret);
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     if let Value::String(s) = vm.ret {
         assert_eq!(s, "A");
     } else {
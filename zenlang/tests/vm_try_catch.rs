@@ -0,0 +1,234 @@
+use zenlang::compiler::*;
+use zenlang::parser::*;
+use zenlang::tokenizer::*;
+use zenlang::value::*;
+use zenlang::vm::*;
+
+fn run(code: String) -> (VM, StopReason) {
+    let mut tokenizer = Tokenizer::new(code);
+    let mut parser = Parser::new(&mut tokenizer);
+    let mut compiler = Compiler::new(&mut parser);
+    if let Err(e) = compiler.compile() {
+        assert_eq!(e, "");
+    }
+    let mut vm = VM::new();
+    let module = compiler.get_module();
+    println!("{:?}", module.opcodes);
+
+    let _ = vm.load_module(&zenlang::stdlib::compile_stdlib_module());
+    let _ = vm.load_module(module);
+
+    if let Err(e) = vm.set_entry_function("main") {
+        assert_eq!(e, "");
+    }
+
+    let reason = vm.run_until_halt();
+    return (vm, reason);
+}
+
+fn expect_to_return(code: String, value: Value) {
+    let (vm, _) = run(code);
+
+    println!("vm.ret: {:?}", vm.ret);
+    assert_eq!(vm.error, None);
+    assert!(vm.ret.equal(&value, &vm));
+    assert!(vm.handlers.is_empty());
+}
+
+#[test]
+fn vm_try_catch_runtime_error() {
+    expect_to_return(
+        r#"
+fn main {
+    try {
+        let x = 1 / 0;
+        return "unreachable";
+    } catch e {
+        return e.kind + ": " + e.message;
+    }
+}
+        "#
+        .into(),
+        Value::String("DivisionByZero: division by 0".into()),
+    );
+}
+
+#[test]
+fn vm_try_catch_no_error() {
+    expect_to_return(
+        r#"
+fn main {
+    let x = 1;
+    try {
+        let x = 2;
+    } catch e {
+        let x = 3;
+    }
+    return x;
+}
+        "#
+        .into(),
+        Value::Number(2.0),
+    );
+}
+
+#[test]
+fn vm_try_catch_throw_across_calls() {
+    expect_to_return(
+        r#"
+fn fail n {
+    if n == 0 {
+        throw {"code" = 42};
+    }
+    return fail(n - 1);
+}
+fn main {
+    try {
+        fail(5);
+    } catch e {
+        return e.code;
+    }
+    return null;
+}
+        "#
+        .into(),
+        Value::Number(42.0),
+    );
+}
+
+#[test]
+fn vm_try_catch_unwinds_stack_and_args() {
+    expect_to_return(
+        r#"
+fn fail {
+    throw "x";
+}
+fn add a b {
+    return a + b;
+}
+fn main {
+    let result = 0;
+    try {
+        let result = add(1, add(2, fail()));
+    } catch {
+        let result = add(10, 20);
+    }
+    return result;
+}
+        "#
+        .into(),
+        Value::Number(30.0),
+    );
+}
+
+#[test]
+fn vm_try_catch_nested_rethrow() {
+    expect_to_return(
+        r#"
+fn main {
+    try {
+        try {
+            throw "inner";
+        } catch e {
+            throw e + " outer";
+        }
+    } catch e {
+        return e;
+    }
+}
+        "#
+        .into(),
+        Value::String("inner outer".into()),
+    );
+}
+
+#[test]
+fn vm_try_catch_uncaught_throw() {
+    let (vm, reason) = run(r#"
+fn main {
+    throw "oops";
+}
+        "#
+    .into());
+
+    match reason {
+        StopReason::Error(error) => {
+            assert_eq!(error.kind, RuntimeErrorKind::Thrown);
+            assert_eq!(error.message, "uncaught exception: oops");
+        }
+        _ => assert!(false),
+    }
+    assert!(vm.error.is_some());
+}
+
+#[test]
+fn vm_try_catch_return_drops_handler() {
+    let (_, reason) = run(r#"
+fn safe {
+    try {
+        return 1;
+    } catch {
+        return 2;
+    }
+}
+fn main {
+    safe();
+    return 1 / 0;
+}
+        "#
+    .into());
+
+    match reason {
+        StopReason::Error(error) => assert_eq!(error.kind, RuntimeErrorKind::DivisionByZero),
+        _ => assert!(false),
+    }
+}
+
+#[test]
+fn vm_try_catch_break_drops_handler() {
+    let (_, reason) = run(r#"
+fn main {
+    while true {
+        try {
+            break;
+        } catch {
+            return "caught in loop";
+        }
+    }
+    return 1 / 0;
+}
+        "#
+    .into());
+
+    match reason {
+        StopReason::Error(error) => assert_eq!(error.kind, RuntimeErrorKind::DivisionByZero),
+        _ => assert!(false),
+    }
+}
+
+#[test]
+fn vm_try_catch_in_loop() {
+    expect_to_return(
+        r#"
+fn main {
+    let i = 0;
+    let caught = 0;
+    while i < 5 {
+        let i = i + 1;
+        try {
+            if i == 2 {
+                continue;
+            }
+            let x = [1][i];
+            let y = x + 1;
+        } catch {
+            let caught = caught + 1;
+        }
+    }
+    return caught;
+}
+        "#
+        .into(),
+        Value::Number(4.0),
+    );
+}
@@ -35,13 +35,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -70,13 +70,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -105,13 +105,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -140,13 +140,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -172,11 +172,11 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
+    assert_eq!(vm.error, None);
     assert!(matches!(vm.ret, Value::Number(8.0)));
 }
@@ -34,13 +34,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(2.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(2)));
 }
 
 #[test]
@@ -72,13 +72,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(0.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(0)));
 }
 
 #[test]
@@ -114,13 +114,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(3.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(3)));
 }
 
 #[test]
@@ -157,13 +157,13 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(3.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(3)));
 }
 
 #[test]
@@ -201,11 +201,11 @@ fn main {
     }
 
     loop {
-        if !vm.step() {
+        if vm.step().is_some() {
             break;
         }
     }
 
-    assert_eq!(vm.error, "");
-    assert!(matches!(vm.ret, Value::Number(0.0)));
+    assert_eq!(vm.error, None);
+    assert!(matches!(vm.ret, Value::Int(0)));
 }